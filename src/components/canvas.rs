@@ -0,0 +1,216 @@
+use crate::components::cluster::Rect;
+use crate::components::edge::{ArrowStyle, DEFAULT_ARROW_SIZE, DEFAULT_HIT_WIDTH};
+use crate::components::graph::Point;
+use crate::components::node::NodeShape;
+use crate::components::theme::ThemeColors;
+use crate::components::viewport::ViewBox;
+use crate::components::{Edge as GraphEdge, Node as GraphNode};
+use dioxus::prelude::*;
+use petgraph::graph::{EdgeIndex, NodeIndex};
+
+/// What [`Canvas`] draws for a placed node. The fields are the [`crate::components::Node`] props of the same name.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct CanvasNode {
+    pub node_idx: NodeIndex,
+    pub position: Point,
+    pub label: String,
+    pub description: Option<String>,
+    pub shape: NodeShape,
+    pub colors: ThemeColors,
+    pub is_selected: bool,
+    pub is_highlighted: bool,
+    pub is_entry: bool,
+    pub is_anchored: bool,
+    pub is_dimmed: bool,
+    pub is_editing: bool,
+}
+
+/// What [`Canvas`] draws for an edge whose ends are both placed. The fields are the [`crate::components::Edge`]
+/// props of the same name.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct CanvasEdge {
+    pub edge_idx: EdgeIndex,
+    pub source_pos: Point,
+    pub target_pos: Point,
+    pub label: String,
+    pub source_shape: NodeShape,
+    pub target_shape: NodeShape,
+    pub colors: ThemeColors,
+    pub waypoints: Vec<Point>,
+    pub dashed: bool,
+    pub animate: bool,
+    pub is_selected: bool,
+    pub is_highlighted: bool,
+    pub is_dimmed: bool,
+}
+
+/// A labelled group drawn behind its nodes.
+#[derive(PartialEq, Clone, Debug)]
+pub struct CanvasCluster {
+    pub id: String,
+    pub rect: Rect,
+    pub label: String,
+    pub color: &'static str,
+}
+
+/// The drawing shared by [`crate::components::Graph`] and [`crate::components::Flow`]: clusters, edges, the line of
+/// an edge being dragged out, then nodes on top. The parent works out what each of them looks like and handles the
+/// pointer events passed up from them.
+#[component]
+pub fn Canvas(
+    nodes: Vec<CanvasNode>,
+    edges: Vec<CanvasEdge>,
+    #[props(default)] clusters: Vec<CanvasCluster>,
+    /// Start and pointer end of the line shown while dragging out a new edge.
+    band: Option<(Point, Point)>,
+    #[props(default = 25.0)] node_radius: f64,
+    #[props(default)] colors: ThemeColors,
+    #[props(default = DEFAULT_HIT_WIDTH)] hit_width: f64,
+    #[props(default = DEFAULT_ARROW_SIZE)] arrow_size: f64,
+    #[props(default)] arrow_style: ArrowStyle,
+    cursor: &'static str,
+    /// The part of the drawing shown; `None` shows it unmoved.
+    view_box: Option<ViewBox>,
+    on_mouse_move: EventHandler<MouseEvent>,
+    on_mouse_up: EventHandler<MouseEvent>,
+    on_mouse_leave: EventHandler<MouseEvent>,
+    on_click: EventHandler<MouseEvent>,
+    on_wheel: Option<EventHandler<WheelEvent>>,
+    on_double_click: Option<EventHandler<MouseEvent>>,
+    on_node_drag_start: EventHandler<NodeIndex>,
+    on_node_click: EventHandler<NodeIndex>,
+    /// Called instead of `on_node_click` when shift is held, if set.
+    on_node_shift_click: Option<EventHandler<NodeIndex>>,
+    /// Called instead of `on_node_click` when alt is held, if set.
+    on_node_alt_click: Option<EventHandler<NodeIndex>>,
+    on_node_double_click: Option<EventHandler<NodeIndex>>,
+    on_node_rename: Option<EventHandler<(NodeIndex, String)>>,
+    on_node_cancel_rename: Option<EventHandler<NodeIndex>>,
+    on_node_context_menu: Option<EventHandler<(NodeIndex, Point)>>,
+    on_edge_click: EventHandler<EdgeIndex>,
+    on_edge_context_menu: Option<EventHandler<(EdgeIndex, Point)>>,
+    on_edge_hover: Option<EventHandler<Option<EdgeIndex>>>,
+) -> Element {
+    // Only nodes and edges have a context menu, when the parent gives them one
+    let has_context_menu = on_node_context_menu.is_some() || on_edge_context_menu.is_some();
+
+    rsx! {
+        svg {
+            class: "absolute top-0 left-0 w-full h-full",
+            cursor,
+            view_box: view_box.map(|view| view.to_string()),
+            onmousemove: move |event| on_mouse_move.call(event),
+            onmouseup: move |event| on_mouse_up.call(event),
+            onmouseleave: move |event| on_mouse_leave.call(event),
+            onclick: move |event| on_click.call(event),
+            onwheel: move |event| {
+                if let Some(handler) = on_wheel {
+                    handler.call(event);
+                }
+            },
+            ondoubleclick: move |event| {
+                if let Some(handler) = on_double_click {
+                    handler.call(event);
+                }
+            },
+            oncontextmenu: move |event: MouseEvent| {
+                if has_context_menu {
+                    event.prevent_default();
+                }
+            },
+            // Clusters, behind everything else
+            for cluster in clusters {
+                g { key: "cluster-{cluster.id}", pointer_events: "none",
+                    rect {
+                        x: "{cluster.rect.x}",
+                        y: "{cluster.rect.y}",
+                        width: "{cluster.rect.width}",
+                        height: "{cluster.rect.height}",
+                        rx: "8",
+                        fill: cluster.color,
+                        fill_opacity: "0.15",
+                        stroke: cluster.color,
+                        stroke_width: "1",
+                    }
+                    text {
+                        x: "{cluster.rect.x + 6.0}",
+                        y: "{cluster.rect.y + 14.0}",
+                        font_size: "11",
+                        fill: colors.node_text,
+                        "{cluster.label}"
+                    }
+                }
+            }
+
+            // Edges with their arrows, between the current node positions
+            for edge in edges {
+                GraphEdge {
+                    key: "{edge.edge_idx.index()}",
+                    source_pos: edge.source_pos,
+                    target_pos: edge.target_pos,
+                    weight: 1, // Default weight for visualization
+                    edge_idx: edge.edge_idx,
+                    on_click: on_edge_click,
+                    on_hover: on_edge_hover,
+                    is_selected: edge.is_selected,
+                    is_highlighted: edge.is_highlighted,
+                    edge_label: Some(edge.label),
+                    node_radius,
+                    hit_width,
+                    arrow_size,
+                    arrow_style,
+                    waypoints: edge.waypoints,
+                    source_shape: edge.source_shape,
+                    target_shape: edge.target_shape,
+                    colors: edge.colors,
+                    dashed: edge.dashed,
+                    animate: edge.animate,
+                    is_dimmed: edge.is_dimmed,
+                    on_context_menu: on_edge_context_menu,
+                }
+            }
+
+            // Rubber-band line while dragging out a new edge
+            if let Some((start, pointer)) = band {
+                line {
+                    x1: "{start.x}",
+                    y1: "{start.y}",
+                    x2: "{pointer.x}",
+                    y2: "{pointer.y}",
+                    stroke: colors.edge,
+                    stroke_width: "2",
+                    stroke_dasharray: "4 4",
+                    pointer_events: "none",
+                }
+            }
+
+            // Nodes
+            for node in nodes {
+                GraphNode {
+                    key: "{node.node_idx.index()}",
+                    position: node.position,
+                    label: node.label,
+                    description: node.description,
+                    radius: node_radius,
+                    shape: node.shape,
+                    colors: node.colors,
+                    node_idx: node.node_idx,
+                    on_drag_start: on_node_drag_start,
+                    on_click: on_node_click,
+                    on_double_click: on_node_double_click,
+                    on_shift_click: on_node_shift_click,
+                    on_alt_click: on_node_alt_click,
+                    is_editing: node.is_editing,
+                    on_rename: on_node_rename,
+                    on_cancel_rename: on_node_cancel_rename,
+                    is_selected: node.is_selected,
+                    is_highlighted: node.is_highlighted,
+                    is_entry: node.is_entry,
+                    is_anchored: node.is_anchored,
+                    is_dimmed: node.is_dimmed,
+                    on_context_menu: on_node_context_menu,
+                }
+            }
+        }
+    }
+}
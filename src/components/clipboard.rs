@@ -0,0 +1,10 @@
+use dioxus::prelude::*;
+
+/// Asks the webview to put `text` on the system clipboard.
+pub fn copy_to_clipboard(text: &str) {
+    let script = format!(
+        "navigator.clipboard.writeText({});",
+        serde_json::to_string(text).unwrap_or_default()
+    );
+    document::eval(&script);
+}
@@ -0,0 +1,49 @@
+use crate::components::graph::Point;
+use petgraph::graph::NodeIndex;
+use std::collections::{BTreeMap, HashMap};
+
+/// Space between the outermost node centers of a cluster and its rectangle, enough to clear the node circles.
+pub const CLUSTER_PADDING: f64 = 40.0;
+
+/// An axis-aligned rectangle in drawing coordinates.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// The padded bounding box of every group's placed nodes, sorted by group id. Groups without placed nodes are left
+/// out.
+pub fn cluster_bounds(
+    positions: &HashMap<NodeIndex, Point>,
+    groups: &HashMap<String, Vec<NodeIndex>>,
+) -> Vec<(String, Rect)> {
+    let sorted: BTreeMap<&String, &Vec<NodeIndex>> = groups.iter().collect();
+    sorted
+        .into_iter()
+        .filter_map(|(group, nodes)| {
+            let placed: Vec<&Point> = nodes
+                .iter()
+                .filter_map(|node_idx| positions.get(node_idx))
+                .collect();
+            if placed.is_empty() {
+                return None;
+            }
+            let min_x = placed.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+            let min_y = placed.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+            let max_x = placed.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
+            let max_y = placed.iter().map(|p| p.y).fold(f64::NEG_INFINITY, f64::max);
+            Some((
+                group.clone(),
+                Rect {
+                    x: min_x - CLUSTER_PADDING,
+                    y: min_y - CLUSTER_PADDING,
+                    width: max_x - min_x + 2.0 * CLUSTER_PADDING,
+                    height: max_y - min_y + 2.0 * CLUSTER_PADDING,
+                },
+            ))
+        })
+        .collect()
+}
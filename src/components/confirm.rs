@@ -0,0 +1,42 @@
+use dioxus::prelude::*;
+
+/// A modal asking to confirm `message` before something that can't be undone. Clicking outside of it, "Cancel" or
+/// Escape calls `on_cancel`.
+#[component]
+pub fn ConfirmDialog(
+    message: String,
+    #[props(default = "Confirm".to_string())] confirm_label: String,
+    on_confirm: EventHandler<()>,
+    on_cancel: EventHandler<()>,
+) -> Element {
+    rsx! {
+        // Dimmed overlay catching clicks outside of the dialog
+        div {
+            class: "fixed inset-0 z-40 bg-black bg-opacity-30",
+            onclick: move |_| on_cancel.call(()),
+        }
+        div {
+            class: "fixed z-50 top-1/3 left-1/2 -translate-x-1/2 p-4 bg-white border border-gray-300 rounded shadow text-sm",
+            tabindex: "0",
+            autofocus: true,
+            onkeydown: move |evt: KeyboardEvent| {
+                if evt.key() == Key::Escape {
+                    on_cancel.call(());
+                }
+            },
+            p { class: "mb-4", "{message}" }
+            div { class: "flex justify-end space-x-2",
+                button {
+                    class: "px-3 py-1 rounded text-sm bg-gray-200",
+                    onclick: move |_| on_cancel.call(()),
+                    "Cancel"
+                }
+                button {
+                    class: "px-3 py-1 rounded text-sm bg-red-500 text-white",
+                    onclick: move |_| on_confirm.call(()),
+                    "{confirm_label}"
+                }
+            }
+        }
+    }
+}
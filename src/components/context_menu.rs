@@ -0,0 +1,122 @@
+use crate::components::graph::Point;
+use dioxus::prelude::*;
+use petgraph::graph::{DiGraph, EdgeIndex, NodeIndex};
+use std::str::FromStr;
+
+/// The element a context menu was opened on.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum MenuTarget {
+    Node(NodeIndex),
+    Edge(EdgeIndex),
+}
+
+/// The item picked from a context menu.
+#[derive(PartialEq, Clone, Debug)]
+pub enum MenuItem {
+    Delete,
+    Rename(String),
+    AddOutgoingEdge,
+    /// Locks a node in place, or unlocks it.
+    ToggleAnchor,
+}
+
+/// A graph change requested from a context menu. "Add Outgoing Edge" only switches the editing mode, so it has no
+/// action here.
+#[derive(PartialEq, Clone, Debug)]
+pub enum MenuAction {
+    Delete(MenuTarget),
+    Rename(MenuTarget, String),
+}
+
+/// Applies a context menu action to the graph. A rename whose label doesn't parse into the weight type is ignored.
+pub fn apply_menu_action<N, E>(graph: &mut DiGraph<N, E>, action: MenuAction)
+where
+    N: FromStr,
+    E: FromStr,
+{
+    match action {
+        MenuAction::Delete(MenuTarget::Node(node_idx)) => {
+            graph.remove_node(node_idx);
+        }
+        MenuAction::Delete(MenuTarget::Edge(edge_idx)) => {
+            graph.remove_edge(edge_idx);
+        }
+        MenuAction::Rename(MenuTarget::Node(node_idx), label) => {
+            if let (Some(weight), Ok(renamed)) = (graph.node_weight_mut(node_idx), label.parse()) {
+                *weight = renamed;
+            }
+        }
+        MenuAction::Rename(MenuTarget::Edge(edge_idx), label) => {
+            if let (Some(weight), Ok(renamed)) = (graph.edge_weight_mut(edge_idx), label.parse()) {
+                *weight = renamed;
+            }
+        }
+    }
+}
+
+/// A small menu shown at `position` (client coordinates). Clicking outside of it calls `on_close`.
+#[component]
+pub fn ContextMenu(
+    target: MenuTarget,
+    position: Point,
+    label: String,
+    /// Whether the node the menu is for is anchored, which turns "Anchor" into "Unanchor".
+    #[props(default)]
+    anchored: bool,
+    on_select: EventHandler<MenuItem>,
+    on_close: EventHandler<()>,
+) -> Element {
+    // Holds the text being typed once "Rename" is picked
+    let mut rename_text = use_signal(|| None::<String>);
+
+    let item_class = "px-4 py-1 text-left hover:bg-gray-100";
+
+    rsx! {
+        // Transparent overlay catching clicks outside of the menu
+        div {
+            class: "fixed inset-0 z-40",
+            onclick: move |_| on_close.call(()),
+            oncontextmenu: move |event: MouseEvent| {
+                event.prevent_default();
+                on_close.call(());
+            },
+        }
+        div {
+            class: "fixed z-50 flex flex-col py-1 bg-white border border-gray-300 rounded shadow text-sm",
+            style: "left: {position.x}px; top: {position.y}px;",
+            {
+                if let Some(text) = rename_text() {
+                    rsx! {
+                        input {
+                            class: "border border-gray-300 rounded px-2 py-1 mx-1",
+                            r#type: "text",
+                            value: "{text}",
+                            autofocus: true,
+                            oninput: move |evt| rename_text.set(Some(evt.value())),
+                            onkeydown: move |evt: KeyboardEvent| {
+                                if evt.key() == Key::Enter {
+                                    if let Some(text) = rename_text() {
+                                        on_select.call(MenuItem::Rename(text));
+                                    }
+                                } else if evt.key() == Key::Escape {
+                                    on_close.call(());
+                                }
+                            },
+                        }
+                    }
+                } else {
+                    rsx! {
+                        button { class: item_class, onclick: move |_| on_select.call(MenuItem::Delete), "Delete" }
+                        button { class: item_class, onclick: move |_| rename_text.set(Some(label.clone())), "Rename" }
+                        if matches!(target, MenuTarget::Node(_)) {
+                            button { class: item_class, onclick: move |_| on_select.call(MenuItem::AddOutgoingEdge), "Add Outgoing Edge" }
+                            button { class: item_class, onclick: move |_| on_select.call(MenuItem::ToggleAnchor),
+                                if anchored { "Unanchor" } else { "Anchor" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
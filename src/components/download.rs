@@ -0,0 +1,19 @@
+use dioxus::prelude::*;
+
+/// Asks the webview to save `content` as a file named `file_name` with the given MIME type.
+pub fn download_file(content: &str, file_name: &str, mime: &str) {
+    let script = format!(
+        r#"
+        const blob = new Blob([{}], {{ type: {} }});
+        const link = document.createElement("a");
+        link.href = URL.createObjectURL(blob);
+        link.download = {};
+        link.click();
+        URL.revokeObjectURL(link.href);
+        "#,
+        serde_json::to_string(content).unwrap_or_default(),
+        serde_json::to_string(mime).unwrap_or_default(),
+        serde_json::to_string(file_name).unwrap_or_default()
+    );
+    document::eval(&script);
+}
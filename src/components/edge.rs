@@ -1,47 +1,286 @@
 use crate::components::graph::Point;
+use crate::components::node::{NodeShape, DIMMED_OPACITY};
+use crate::components::theme::ThemeColors;
 use dioxus::prelude::*;
 
-#[component]
-pub fn Edge(
-    source_pos: Point,
-    target_pos: Point,
-    weight: i32,
-    edge_idx: petgraph::graph::EdgeIndex,
-    on_click: EventHandler<petgraph::graph::EdgeIndex>,
-    is_selected: bool,
-    edge_label: Option<String>,
-) -> Element {
-    // Calculate direction vector for arrow
+/// The `stroke-dasharray` for an edge line: dashed edges use short dashes, others are solid.
+pub fn dash_array(dashed: bool) -> &'static str {
+    if dashed {
+        "6 4"
+    } else {
+        "none"
+    }
+}
+
+/// Attributes of the moving dash drawn over an active edge: its `stroke-dasharray` and the inline style running the
+/// `edge-flow` keyframes from `main.css`, which shift `stroke-dashoffset` by one dash period.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct FlowAnimation {
+    pub dash_array: &'static str,
+    pub style: &'static str,
+}
+
+/// The moving dash for an edge, or `None` when it isn't animated.
+pub fn flow_animation(animate: bool) -> Option<FlowAnimation> {
+    animate.then_some(FlowAnimation {
+        dash_array: "8 6",
+        style: "animation: edge-flow 0.8s linear infinite;",
+    })
+}
+
+/// Where an edge line starts and ends: on the outline of the source and target shapes rather than their centers.
+/// Shapes at the same position have no direction between them, so the line is left at their centers.
+pub fn edge_endpoints(
+    source_pos: &Point,
+    target_pos: &Point,
+    source_shape: NodeShape,
+    target_shape: NodeShape,
+    node_radius: f64,
+) -> (Point, Point) {
     let dx = target_pos.x - source_pos.x;
     let dy = target_pos.y - source_pos.y;
     let length = (dx * dx + dy * dy).sqrt();
+    if length == 0.0 {
+        return (source_pos.clone(), target_pos.clone());
+    }
 
     // Normalize and calculate arrow offset
     let unit_x = dx / length;
     let unit_y = dy / length;
 
     // Start from node border (not center)
-    let start_offset = 25.0; // Radius of node
-    let end_offset = 25.0; // Radius of node
+    let start_offset = source_shape.boundary_distance(node_radius, dx, dy);
+    let end_offset = target_shape.boundary_distance(node_radius, dx, dy);
 
-    let start_x = source_pos.x + unit_x * start_offset;
-    let start_y = source_pos.y + unit_y * start_offset;
-    let end_x = target_pos.x - unit_x * end_offset;
-    let end_y = target_pos.y - unit_y * end_offset;
+    let start = Point {
+        x: source_pos.x + unit_x * start_offset,
+        y: source_pos.y + unit_y * start_offset,
+    };
+    let end = Point {
+        x: target_pos.x - unit_x * end_offset,
+        y: target_pos.y - unit_y * end_offset,
+    };
+    (start, end)
+}
 
-    // Calculate arrowhead points
-    let arrow_size = 10.0;
-    let angle = dy.atan2(dx);
-    let arrow_angle = std::f64::consts::PI / 6.0; // 30 degrees
+/// Bends of a self-loop on the node at `center`: a loop above it, as wide as the node and reaching as far again.
+pub fn self_loop_waypoints(center: &Point, node_radius: f64) -> [Point; 2] {
+    let top = center.y - node_radius * 2.0;
+    [
+        Point {
+            x: center.x - node_radius,
+            y: top,
+        },
+        Point {
+            x: center.x + node_radius,
+            y: top,
+        },
+    ]
+}
 
-    let arrow_x1 = end_x - arrow_size * (angle - arrow_angle).cos();
-    let arrow_y1 = end_y - arrow_size * (angle - arrow_angle).sin();
-    let arrow_x2 = end_x - arrow_size * (angle + arrow_angle).cos();
-    let arrow_y2 = end_y - arrow_size * (angle + arrow_angle).sin();
+/// The points an edge is drawn through: from the source outline through `waypoints` to the target outline. The
+/// line leaves the source towards the first waypoint and enters the target from the last one. Without waypoints this
+/// is the straight line of [`edge_endpoints`], unless both ends are at the same position, as for a self-loop, which
+/// is drawn through [`self_loop_waypoints`].
+pub fn polyline_points(
+    source_pos: &Point,
+    target_pos: &Point,
+    waypoints: &[Point],
+    source_shape: NodeShape,
+    target_shape: NodeShape,
+    node_radius: f64,
+) -> Vec<Point> {
+    if waypoints.is_empty() && source_pos == target_pos {
+        return polyline_points(
+            source_pos,
+            target_pos,
+            &self_loop_waypoints(source_pos, node_radius),
+            source_shape,
+            target_shape,
+            node_radius,
+        );
+    }
+    let (Some(first), Some(last)) = (waypoints.first(), waypoints.last()) else {
+        let (start, end) = edge_endpoints(
+            source_pos,
+            target_pos,
+            source_shape,
+            target_shape,
+            node_radius,
+        );
+        return vec![start, end];
+    };
+    let (start, _) = edge_endpoints(source_pos, first, source_shape, target_shape, node_radius);
+    let (_, end) = edge_endpoints(last, target_pos, source_shape, target_shape, node_radius);
+
+    let mut points = Vec::with_capacity(waypoints.len() + 2);
+    points.push(start);
+    points.extend(waypoints.iter().cloned());
+    points.push(end);
+    points
+}
 
-    // Determine edge color based on selection state
-    let stroke_color = if is_selected { "darkgreen" } else { "blue" };
-    let stroke_width = if is_selected { "3" } else { "2" };
+/// Direction of the last segment of `points`, in radians as `atan2` gives it, which the arrowhead points along.
+/// Zero when there is no segment.
+pub fn arrowhead_angle(points: &[Point]) -> f64 {
+    match points {
+        [.., from, to] => (to.y - from.y).atan2(to.x - from.x),
+        _ => 0.0,
+    }
+}
+
+/// Where the label of an edge drawn through `points` goes: up and right of the middle of its middle segment.
+pub fn label_position(points: &[Point]) -> Point {
+    let middle = points.len() / 2;
+    let (x, y) = match (points.get(middle.saturating_sub(1)), points.get(middle)) {
+        (Some(from), Some(to)) => ((from.x + to.x) / 2.0, (from.y + to.y) / 2.0),
+        (Some(point), None) | (None, Some(point)) => (point.x, point.y),
+        (None, None) => (0.0, 0.0),
+    };
+    Point {
+        x: x + 10.0,
+        y: y - 10.0,
+    }
+}
+
+/// The `points` attribute of an SVG `polyline` through `points`.
+pub fn svg_points(points: &[Point]) -> String {
+    points
+        .iter()
+        .map(|point| format!("{},{}", point.x, point.y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Width of the invisible line behind an edge that catches clicks, unless the parent sets another.
+pub const DEFAULT_HIT_WIDTH: f64 = 10.0;
+
+/// The invisible line behind an edge that catches clicks on and near it, as the attributes of its `polyline`.
+#[derive(PartialEq, Clone, Debug)]
+pub struct HitLine {
+    /// The `points` attribute, the same as the visible line's.
+    pub points: String,
+    pub stroke: &'static str,
+    pub stroke_width: f64,
+}
+
+/// The hit line for an edge drawn through `points`: a transparent stroke `hit_width` wide over the very points of
+/// the visible line, so the hit area always lines up with it. A negative width counts as none.
+pub fn hit_line(points: &[Point], hit_width: f64) -> HitLine {
+    HitLine {
+        points: svg_points(points),
+        stroke: "transparent",
+        stroke_width: hit_width.max(0.0),
+    }
+}
+
+/// Length of an arrowhead's sides, unless the parent sets another.
+pub const DEFAULT_ARROW_SIZE: f64 = 10.0;
+
+/// Angle between each side of an arrowhead and the edge line.
+const ARROW_ANGLE: f64 = std::f64::consts::PI / 6.0; // 30 degrees
+
+/// How an edge's arrowhead is drawn.
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum ArrowStyle {
+    /// Two lines meeting at the tip.
+    #[default]
+    Open,
+    /// A solid triangle in the edge's color.
+    Filled,
+}
+
+/// The two outer points of an arrowhead with sides `arrow_size` long, drawn at `end` and pointing along `angle`, see
+/// [`arrowhead_angle`].
+pub fn arrowhead_at(end: &Point, angle: f64, arrow_size: f64) -> (Point, Point) {
+    (
+        Point {
+            x: end.x - arrow_size * (angle - ARROW_ANGLE).cos(),
+            y: end.y - arrow_size * (angle - ARROW_ANGLE).sin(),
+        },
+        Point {
+            x: end.x - arrow_size * (angle + ARROW_ANGLE).cos(),
+            y: end.y - arrow_size * (angle + ARROW_ANGLE).sin(),
+        },
+    )
+}
+
+/// Corners of a filled arrowhead drawn at `end` and pointing along `angle`: the tip, then the two outer points of
+/// [`arrowhead_at`].
+pub fn arrow_polygon(end: &Point, angle: f64, arrow_size: f64) -> [Point; 3] {
+    let (side_1, side_2) = arrowhead_at(end, angle, arrow_size);
+    [end.clone(), side_1, side_2]
+}
+
+#[component]
+pub fn Edge(
+    source_pos: Point,
+    target_pos: Point,
+    weight: i32,
+    edge_idx: petgraph::graph::EdgeIndex,
+    on_click: EventHandler<petgraph::graph::EdgeIndex>,
+    is_selected: bool,
+    #[props(default)] is_highlighted: bool,
+    edge_label: Option<String>,
+    #[props(default)] dashed: bool,
+    #[props(default = 25.0)] node_radius: f64,
+    #[props(default)] source_shape: NodeShape,
+    #[props(default)] target_shape: NodeShape,
+    #[props(default)] colors: ThemeColors,
+    #[props(default)] animate: bool,
+    /// Fades the edge out, see [`DIMMED_OPACITY`].
+    #[props(default)]
+    is_dimmed: bool,
+    on_context_menu: Option<EventHandler<(petgraph::graph::EdgeIndex, Point)>>,
+    /// Width of the invisible line catching clicks: narrower for dense graphs, wider for sparse ones.
+    #[props(default = DEFAULT_HIT_WIDTH)]
+    hit_width: f64,
+    /// Length of the arrowhead's sides.
+    #[props(default = DEFAULT_ARROW_SIZE)]
+    arrow_size: f64,
+    #[props(default)] arrow_style: ArrowStyle,
+    /// Bends the edge is routed through, in order. Empty for a straight edge.
+    #[props(default)]
+    waypoints: Vec<Point>,
+    /// Called with the edge when the pointer moves onto it and with `None` when it leaves.
+    on_hover: Option<EventHandler<Option<petgraph::graph::EdgeIndex>>>,
+) -> Element {
+    let points = polyline_points(
+        &source_pos,
+        &target_pos,
+        &waypoints,
+        source_shape,
+        target_shape,
+        node_radius,
+    );
+    let line_points = svg_points(&points);
+    let hit = hit_line(&points, hit_width);
+    let end = points.last().cloned().unwrap_or_else(|| target_pos.clone());
+    let (end_x, end_y) = (end.x, end.y);
+
+    // Calculate arrowhead points, along the last segment
+    let angle = arrowhead_angle(&points);
+    let (arrow_1, arrow_2) = arrowhead_at(&end, angle, arrow_size);
+    let (arrow_x1, arrow_y1) = (arrow_1.x, arrow_1.y);
+    let (arrow_x2, arrow_y2) = (arrow_2.x, arrow_2.y);
+    let arrow_points = svg_points(&arrow_polygon(&end, angle, arrow_size));
+
+    let label = label_position(&points);
+    let (label_x, label_y) = (label.x, label.y);
+
+    // Determine edge color based on selection and highlight state
+    let stroke_color = if is_selected {
+        "darkgreen"
+    } else if is_highlighted {
+        "darkorange"
+    } else {
+        colors.edge
+    };
+    let stroke_width = if is_selected || is_highlighted {
+        "3"
+    } else {
+        "2"
+    };
 
     let handle_edge_click = move |event: MouseEvent| {
         event.prevent_default();
@@ -49,59 +288,111 @@ pub fn Edge(
         on_click.call(edge_idx);
     };
 
+    let handle_edge_contextmenu = move |event: MouseEvent| {
+        event.prevent_default();
+        event.stop_propagation();
+        if let Some(handler) = on_context_menu {
+            let coords = event.data().client_coordinates();
+            handler.call((
+                edge_idx,
+                Point {
+                    x: coords.x as f64,
+                    y: coords.y as f64,
+                },
+            ));
+        }
+    };
+
     rsx! {
         g {
+            opacity: if is_dimmed { DIMMED_OPACITY } else { 1.0 },
+            onmouseenter: move |_| {
+                if let Some(handler) = on_hover {
+                    handler.call(Some(edge_idx));
+                }
+            },
+            onmouseleave: move |_| {
+                if let Some(handler) = on_hover {
+                    handler.call(None);
+                }
+            },
             // Invisible hit area for easier selection (wider line behind the visible edge)
-            line {
-                x1: "{start_x}",
-                y1: "{start_y}",
-                x2: "{end_x}",
-                y2: "{end_y}",
-                stroke: "transparent",
-                stroke_width: "10", // Much wider for easier clicking
+            polyline {
+                points: "{hit.points}",
+                fill: "none",
+                stroke: hit.stroke,
+                stroke_width: "{hit.stroke_width}",
                 cursor: "pointer",
                 onclick: handle_edge_click,
+                oncontextmenu: handle_edge_contextmenu,
             }
             // Edge line
-            line {
-                x1: "{start_x}",
-                y1: "{start_y}",
-                x2: "{end_x}",
-                y2: "{end_y}",
+            polyline {
+                points: "{line_points}",
+                fill: "none",
+                stroke_linejoin: "round",
                 stroke: stroke_color,
                 stroke_width,
+                stroke_dasharray: dash_array(dashed),
                 cursor: "pointer",
                 onclick: handle_edge_click,
+                oncontextmenu: handle_edge_contextmenu,
             }
-            // Arrowhead
-            line {
-                x1: "{end_x}",
-                y1: "{end_y}",
-                x2: "{arrow_x1}",
-                y2: "{arrow_y1}",
-                stroke: stroke_color,
-                stroke_width,
-                cursor: "pointer",
-                onclick: handle_edge_click,
+            // Moving dash over an active edge
+            if let Some(animation) = flow_animation(animate) {
+                polyline {
+                    points: "{line_points}",
+                    fill: "none",
+                    stroke: "white",
+                    stroke_width,
+                    stroke_dasharray: animation.dash_array,
+                    style: animation.style,
+                    pointer_events: "none",
+                }
             }
-            line {
-                x1: "{end_x}",
-                y1: "{end_y}",
-                x2: "{arrow_x2}",
-                y2: "{arrow_y2}",
-                stroke: stroke_color,
-                stroke_width,
-                cursor: "pointer",
-                onclick: handle_edge_click,
+            // Arrowhead
+            if arrow_style == ArrowStyle::Filled {
+                polygon {
+                    points: "{arrow_points}",
+                    fill: stroke_color,
+                    stroke: stroke_color,
+                    stroke_linejoin: "round",
+                    cursor: "pointer",
+                    onclick: handle_edge_click,
+                    oncontextmenu: handle_edge_contextmenu,
+                }
+            } else {
+                line {
+                    x1: "{end_x}",
+                    y1: "{end_y}",
+                    x2: "{arrow_x1}",
+                    y2: "{arrow_y1}",
+                    stroke: stroke_color,
+                    stroke_width,
+                    cursor: "pointer",
+                    onclick: handle_edge_click,
+                    oncontextmenu: handle_edge_contextmenu,
+                }
+                line {
+                    x1: "{end_x}",
+                    y1: "{end_y}",
+                    x2: "{arrow_x2}",
+                    y2: "{arrow_y2}",
+                    stroke: stroke_color,
+                    stroke_width,
+                    cursor: "pointer",
+                    onclick: handle_edge_click,
+                    oncontextmenu: handle_edge_contextmenu,
+                }
             }
             // Edge label (display the edge name if provided, otherwise show weight)
             {
                 if let Some(label) = edge_label {
                     rsx! {
                         text {
-                            x: "{(start_x + end_x) / 2.0 + 10.0}",
-                            y: "{(start_y + end_y) / 2.0 - 10.0}",
-                            fill: "red",
+                            x: "{label_x}",
+                            y: "{label_y}",
+                            fill: colors.edge_label,
                             font_size: "12",
                             font_weight: "bold",
                             "{label}"
@@ -111,9 +402,9 @@ pub fn Edge(
                     // Fallback to weight if no label is provided
                     rsx! {
                         text {
-                            x: "{(start_x + end_x) / 2.0 + 10.0}",
-                            y: "{(start_y + end_y) / 2.0 - 10.0}",
-                            fill: "red",
+                            x: "{label_x}",
+                            y: "{label_y}",
+                            fill: colors.edge_label,
                             font_size: "12",
                             font_weight: "bold",
                             "{weight}"
@@ -0,0 +1,1575 @@
+use crate::components::canvas::{Canvas, CanvasCluster, CanvasEdge, CanvasNode};
+use crate::components::clipboard::copy_to_clipboard;
+use crate::components::cluster::cluster_bounds;
+use crate::components::confirm::ConfirmDialog;
+use crate::components::edge::{ArrowStyle, DEFAULT_ARROW_SIZE, DEFAULT_HIT_WIDTH};
+use crate::components::graph::{
+    clear_graph, cursor_for_mode, hovered_endpoints, node_at, toggle_selection, EditingMode, Point,
+    Selection, Tab,
+};
+use crate::components::layout;
+use crate::components::node::NodeShape;
+use crate::components::svg_export::{download_svg, render_svg, SvgStyle};
+use crate::components::theme::{colors_for, Theme, ThemeColors};
+use crate::components::viewport::{center_on, clamp_viewbox, content_bounds, ViewBox};
+use crate::layout_io;
+use crate::workflow::{
+    node_to_yaml, Edge as WorkflowEdge, EdgeKind, ExecutionState, Node as WorkflowNode, NodeKind,
+    Workflow,
+};
+use dioxus::prelude::*;
+use petgraph::graph::{DiGraph, EdgeIndex, NodeIndex};
+use std::collections::{HashMap, HashSet};
+
+/// Node kinds shown in the legend, with their fill color, in display order.
+const NODE_KINDS: [(&str, &str); 3] = [
+    ("Action", "lightblue"),
+    ("SubGraph", "plum"),
+    ("Router", "khaki"),
+];
+
+/// The legend kind of a node: sub graphs first, then action nodes that route through ports, then plain actions.
+fn node_kind_index(node: &WorkflowNode) -> usize {
+    match node.kind {
+        NodeKind::SubGraph(_) => 1,
+        NodeKind::Action(_) if node.routing_ports().is_some() => 2,
+        NodeKind::Action(_) => 0,
+    }
+}
+
+/// Fill color of a node, by kind.
+pub fn node_kind_color(node: &WorkflowNode) -> &'static str {
+    NODE_KINDS[node_kind_index(node)].1
+}
+
+/// Outline of a node, by kind: sub graphs are rectangles and routers diamonds, following flowchart convention.
+pub fn node_shape(node: &WorkflowNode) -> NodeShape {
+    match node_kind_index(node) {
+        1 => NodeShape::Rect,
+        2 => NodeShape::Diamond,
+        _ => NodeShape::Circle,
+    }
+}
+
+/// `(kind, color, count)` for every node kind, counting the graph's nodes in a single pass.
+pub fn legend_entries(graph: &DiGraph<WorkflowNode, WorkflowEdge>) -> Vec<(String, String, usize)> {
+    let mut counts = [0; NODE_KINDS.len()];
+    for node in graph.node_weights() {
+        counts[node_kind_index(node)] += 1;
+    }
+    NODE_KINDS
+        .iter()
+        .zip(counts)
+        .map(|((kind, color), count)| (kind.to_string(), color.to_string(), count))
+        .collect()
+}
+
+/// Nodes the workflow starts from: those without incoming edges in any of its entry graphs, in index order.
+pub fn entry_nodes(workflow: &Workflow) -> Vec<NodeIndex> {
+    workflow
+        .graph
+        .node_indices()
+        .filter(|&node_idx| {
+            workflow
+                .entry_graph_ids
+                .contains(&workflow.graph[node_idx].subgraph)
+                && workflow.in_degree(node_idx) == 0
+        })
+        .collect()
+}
+
+/// Nodes on some path through `node_idx`: the node itself, its ancestors and its descendants. Isolating the
+/// selection dims everything else.
+pub fn focus_set(workflow: &Workflow, node_idx: NodeIndex) -> HashSet<NodeIndex> {
+    let mut focus = workflow.ancestors(node_idx);
+    focus.extend(workflow.descendants(node_idx));
+    focus.insert(node_idx);
+    focus
+}
+
+/// Splits the edge at `edge_idx` with a new node of the edge's source graph, placed halfway between the edge's ends
+/// when both have a position in `positions`, which is keyed by node id. Returns the new node.
+pub fn insert_between(
+    workflow: &mut Workflow,
+    positions: &mut HashMap<String, Point>,
+    edge_idx: EdgeIndex,
+) -> crate::Result<NodeIndex> {
+    let (source, target) = workflow
+        .graph
+        .edge_endpoints(edge_idx)
+        .ok_or_else(|| crate::Error::Input(format!("edge {} does not exist", edge_idx.index())))?;
+    let subgraph = workflow.graph[source].subgraph.clone();
+    let from = positions.get(&workflow.graph[source].id).cloned();
+    let to = positions.get(&workflow.graph[target].id).cloned();
+    let new_node = new_node(subgraph);
+    let new_node_id = new_node.id.clone();
+    let (new_node_idx, _, _) = workflow.insert_node_on_edge(edge_idx, new_node)?;
+
+    if let (Some(from), Some(to)) = (from, to) {
+        let midpoint = Point {
+            x: (from.x + to.x) / 2.0,
+            y: (from.y + to.y) / 2.0,
+        };
+        positions.insert(new_node_id, midpoint);
+    }
+    Ok(new_node_idx)
+}
+
+/// The edit clicking the edge at `edge_idx` makes in `mode`: Delete Edge removes it and Insert Node splits it with
+/// [`insert_between`], returning the new node. Other modes leave the workflow alone.
+pub fn apply_edge_click(
+    mode: &EditingMode,
+    workflow: &mut Workflow,
+    positions: &mut HashMap<String, Point>,
+    edge_idx: EdgeIndex,
+) -> crate::Result<Option<NodeIndex>> {
+    match mode {
+        EditingMode::DeleteEdge => {
+            workflow.graph.remove_edge(edge_idx);
+            Ok(None)
+        }
+        EditingMode::InsertNode => insert_between(workflow, positions, edge_idx).map(Some),
+        EditingMode::Normal
+        | EditingMode::AddEdge
+        | EditingMode::ConnectEdge
+        | EditingMode::AddNode
+        | EditingMode::DeleteNode => Ok(None),
+    }
+}
+
+/// Commits the text typed into a node's rename box. Surrounding whitespace is dropped; blank text is rejected and
+/// leaves the node as it was.
+pub fn apply_rename(workflow: &mut Workflow, node_idx: NodeIndex, text: &str) -> crate::Result<()> {
+    workflow.rename_node(node_idx, text.trim().to_string())
+}
+
+/// Nodes hidden by collapsing the sub graph nodes whose ids are in `collapsed`: everything inside the graphs they
+/// expand into, including the graphs nested inside those.
+pub fn hidden_nodes(
+    graph: &DiGraph<WorkflowNode, WorkflowEdge>,
+    collapsed: &HashSet<String>,
+) -> HashSet<NodeIndex> {
+    let mut pending: Vec<&str> = graph
+        .node_weights()
+        .filter_map(|node| match &node.kind {
+            NodeKind::SubGraph(graph_id) if collapsed.contains(&node.id) => Some(graph_id.as_str()),
+            _ => None,
+        })
+        .collect();
+    let mut hidden_graphs: HashSet<&str> = HashSet::new();
+    while let Some(graph_id) = pending.pop() {
+        if !hidden_graphs.insert(graph_id) {
+            continue;
+        }
+        for node in graph.node_weights() {
+            if let NodeKind::SubGraph(inner_id) = &node.kind {
+                if node.subgraph == graph_id {
+                    pending.push(inner_id);
+                }
+            }
+        }
+    }
+    graph
+        .node_indices()
+        .filter(|&node_idx| hidden_graphs.contains(graph[node_idx].subgraph.as_str()))
+        .collect()
+}
+
+/// Control edges (routed out of a filter/router port) are drawn dashed.
+pub fn edge_is_dashed(edge: &WorkflowEdge) -> bool {
+    edge.edge_kind == EdgeKind::Control
+}
+
+/// Node outline color for a run state: gray, blue, green or red.
+pub fn state_color(state: ExecutionState) -> &'static str {
+    match state {
+        ExecutionState::Pending => "gray",
+        ExecutionState::Running => "blue",
+        ExecutionState::Succeeded => "green",
+        ExecutionState::Failed => "red",
+    }
+}
+
+/// The [`SvgStyle`] that draws `workflow` as the canvas does: `base` carries the canvas settings, to which this adds
+/// each node's shape and kind fill, the bends in `waypoints` (keyed by edge id) and the dashed control edges.
+pub fn workflow_svg_style(
+    workflow: &Workflow,
+    waypoints: &HashMap<String, Vec<Point>>,
+    base: SvgStyle,
+) -> SvgStyle {
+    let graph = &workflow.graph;
+    SvgStyle {
+        // Kind fills are light in both themes
+        colors: ThemeColors {
+            node_text: "black",
+            ..base.colors
+        },
+        shapes: graph
+            .node_indices()
+            .map(|node_idx| (node_idx, node_shape(&graph[node_idx])))
+            .collect(),
+        node_fills: graph
+            .node_indices()
+            .map(|node_idx| (node_idx, node_kind_color(&graph[node_idx])))
+            .collect(),
+        waypoints: graph
+            .edge_indices()
+            .filter_map(|edge_idx| Some((edge_idx, waypoints.get(&graph[edge_idx].id)?.clone())))
+            .collect(),
+        dashed: graph
+            .edge_indices()
+            .filter(|&edge_idx| edge_is_dashed(&graph[edge_idx]))
+            .collect(),
+        ..base
+    }
+}
+
+/// Translucent fills for subgraph clusters, cycled through in cluster order.
+const CLUSTER_COLORS: [&str; 4] = ["#93c5fd", "#fcd34d", "#86efac", "#f9a8d4"];
+
+/// A fresh action node placed in `subgraph`.
+fn new_node(subgraph: String) -> WorkflowNode {
+    WorkflowNode {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: "NewNode".to_string(),
+        subgraph,
+        ..Default::default()
+    }
+}
+
+/// `(key, value)` rows for a `with` block, ordered by key. Strings are shown bare and other values as inline YAML.
+fn with_rows(with: &HashMap<String, serde_yaml::Value>) -> Vec<(String, String)> {
+    let mut rows: Vec<(String, String)> = with
+        .iter()
+        .map(|(key, value)| {
+            let text = match value {
+                serde_yaml::Value::String(text) => text.clone(),
+                other => serde_yaml::to_string(other)
+                    .map(|yaml| yaml.trim_end().to_string())
+                    .unwrap_or_default(),
+            };
+            (key.clone(), text)
+        })
+        .collect();
+    rows.sort();
+    rows
+}
+
+/// Narrowest and widest the properties panel can be dragged to, in pixels.
+const PANEL_MIN_WIDTH: f64 = 180.0;
+const PANEL_MAX_WIDTH: f64 = 600.0;
+
+/// `(label, value)` rows describing the selected node or edge, as listed in the properties panel. A node's or edge's
+/// own fields come first, followed by its `with` entries.
+pub fn properties_rows(selection: &Selection<WorkflowNode, WorkflowEdge>) -> Vec<(String, String)> {
+    let mut rows: Vec<(String, String)> = Vec::new();
+    let with = match selection {
+        Selection::Node((_, node)) => {
+            rows.push(("Id".to_string(), node.id.clone()));
+            rows.push(("Name".to_string(), node.name.clone()));
+            match &node.kind {
+                NodeKind::Action(action) => {
+                    rows.push(("Type".to_string(), "action".to_string()));
+                    rows.push(("Action".to_string(), action.to_string()));
+                }
+                NodeKind::SubGraph(graph_id) => {
+                    rows.push(("Type".to_string(), "subGraph".to_string()));
+                    rows.push(("Sub graph".to_string(), graph_id.clone()));
+                }
+            }
+            rows.push(("Graph".to_string(), node.subgraph.clone()));
+            if let Some(ports) = node.routing_ports() {
+                rows.push(("Ports".to_string(), ports.join(", ")));
+            }
+            if let Some(description) = &node.description {
+                rows.push(("Description".to_string(), description.clone()));
+            }
+            node.with.as_ref()
+        }
+        Selection::Edge((_, edge)) => {
+            rows.push(("Id".to_string(), edge.id.clone()));
+            rows.push(("Name".to_string(), edge.name.clone()));
+            rows.push(("From port".to_string(), edge.from_port.clone()));
+            rows.push(("To port".to_string(), edge.to_port.clone()));
+            rows.push(("Kind".to_string(), format!("{:?}", edge.edge_kind)));
+            if let Some(weight) = edge.weight {
+                rows.push(("Weight".to_string(), weight.to_string()));
+            }
+            edge.with.as_ref()
+        }
+        Selection::None => None,
+    };
+    rows.extend(with.map(with_rows).unwrap_or_default());
+    rows
+}
+
+/// A fresh data edge between the default ports.
+fn new_edge() -> WorkflowEdge {
+    WorkflowEdge {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: "default".to_string(),
+        from_port: "default".to_string(),
+        to_port: "default".to_string(),
+        ..Default::default()
+    }
+}
+
+/// Workflow-specific counterpart of [`crate::components::Graph`], editing a [`Workflow`] in place.
+#[component]
+pub fn Flow(
+    mut workflow: Signal<Workflow>,
+    /// Node positions keyed by node id, kept in step with the canvas.
+    initial_positions: Option<Signal<HashMap<String, Point>>>,
+    #[props(default = 25.0)] node_radius: f64,
+    #[props(default)] theme: Theme,
+    /// Width of the area around each edge that catches clicks.
+    #[props(default = DEFAULT_HIT_WIDTH)]
+    edge_hit_width: f64,
+    /// Length of the sides of each edge's arrowhead.
+    #[props(default = DEFAULT_ARROW_SIZE)]
+    arrow_size: f64,
+    #[props(default)] arrow_style: ArrowStyle,
+    /// Edges currently carrying data in a running workflow, drawn with a moving dash.
+    #[props(default)]
+    active_edges: HashSet<petgraph::graph::EdgeIndex>,
+    /// Id of a node to center the view on, e.g. from a deep link.
+    focus_node_id: Option<String>,
+) -> Element {
+    // Store node positions in a signal for dragging, using provided positions or default layout. They are keyed by
+    // node id, because removing a node moves another one to its index
+    let mut node_positions = use_signal(move || {
+        let workflow_ref = workflow.read();
+        let graph_ref = &workflow_ref.graph;
+        let node_count = graph_ref.node_count();
+        let mut positions = std::collections::HashMap::new();
+
+        if node_count > 0 {
+            // Use provided positions if available, placing any nodes they miss, otherwise the workflow's default layout
+            if let Some(initial_pos) = initial_positions.filter(|pos| !pos.read().is_empty()) {
+                let by_index = layout_io::positions_by_index(&workflow_ref, &initial_pos.read());
+                positions = layout::incremental(&by_index, graph_ref);
+            } else {
+                positions =
+                    layout::default_layout(&workflow_ref, layout::DEFAULT_CANVAS_SIZE.into());
+            }
+        }
+
+        layout_io::positions_by_id(&workflow_ref, &positions)
+    });
+
+    // The positions keyed by the nodes' current indices, for drawing and hit testing
+    let index_positions =
+        move || layout_io::positions_by_index(&workflow.read(), &node_positions.read());
+
+    // Records where the node at `node_idx` sits
+    let mut place_node = move |node_idx: petgraph::graph::NodeIndex, point: Point| {
+        let id = workflow.read().stable_id(node_idx).map(str::to_string);
+        if let Some(id) = id {
+            node_positions.write().insert(id, point);
+        }
+    };
+
+    // Keep the provided positions in step with the canvas, so the parent can save them
+    use_effect(move || {
+        let positions = node_positions.read().clone();
+        if let Some(mut shared) = initial_positions {
+            shared.set(positions);
+        }
+    });
+
+    // Size of the canvas element, kept up to date by `onresize`
+    let mut canvas_size = use_signal(|| layout::DEFAULT_CANVAS_SIZE);
+
+    // Whether the default layout still has to be fitted to the canvas once it is first measured
+    let mut layout_awaits_size =
+        use_signal(move || initial_positions.is_none_or(|pos| pos.peek().is_empty()));
+
+    // The part of the drawing shown on the canvas; `None` shows it unmoved
+    let mut view_box = use_signal(|| None::<ViewBox>);
+
+    // Text typed into the node search box
+    let mut search_query = use_signal(String::new);
+
+    // Nodes shift-clicked as the ends of a path to highlight, at most two
+    let mut path_ends = use_signal(Vec::<petgraph::graph::NodeIndex>::new);
+
+    // Whether nodes and edges on cycles are drawn in red
+    let mut show_cycles = use_signal(|| false);
+
+    // Whether everything off the paths through the selected node is dimmed
+    let mut isolate_selection = use_signal(|| false);
+
+    // Action picked in the Node tab filter; its nodes are highlighted. Empty means no filter
+    let mut action_filter = use_signal(String::new);
+
+    // Ids of sub graph nodes whose contents are collapsed into them
+    let mut collapsed = use_signal(HashSet::<String>::new);
+
+    // Nodes of the connected component picked with alt-click, drawn as selected
+    let mut selected_component = use_signal(HashSet::<petgraph::graph::NodeIndex>::new);
+
+    // Bends each edge is routed through, by edge id so they outlive index changes. Edges without any are straight
+    let mut waypoints = use_signal(HashMap::<String, Vec<Point>>::new);
+
+    // Edge under the pointer; its endpoints are highlighted
+    let mut hovered_edge = use_signal(|| None::<petgraph::graph::EdgeIndex>);
+
+    // Action node whose label is being edited in place
+    let mut editing_node = use_signal(|| None::<petgraph::graph::NodeIndex>);
+
+    // Track which node is currently being dragged
+    let mut dragging_node = use_signal(|| None::<petgraph::graph::NodeIndex>);
+
+    // Track an edge being dragged out in connect mode: the source node and the current pointer position
+    let mut connecting = use_signal(|| None::<(petgraph::graph::NodeIndex, Point)>);
+
+    // Track the current editing mode
+    let mut editing_mode = use_signal(|| EditingMode::Normal);
+
+    // Track selected nodes for edge creation
+    let mut selected_nodes = use_signal(|| Vec::<petgraph::graph::NodeIndex>::new());
+
+    // Track current selection (for properties panel)
+    let mut current_selection = use_signal(|| Selection::<WorkflowNode, WorkflowEdge>::None);
+
+    // Whether the dialog confirming "Clear Graph" is open
+    let mut confirming_clear = use_signal(|| false);
+
+    // Properties panel beside the canvas: whether it's expanded, its width, and the pointer's last x while its edge is
+    // being dragged
+    let mut properties_open = use_signal(|| true);
+    let mut panel_width = use_signal(|| 280.0);
+    let mut resizing_from = use_signal(|| None::<f64>);
+
+    // Track current active tab
+    let mut active_tab = use_signal(|| Tab::Node);
+
+    // Removing nodes moves others to new indices, so everything holding node or edge indices is dropped
+    let mut forget_indices = move || {
+        *current_selection.write() = Selection::None;
+        selected_nodes.write().clear();
+        path_ends.write().clear();
+        selected_component.write().clear();
+        hovered_edge.set(None);
+        editing_node.set(None);
+    };
+
+    // Converts a point on the canvas element to drawing coordinates
+    let to_drawing = move |coordinates: dioxus::html::geometry::ElementPoint| {
+        let point = Point {
+            x: coordinates.x,
+            y: coordinates.y,
+        };
+        match view_box() {
+            Some(view) => view.to_drawing(point, canvas_size()),
+            None => point,
+        }
+    };
+
+    // Centers the view on a node and selects it
+    let mut pan_to = move |node_idx: petgraph::graph::NodeIndex| {
+        let view = center_on(&index_positions(), node_idx, canvas_size());
+        if let Some(view) = view {
+            view_box.set(Some(view));
+        }
+        let node_data = workflow.read().graph.node_weight(node_idx).cloned();
+        if let Some(node_data) = node_data {
+            *current_selection.write() = Selection::Node((node_idx, node_data));
+        }
+    };
+
+    // Pan to the requested node whenever the id changes
+    use_effect(use_reactive!(|focus_node_id| {
+        let node_idx = focus_node_id
+            .as_deref()
+            .and_then(|id| workflow.peek().find_node(id));
+        if let Some(node_idx) = node_idx {
+            pan_to(node_idx);
+        }
+    }));
+
+    // Zooms around the pointer, keeping part of the drawing in view
+    let handle_wheel = move |event: WheelEvent| {
+        event.prevent_default();
+        let (width, height) = canvas_size();
+        let current = view_box().unwrap_or(ViewBox::new(width, height));
+        let anchor = to_drawing(event.data().element_coordinates());
+        let factor = if event.data().delta().strip_units().y < 0.0 {
+            1.1
+        } else {
+            1.0 / 1.1
+        };
+        let zoomed = current.zoom(factor, &anchor, width);
+        let zoomed = match content_bounds(&index_positions(), node_radius) {
+            Some(content) => clamp_viewbox(zoomed, content),
+            None => zoomed,
+        };
+        view_box.set(Some(zoomed));
+    };
+
+    let handle_mousemove = move |event: MouseEvent| {
+        let connecting_from = connecting.read().as_ref().map(|(source, _)| *source);
+        if let Some(source) = connecting_from {
+            let pointer = to_drawing(event.data().element_coordinates());
+            *connecting.write() = Some((source, pointer));
+        } else if let Some(node_idx) = *dragging_node.read() {
+            let Point { x, y } = to_drawing(event.data().element_coordinates());
+
+            // Update the position of the dragged node
+            place_node(node_idx, Point { x, y });
+        }
+    };
+
+    let handle_mouseup = move |_| {
+        *dragging_node.write() = None;
+
+        // Finish a connect drag: released over another node creates the edge, anywhere else cancels it
+        let finished = connecting.write().take();
+        if let Some((source, pointer)) = finished {
+            let target = node_at(&index_positions(), &pointer, node_radius);
+            if let Some(target) = target.filter(|target| *target != source) {
+                workflow.write().graph.add_edge(source, target, new_edge());
+            }
+        }
+    };
+
+    let handle_mouseleave = move |_| {
+        *dragging_node.write() = None;
+        *connecting.write() = None;
+    };
+
+    let handle_drag_start = move |node_idx: petgraph::graph::NodeIndex| {
+        if *editing_mode.read() == EditingMode::ConnectEdge {
+            let start = index_positions().get(&node_idx).cloned();
+            if let Some(start) = start {
+                *connecting.write() = Some((node_idx, start));
+            }
+        } else {
+            *dragging_node.write() = Some(node_idx);
+        }
+    };
+
+    // Shift-clicking picks the two ends of the path to highlight; a third shift-click starts over
+    let handle_node_shift_click = move |node_idx: petgraph::graph::NodeIndex| {
+        let mut ends = path_ends.write();
+        if ends.len() == 2 {
+            ends.clear();
+        }
+        ends.push(node_idx);
+    };
+
+    // Alt-clicking a node selects it together with everything connected to it
+    let handle_node_alt_click = move |node_idx: petgraph::graph::NodeIndex| {
+        if *editing_mode.read() != EditingMode::Normal {
+            return;
+        }
+        let workflow_ref = workflow.read();
+        if let Some(node_data) = workflow_ref.graph.node_weight(node_idx) {
+            *current_selection.write() = Selection::Node((node_idx, node_data.clone()));
+            selected_component.set(workflow_ref.connected_component(node_idx));
+        }
+    };
+
+    let handle_node_click = move |node_idx: petgraph::graph::NodeIndex| {
+        match *editing_mode.read() {
+            EditingMode::Normal => {
+                // Select the node for properties panel, or deselect it if it already is
+                selected_component.write().clear();
+                let node_data = workflow.read().graph.node_weight(node_idx).cloned();
+                if let Some(node_data) = node_data {
+                    let next = toggle_selection(
+                        &current_selection.read(),
+                        Selection::Node((node_idx, node_data)),
+                    );
+                    current_selection.set(next);
+                }
+            }
+            EditingMode::AddEdge => {
+                // Add node to selection for edge creation
+                let mut nodes = selected_nodes.write();
+                if !nodes.contains(&node_idx) {
+                    nodes.push(node_idx);
+                }
+
+                // If we have two nodes selected, create an edge
+                if nodes.len() == 2 {
+                    let source = nodes[0];
+                    let target = nodes[1];
+
+                    // Add a data edge between the default ports
+                    workflow.write().graph.add_edge(source, target, new_edge());
+
+                    // Clear selection
+                    nodes.clear();
+                }
+            }
+            EditingMode::ConnectEdge => {
+                // Edges are created by dragging, see `handle_mouseup`
+            }
+            EditingMode::DeleteEdge => {
+                // In delete mode, clicking a node doesn't do anything
+                // Edges are deleted by clicking on them directly
+            }
+            EditingMode::AddNode | EditingMode::InsertNode => {
+                // In add or insert node mode, clicking a node doesn't do anything
+            }
+            EditingMode::DeleteNode => {
+                // Remove the node from positions, while its index still names it
+                let id = workflow.read().stable_id(node_idx).map(str::to_string);
+                if let Some(id) = id {
+                    node_positions.write().remove(&id);
+                }
+
+                // Remove the node from the graph
+                workflow.write().remove_node(node_idx);
+
+                forget_indices();
+            }
+        }
+    };
+
+    let handle_canvas_click = move |event: MouseEvent| {
+        // Shift-clicking the canvas while an edge is selected bends the edge through the clicked point
+        if *editing_mode.read() == EditingMode::Normal
+            && event.modifiers().contains(Modifiers::SHIFT)
+        {
+            if let Selection::Edge((_, edge_data)) = current_selection() {
+                let bend = to_drawing(event.data().element_coordinates());
+                waypoints
+                    .write()
+                    .entry(edge_data.id)
+                    .or_default()
+                    .push(bend);
+            }
+            return;
+        }
+        // Clicking empty canvas clears the selection; nodes and edges stop their own clicks
+        if *editing_mode.read() == EditingMode::Normal {
+            current_selection.set(Selection::None);
+        }
+        if *editing_mode.read() == EditingMode::AddNode {
+            let click = to_drawing(event.data().element_coordinates());
+            let (width, height) = canvas_size();
+            let view = view_box().unwrap_or(ViewBox::new(width, height));
+            let position = layout::new_node_position(&index_positions(), click, &view);
+
+            // Add a new node to the entry graph
+            let subgraph = workflow
+                .read()
+                .primary_entry()
+                .unwrap_or_default()
+                .to_string();
+            let new_node = new_node(subgraph);
+
+            // Add the new node's position
+            node_positions.write().insert(new_node.id.clone(), position);
+            workflow.write().graph.add_node(new_node);
+        }
+    };
+
+    let handle_edge_click = move |edge_idx: petgraph::graph::EdgeIndex| {
+        let mode = editing_mode();
+        if mode == EditingMode::Normal {
+            // Select the edge for properties panel, or deselect it if it already is
+            let edge_data = workflow.read().graph.edge_weight(edge_idx).cloned();
+            if let Some(edge_data) = edge_data {
+                let next = toggle_selection(
+                    &current_selection.read(),
+                    Selection::Edge((edge_idx, edge_data)),
+                );
+                current_selection.set(next);
+            }
+            return;
+        }
+
+        let changed = apply_edge_click(
+            &mode,
+            &mut workflow.write(),
+            &mut node_positions.write(),
+            edge_idx,
+        );
+        match changed {
+            // A node inserted on the edge becomes the selection
+            Ok(Some(new_node_idx)) => {
+                let new_node = workflow.read().graph[new_node_idx].clone();
+                *current_selection.write() = Selection::Node((new_node_idx, new_node));
+            }
+            Ok(None) if mode == EditingMode::DeleteEdge => {
+                *current_selection.write() = Selection::None
+            }
+            Ok(None) => {}
+            Err(e) => tracing::error!(
+                "failed to insert a node on edge {}: {}",
+                edge_idx.index(),
+                e
+            ),
+        }
+    };
+
+    let set_normal_mode = move |_| {
+        *editing_mode.write() = EditingMode::Normal;
+        selected_nodes.write().clear();
+    };
+
+    let set_add_edge_mode = move |_| {
+        *editing_mode.write() = EditingMode::AddEdge;
+        selected_nodes.write().clear();
+    };
+
+    let set_connect_edge_mode = move |_| {
+        *editing_mode.write() = EditingMode::ConnectEdge;
+        selected_nodes.write().clear();
+    };
+
+    let set_insert_node_mode = move |_| {
+        *editing_mode.write() = EditingMode::InsertNode;
+        selected_nodes.write().clear();
+    };
+
+    let set_delete_edge_mode = move |_| {
+        *editing_mode.write() = EditingMode::DeleteEdge;
+        selected_nodes.write().clear();
+    };
+
+    let set_add_node_mode = move |_| {
+        *editing_mode.write() = EditingMode::AddNode;
+    };
+
+    let set_delete_node_mode = move |_| {
+        *editing_mode.write() = EditingMode::DeleteNode;
+    };
+
+    // Double-clicking a sub graph node collapses its contents into it, or expands them again. Double-clicking an
+    // action node starts renaming it in place
+    let handle_node_double_click = move |node_idx: petgraph::graph::NodeIndex| {
+        let node_id = {
+            let workflow_ref = workflow.read();
+            let node = &workflow_ref.graph[node_idx];
+            match node.kind {
+                NodeKind::SubGraph(_) => node.id.clone(),
+                NodeKind::Action(_) => {
+                    editing_node.set(Some(node_idx));
+                    return;
+                }
+            }
+        };
+        let mut collapsed = collapsed.write();
+        if !collapsed.remove(&node_id) {
+            collapsed.insert(node_id);
+        }
+    };
+
+    let handle_node_rename = move |(node_idx, text): (petgraph::graph::NodeIndex, String)| {
+        // Enter removes the rename box, which may blur it too; only the first submission counts
+        if editing_node() != Some(node_idx) {
+            return;
+        }
+        editing_node.set(None);
+        if let Err(e) = apply_rename(&mut workflow.write(), node_idx, &text) {
+            tracing::warn!("rename of node {} not applied: {}", node_idx.index(), e);
+        }
+    };
+
+    let handle_node_cancel_rename = move |_node_idx: petgraph::graph::NodeIndex| {
+        editing_node.set(None);
+    };
+
+    let duplicate_selected_node = move |_| {
+        let Selection::Node((node_idx, _)) = current_selection() else {
+            return;
+        };
+        let new_node_idx = workflow.write().duplicate_node(node_idx);
+
+        // Place the copy slightly offset from the original
+        let position = index_positions().get(&node_idx).cloned();
+        if let Some(Point { x, y }) = position {
+            place_node(
+                new_node_idx,
+                Point {
+                    x: x + 30.0,
+                    y: y + 30.0,
+                },
+            );
+        }
+
+        let new_node = workflow.read().graph[new_node_idx].clone();
+        *current_selection.write() = Selection::Node((new_node_idx, new_node));
+    };
+
+    // Splits the selected edge with a new node placed halfway between its ends, and selects that node
+    let insert_node_on_selected_edge = move |_| {
+        let Selection::Edge((edge_idx, _)) = current_selection() else {
+            return;
+        };
+        let inserted = insert_between(&mut workflow.write(), &mut node_positions.write(), edge_idx);
+        match inserted {
+            Ok(new_node_idx) => {
+                let new_node = workflow.read().graph[new_node_idx].clone();
+                *current_selection.write() = Selection::Node((new_node_idx, new_node));
+            }
+            Err(e) => tracing::error!(
+                "failed to insert a node on edge {}: {}",
+                edge_idx.index(),
+                e
+            ),
+        }
+    };
+
+    let straighten_selected_edge = move |_| {
+        if let Selection::Edge((_, edge_data)) = current_selection() {
+            waypoints.write().remove(&edge_data.id);
+        }
+    };
+
+    let copy_selected_node_yaml = move |_| {
+        let Selection::Node((_, node_data)) = current_selection() else {
+            return;
+        };
+        match node_to_yaml(&node_data) {
+            Ok(yaml) => copy_to_clipboard(&yaml),
+            Err(e) => tracing::error!("failed to serialize node {}: {}", node_data.id, e),
+        }
+    };
+
+    let colors = colors_for(theme);
+
+    let export_svg = move |_| {
+        let workflow_ref = workflow.read();
+        let style = workflow_svg_style(
+            &workflow_ref,
+            &waypoints.read(),
+            SvgStyle {
+                node_radius,
+                colors,
+                arrow_size,
+                arrow_style,
+                ..Default::default()
+            },
+        );
+        let svg = render_svg(&workflow_ref.graph, &index_positions(), &style);
+        download_svg(svg, &format!("{}.svg", workflow_ref.name));
+    };
+
+    // Empties the workflow's graph along with everything the canvas keeps about its nodes
+    let clear_confirmed = move |_| {
+        clear_graph(
+            &mut workflow.write().graph,
+            &mut HashMap::new(),
+            &mut current_selection.write(),
+        );
+        node_positions.write().clear();
+        workflow.write().node_states.clear();
+        selected_nodes.write().clear();
+        path_ends.write().clear();
+        selected_component.write().clear();
+        collapsed.write().clear();
+        waypoints.write().clear();
+        editing_node.set(None);
+        dragging_node.set(None);
+        connecting.set(None);
+        confirming_clear.set(false);
+    };
+
+    // Positions are keyed by id and only lose the pruned nodes
+    let prune_unreachable = move |_| {
+        let removed = workflow.write().prune_unreachable();
+        if !removed.is_empty() {
+            node_positions.write().retain(|id, _| !removed.contains(id));
+            forget_indices();
+        }
+    };
+
+    let switch_to_node_tab = move |_| {
+        *active_tab.write() = Tab::Node;
+        *editing_mode.write() = EditingMode::Normal;
+    };
+
+    let switch_to_edge_tab = move |_| {
+        *active_tab.write() = Tab::Edge;
+        *editing_mode.write() = EditingMode::Normal;
+    };
+
+    let switch_to_workflow_tab = move |_| {
+        *active_tab.write() = Tab::Workflow;
+        *editing_mode.write() = EditingMode::Normal;
+    };
+
+    // Positions by the nodes' current indices, for this render
+    let positions = index_positions();
+
+    // Get the current selection info for display
+    let selection_info = match &*current_selection.read() {
+        Selection::Node((node_idx, node_data)) => {
+            let workflow_ref = workflow.read();
+            let inputs = workflow_ref.in_degree(*node_idx);
+            let outputs = workflow_ref.out_degree(*node_idx);
+            format!(
+                "Selected Node: {} ({} input{}, {} output{})",
+                node_data,
+                inputs,
+                if inputs == 1 { "" } else { "s" },
+                outputs,
+                if outputs == 1 { "" } else { "s" }
+            )
+        }
+        Selection::Edge((edge_idx, edge_data)) => {
+            format!("Selected Edge: {}", edge_data)
+        }
+        Selection::None => "No selection".to_string(),
+    };
+
+    // Ends of the hovered edge
+    let hover_highlight = hovered_endpoints(&workflow.read().graph, hovered_edge());
+
+    // Starting nodes of the workflow, marked with a badge
+    let entries = entry_nodes(&workflow.read());
+
+    // Nodes downstream of the selected node, highlighted along with the edges leading to them
+    let downstream = match &*current_selection.read() {
+        Selection::Node((node_idx, _)) => workflow.read().descendants(*node_idx),
+        _ => HashSet::new(),
+    };
+
+    // Nodes left undimmed while the selection is isolated, `None` when nothing is dimmed
+    let focus = match &*current_selection.read() {
+        Selection::Node((node_idx, _)) if isolate_selection() => {
+            Some(focus_set(&workflow.read(), *node_idx))
+        }
+        _ => None,
+    };
+
+    // Shortest path between the shift-clicked nodes, as its nodes and its consecutive (source, target) pairs
+    let path: Vec<petgraph::graph::NodeIndex> = match path_ends.read().as_slice() {
+        [from, to] => workflow
+            .read()
+            .shortest_path(*from, *to)
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    let path_steps: HashSet<(petgraph::graph::NodeIndex, petgraph::graph::NodeIndex)> =
+        path.windows(2).map(|step| (step[0], step[1])).collect();
+
+    // Nodes running the action picked in the Node tab filter
+    let action_matches: HashSet<petgraph::graph::NodeIndex> = if action_filter.read().is_empty() {
+        HashSet::new()
+    } else {
+        workflow
+            .read()
+            .find_nodes_by_action(&action_filter.read())
+            .into_iter()
+            .collect()
+    };
+
+    // Cycle each node lies on, by position in `all_cycles`, while the cycle overlay is shown
+    let cycle_of: HashMap<petgraph::graph::NodeIndex, usize> = if show_cycles() {
+        workflow
+            .read()
+            .all_cycles()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, cycle)| cycle.into_iter().map(move |node_idx| (node_idx, i)))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    // Contents of collapsed sub graphs, left out of the canvas
+    let hidden = hidden_nodes(&workflow.read().graph, &collapsed.read());
+
+    // Subgraph clusters of the shown nodes
+    let clusters: Vec<CanvasCluster> = {
+        let workflow_ref = workflow.read();
+        let mut groups: HashMap<String, Vec<petgraph::graph::NodeIndex>> = HashMap::new();
+        for node_idx in workflow_ref
+            .graph
+            .node_indices()
+            .filter(|node_idx| !hidden.contains(node_idx))
+        {
+            groups
+                .entry(workflow_ref.graph[node_idx].subgraph.clone())
+                .or_default()
+                .push(node_idx);
+        }
+        cluster_bounds(&positions, &groups)
+            .into_iter()
+            .enumerate()
+            .map(|(i, (graph_id, rect))| CanvasCluster {
+                label: workflow_ref
+                    .graph_name(&graph_id)
+                    .unwrap_or(&graph_id)
+                    .to_string(),
+                id: graph_id,
+                rect,
+                color: CLUSTER_COLORS[i % CLUSTER_COLORS.len()],
+            })
+            .collect()
+    };
+
+    // Shown nodes and the edges between them, as the canvas draws them
+    let canvas_nodes: Vec<CanvasNode> = {
+        let workflow_ref = workflow.read();
+        let graph_ref = &workflow_ref.graph;
+        graph_ref
+            .node_indices()
+            .filter(|node_idx| !hidden.contains(node_idx))
+            .filter_map(|node_idx| {
+                let node_data = &graph_ref[node_idx];
+                Some(CanvasNode {
+                    node_idx,
+                    position: positions.get(&node_idx)?.clone(),
+                    label: node_data.to_string(),
+                    description: node_data.description.clone(),
+                    shape: node_shape(node_data),
+                    // Kind fills are light in both themes
+                    // Once a run has recorded states, the outline shows each node's state
+                    colors: ThemeColors {
+                        node_fill: node_kind_color(node_data),
+                        node_stroke: if cycle_of.contains_key(&node_idx) {
+                            "red"
+                        } else if workflow_ref.node_states.is_empty() {
+                            colors.node_stroke
+                        } else {
+                            state_color(workflow_ref.execution_state(node_idx))
+                        },
+                        node_text: "black",
+                        ..colors
+                    },
+                    is_selected: matches!(
+                        *current_selection.read(),
+                        Selection::Node((selected_idx, _)) if selected_idx == node_idx
+                    ) || selected_component.read().contains(&node_idx),
+                    is_highlighted: downstream.contains(&node_idx)
+                        || action_matches.contains(&node_idx)
+                        || path.contains(&node_idx)
+                        || path_ends.read().contains(&node_idx)
+                        || hover_highlight.contains(&node_idx),
+                    is_entry: entries.contains(&node_idx),
+                    is_dimmed: focus
+                        .as_ref()
+                        .is_some_and(|focus| !focus.contains(&node_idx)),
+                    is_editing: editing_node() == Some(node_idx),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    };
+    let canvas_edges: Vec<CanvasEdge> = {
+        let workflow_ref = workflow.read();
+        let graph_ref = &workflow_ref.graph;
+        let selected_node = match *current_selection.read() {
+            Selection::Node((selected_idx, _)) => Some(selected_idx),
+            _ => None,
+        };
+        graph_ref
+            .edge_indices()
+            .filter_map(|edge_idx| {
+                let (source, target) = graph_ref.edge_endpoints(edge_idx)?;
+                if hidden.contains(&source) || hidden.contains(&target) {
+                    return None;
+                }
+                let edge_data = &graph_ref[edge_idx];
+                Some(CanvasEdge {
+                    edge_idx,
+                    source_pos: positions.get(&source)?.clone(),
+                    target_pos: positions.get(&target)?.clone(),
+                    label: edge_data.to_string(),
+                    source_shape: node_shape(&graph_ref[source]),
+                    target_shape: node_shape(&graph_ref[target]),
+                    // An edge between two nodes of the same cycle is part of that cycle
+                    colors: match (cycle_of.get(&source), cycle_of.get(&target)) {
+                        (Some(a), Some(b)) if a == b => ThemeColors {
+                            edge: "red",
+                            ..colors
+                        },
+                        _ => colors,
+                    },
+                    waypoints: waypoints
+                        .read()
+                        .get(&edge_data.id)
+                        .cloned()
+                        .unwrap_or_default(),
+                    dashed: edge_is_dashed(edge_data),
+                    animate: active_edges.contains(&edge_idx)
+                        || workflow_ref.execution_state(target) == ExecutionState::Running,
+                    is_selected: matches!(
+                        *current_selection.read(),
+                        Selection::Edge((selected_idx, _)) if selected_idx == edge_idx
+                    ),
+                    is_highlighted: path_steps.contains(&(source, target))
+                        || downstream.contains(&target)
+                            && (downstream.contains(&source) || selected_node == Some(source)),
+                    is_dimmed: focus
+                        .as_ref()
+                        .is_some_and(|focus| !focus.contains(&source) || !focus.contains(&target)),
+                })
+            })
+            .collect()
+    };
+
+    // Rubber band from the source of an edge being dragged out to the pointer
+    let band = connecting.read().as_ref().and_then(|(source, pointer)| {
+        positions
+            .get(source)
+            .map(|start| (start.clone(), pointer.clone()))
+    });
+
+    rsx! {
+        div { class: "flex flex-col h-screen",
+            div { class: "p-4 bg-gray-100",
+                h2 { class: "text-xl font-bold", "Workflow: {workflow.read().name}" }
+                div { class: "mt-2 text-sm text-gray-600",
+                    "Workflow graph visualization. Drag nodes to reposition them."
+                }
+                // Issues raised by edits, e.g. the entry graph changing after its last node was deleted
+                if !workflow.read().edit_warnings.is_empty() {
+                    ul { class: "mt-2 p-2 list-disc list-inside bg-yellow-100 text-yellow-800 rounded text-sm",
+                        for (i, warning) in workflow.read().edit_warnings.iter().enumerate() {
+                            li { key: "{i}", "{warning}" }
+                        }
+                    }
+                }
+                div { class: "flex space-x-2 mt-2",
+                    button {
+                        class: "px-3 py-1 rounded text-sm bg-gray-200",
+                        onclick: export_svg,
+                        "Export SVG"
+                    }
+                    button {
+                        class: "px-3 py-1 rounded text-sm bg-gray-200",
+                        onclick: move |_| view_box.set(None),
+                        "Reset View"
+                    }
+                    button {
+                        class: "px-3 py-1 rounded text-sm bg-gray-200",
+                        onclick: move |_| confirming_clear.set(true),
+                        "Clear Graph"
+                    }
+                    button {
+                        class: "px-3 py-1 rounded text-sm bg-gray-200",
+                        onclick: prune_unreachable,
+                        "Prune Unreachable"
+                    }
+                    {
+                        let btn_class = if show_cycles() {
+                            "px-3 py-1 rounded text-sm bg-red-500 text-white"
+                        } else {
+                            "px-3 py-1 rounded text-sm bg-gray-200"
+                        };
+                        rsx! {
+                            button {
+                                class: "{btn_class}",
+                                onclick: move |_| show_cycles.set(!show_cycles()),
+                                "Highlight Cycles"
+                            }
+                        }
+                    }
+                    {
+                        let btn_class = if isolate_selection() {
+                            "px-3 py-1 rounded text-sm bg-blue-500 text-white"
+                        } else {
+                            "px-3 py-1 rounded text-sm bg-gray-200"
+                        };
+                        rsx! {
+                            button {
+                                class: "{btn_class}",
+                                onclick: move |_| isolate_selection.set(!isolate_selection()),
+                                "Isolate Selection"
+                            }
+                        }
+                    }
+                    input {
+                        class: "border border-gray-300 rounded px-2 py-1 text-sm",
+                        r#type: "text",
+                        placeholder: "Find node by id or name",
+                        value: "{search_query}",
+                        oninput: move |evt| search_query.set(evt.value()),
+                    }
+                }
+                // Search results; clicking one pans to it
+                div { class: "flex flex-wrap gap-1 mt-1",
+                    for node_idx in workflow.read().search(&search_query.read()).into_iter().take(8) {
+                        button {
+                            key: "{node_idx.index()}",
+                            class: "px-2 py-0.5 rounded text-xs bg-blue-100 hover:bg-blue-200",
+                            onclick: move |_| pan_to(node_idx),
+                            "{workflow.read().graph[node_idx].name}"
+                        }
+                    }
+                }
+
+                // Tab navigation
+                div { class: "flex border-b border-gray-200 mb-4",
+                    {
+                        let tab_class = if *active_tab.read() == Tab::Node {
+                            "py-2 px-4 font-medium text-sm text-blue-600 border-b-2 border-blue-600"
+                        } else {
+                            "py-2 px-4 font-medium text-sm text-gray-500 hover:text-gray-700"
+                        };
+                        rsx! {
+                            button { class: "{tab_class}", onclick: switch_to_node_tab, "Nodes" }
+                        }
+                    }
+                    {
+                        let tab_class = if *active_tab.read() == Tab::Edge {
+                            "py-2 px-4 font-medium text-sm text-blue-600 border-b-2 border-blue-600"
+                        } else {
+                            "py-2 px-4 font-medium text-sm text-gray-500 hover:text-gray-700"
+                        };
+                        rsx! {
+                            button { class: "{tab_class}", onclick: switch_to_edge_tab, "Edges" }
+                        }
+                    }
+                    {
+                        let tab_class = if *active_tab.read() == Tab::Workflow {
+                            "py-2 px-4 font-medium text-sm text-blue-600 border-b-2 border-blue-600"
+                        } else {
+                            "py-2 px-4 font-medium text-sm text-gray-500 hover:text-gray-700"
+                        };
+                        rsx! {
+                            button { class: "{tab_class}", onclick: switch_to_workflow_tab, "Workflow" }
+                        }
+                    }
+                }
+
+                // Tab content
+                if *active_tab.read() == Tab::Node {
+                    // Node operations
+                    div { class: "flex space-x-2 mt-2",
+                        {
+                            let btn_class = if *editing_mode.read() == EditingMode::Normal {
+                                "px-3 py-1 rounded text-sm bg-blue-500 text-white"
+                            } else {
+                                "px-3 py-1 rounded text-sm bg-gray-200"
+                            };
+                            rsx! {
+                                button { class: "{btn_class}", onclick: set_normal_mode, "Normal" }
+                            }
+                        }
+                        {
+                            let btn_class = if *editing_mode.read() == EditingMode::AddNode {
+                                "px-3 py-1 rounded text-sm bg-green-500 text-white"
+                            } else {
+                                "px-3 py-1 rounded text-sm bg-gray-200"
+                            };
+                            rsx! {
+                                button { class: "{btn_class}", onclick: set_add_node_mode, "Add Node" }
+                            }
+                        }
+                        {
+                            let btn_class = if *editing_mode.read() == EditingMode::DeleteNode {
+                                "px-3 py-1 rounded text-sm bg-red-500 text-white"
+                            } else {
+                                "px-3 py-1 rounded text-sm bg-gray-200"
+                            };
+                            rsx! {
+                                button { class: "{btn_class}", onclick: set_delete_node_mode, "Delete Node" }
+                            }
+                        }
+                        {
+                            let has_node = matches!(*current_selection.read(), Selection::Node(_));
+                            let btn_class = if has_node {
+                                "px-3 py-1 rounded text-sm bg-gray-200"
+                            } else {
+                                "px-3 py-1 rounded text-sm bg-gray-100 text-gray-400 cursor-not-allowed"
+                            };
+                            rsx! {
+                                button {
+                                    class: "{btn_class}",
+                                    disabled: !has_node,
+                                    onclick: duplicate_selected_node,
+                                    "Duplicate"
+                                }
+                            }
+                        }
+                        {
+                            let actions: std::collections::BTreeSet<String> = workflow
+                                .read()
+                                .graph
+                                .node_weights()
+                                .filter_map(|node| match &node.kind {
+                                    NodeKind::Action(action) => Some(action.to_string()),
+                                    NodeKind::SubGraph(_) => None,
+                                })
+                                .collect();
+                            rsx! {
+                                select {
+                                    class: "border border-gray-300 rounded px-2 py-1 text-sm",
+                                    value: "{action_filter}",
+                                    onchange: move |evt| action_filter.set(evt.value()),
+                                    option { value: "", "All actions" }
+                                    for action in actions {
+                                        option { key: "{action}", value: "{action}", "{action}" }
+                                    }
+                                }
+                                if !action_filter.read().is_empty() {
+                                    span { class: "self-center text-sm text-gray-600",
+                                        "{action_matches.len()} matching"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                } else if *active_tab.read() == Tab::Workflow {
+                    // Workflow metadata and the actions it runs
+                    div { class: "mt-2 text-sm",
+                        p { class: "mb-2 text-gray-600",
+                            "{workflow.read().name} · {workflow.read().graph.node_count()} nodes · {workflow.read().graph.edge_count()} edges"
+                        }
+                        table { class: "border-collapse",
+                            thead {
+                                tr {
+                                    th { class: "pr-4 text-left font-medium", "Action" }
+                                    th { class: "text-right font-medium", "Count" }
+                                }
+                            }
+                            tbody {
+                                for (action, count) in workflow.read().actions_summary() {
+                                    tr { key: "{action}",
+                                        td { class: "pr-4", "{action}" }
+                                        td { class: "text-right", "{count}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    // Edge operations
+                    div { class: "flex space-x-2 mt-2",
+                        {
+                            let btn_class = if *editing_mode.read() == EditingMode::Normal {
+                                "px-3 py-1 rounded text-sm bg-blue-500 text-white"
+                            } else {
+                                "px-3 py-1 rounded text-sm bg-gray-200"
+                            };
+                            rsx! {
+                                button { class: "{btn_class}", onclick: set_normal_mode, "Normal" }
+                            }
+                        }
+                        {
+                            let btn_class = if *editing_mode.read() == EditingMode::AddEdge {
+                                "px-3 py-1 rounded text-sm bg-green-500 text-white"
+                            } else {
+                                "px-3 py-1 rounded text-sm bg-gray-200"
+                            };
+                            rsx! {
+                                button { class: "{btn_class}", onclick: set_add_edge_mode, "Add Edge" }
+                            }
+                        }
+                        {
+                            let btn_class = if *editing_mode.read() == EditingMode::ConnectEdge {
+                                "px-3 py-1 rounded text-sm bg-green-500 text-white"
+                            } else {
+                                "px-3 py-1 rounded text-sm bg-gray-200"
+                            };
+                            rsx! {
+                                button { class: "{btn_class}", onclick: set_connect_edge_mode, "Connect Edge" }
+                            }
+                        }
+                        {
+                            let btn_class = if *editing_mode.read() == EditingMode::DeleteEdge {
+                                "px-3 py-1 rounded text-sm bg-red-500 text-white"
+                            } else {
+                                "px-3 py-1 rounded text-sm bg-gray-200"
+                            };
+                            rsx! {
+                                button { class: "{btn_class}", onclick: set_delete_edge_mode, "Delete Edge" }
+                            }
+                        }
+                        {
+                            let btn_class = if *editing_mode.read() == EditingMode::InsertNode {
+                                "px-3 py-1 rounded text-sm bg-green-500 text-white"
+                            } else {
+                                "px-3 py-1 rounded text-sm bg-gray-200"
+                            };
+                            rsx! {
+                                button { class: "{btn_class}", onclick: set_insert_node_mode, "Insert Node" }
+                            }
+                        }
+                    }
+                }
+
+                // Selection info
+                {
+                    let mode_text = match *editing_mode.read() {
+                        EditingMode::Normal => "Normal",
+                        EditingMode::AddEdge => "Add Edge",
+                        EditingMode::ConnectEdge => "Connect Edge",
+                        EditingMode::DeleteEdge => "Delete Edge",
+                        EditingMode::AddNode => "Add Node",
+                        EditingMode::DeleteNode => "Delete Node",
+                        EditingMode::InsertNode => "Insert Node",
+                    };
+                    rsx! {
+                        div { class: "mt-2 text-sm", "Mode: {mode_text} | {selection_info}" }
+                        if matches!(*current_selection.read(), Selection::Node(_)) {
+                            button {
+                                class: "mt-1 px-3 py-1 rounded text-sm bg-gray-200",
+                                onclick: copy_selected_node_yaml,
+                                "Copy as YAML"
+                            }
+                        }
+                        if matches!(*current_selection.read(), Selection::Edge(_)) {
+                            button {
+                                class: "mt-1 px-3 py-1 rounded text-sm bg-gray-200",
+                                onclick: insert_node_on_selected_edge,
+                                "Insert Node"
+                            }
+                            button {
+                                class: "mt-1 ml-1 px-3 py-1 rounded text-sm bg-gray-200",
+                                title: "Shift-click the canvas to bend the selected edge",
+                                onclick: straighten_selected_edge,
+                                "Straighten"
+                            }
+                        }
+                    }
+                }
+                // Selected nodes for edge creation
+                if *editing_mode.read() == EditingMode::AddEdge && !selected_nodes.read().is_empty() {
+                    div { class: "text-sm",
+                        "Selected nodes for edge: {selected_nodes.read().len()} selected"
+                    }
+                }
+            }
+            div {
+                class: "flex flex-1 min-h-0",
+                // Dragging the panel's edge resizes it; the listeners sit here so the pointer can cross the canvas
+                onmousemove: move |evt: Event<MouseData>| {
+                    if let Some(last_x) = resizing_from() {
+                        // The panel sits right of its edge, so moving the pointer left widens it
+                        let x = evt.data().client_coordinates().x;
+                        let width = panel_width() + last_x - x;
+                        panel_width.set(width.clamp(PANEL_MIN_WIDTH, PANEL_MAX_WIDTH));
+                        resizing_from.set(Some(x));
+                    }
+                },
+                onmouseup: move |_| resizing_from.set(None),
+                onmouseleave: move |_| resizing_from.set(None),
+                div {
+                    class: "flex-1 relative border-2 border-gray-300 rounded-lg overflow-hidden",
+                    style: "background-color: {colors.background};",
+                    onresize: move |evt: Event<ResizeData>| {
+                        if let Ok(size) = evt.data().get_content_box_size() {
+                            canvas_size.set((size.width, size.height));
+                            if layout_awaits_size() {
+                                layout_awaits_size.set(false);
+                                let workflow_ref = workflow.peek();
+                                let fitted = layout::default_layout(&workflow_ref, (size.width, size.height).into());
+                                node_positions.set(layout_io::positions_by_id(&workflow_ref, &fitted));
+                            }
+                        }
+                    },
+                    // Hint for an empty canvas; clicks pass through to it
+                    if workflow.read().graph.node_count() == 0 {
+                        div { class: "absolute inset-0 flex items-center justify-center text-gray-400 pointer-events-none",
+                            "No nodes — switch to Add Node mode"
+                        }
+                    }
+                    Canvas {
+                        nodes: canvas_nodes,
+                        edges: canvas_edges,
+                        clusters,
+                        band,
+                        node_radius,
+                        colors,
+                        hit_width: edge_hit_width,
+                        arrow_size,
+                        arrow_style,
+                        cursor: cursor_for_mode(&editing_mode.read()),
+                        view_box: view_box(),
+                        on_mouse_move: handle_mousemove,
+                        on_mouse_up: handle_mouseup,
+                        on_mouse_leave: handle_mouseleave,
+                        on_click: handle_canvas_click,
+                        on_wheel: handle_wheel,
+                        // Double-clicking empty canvas resets the view; nodes stop their own double-clicks
+                        on_double_click: move |_| view_box.set(None),
+                        on_node_drag_start: handle_drag_start,
+                        on_node_click: handle_node_click,
+                        on_node_double_click: handle_node_double_click,
+                        on_node_shift_click: handle_node_shift_click,
+                        on_node_alt_click: handle_node_alt_click,
+                        on_node_rename: handle_node_rename,
+                        on_node_cancel_rename: handle_node_cancel_rename,
+                        on_edge_click: handle_edge_click,
+                        on_edge_hover: move |edge_idx| hovered_edge.set(edge_idx),
+                    }
+
+                    // Node kind legend
+                    div { class: "absolute top-2 right-2 p-2 bg-white bg-opacity-90 border border-gray-300 rounded text-xs text-gray-700",
+                        for (kind, color, count) in legend_entries(&workflow.read().graph) {
+                            div { key: "{kind}", class: "flex items-center space-x-2",
+                                span {
+                                    class: "inline-block w-3 h-3 rounded-full border border-gray-500",
+                                    style: "background-color: {color};",
+                                }
+                                span { "{kind} ({count})" }
+                            }
+                        }
+                    }
+                }
+                // Properties of the selection
+                if properties_open() {
+                    div {
+                        class: "w-1 cursor-col-resize bg-gray-200 hover:bg-gray-400",
+                        title: "Drag to resize the properties panel",
+                        onmousedown: move |evt: Event<MouseData>| {
+                            evt.prevent_default();
+                            resizing_from.set(Some(evt.data().client_coordinates().x));
+                        },
+                    }
+                    div {
+                        class: "p-2 overflow-auto bg-white border-l border-gray-300 text-sm",
+                        style: "width: {panel_width}px;",
+                        div { class: "flex items-center justify-between mb-2",
+                            h3 { class: "font-bold", "Properties" }
+                            button {
+                                class: "px-2 rounded bg-gray-200",
+                                title: "Collapse the properties panel",
+                                onclick: move |_| properties_open.set(false),
+                                "»"
+                            }
+                        }
+                        {
+                            let rows = properties_rows(&current_selection.read());
+                            if rows.is_empty() {
+                                rsx! {
+                                    div { class: "text-gray-500", "Select a node or edge to see its properties" }
+                                }
+                            } else {
+                                rsx! {
+                                    table { class: "w-full",
+                                        for (i, (label, value)) in rows.into_iter().enumerate() {
+                                            tr { key: "{i}",
+                                                td { class: "pr-2 align-top text-gray-600", "{label}" }
+                                                td { class: "font-mono break-all", "{value}" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    button {
+                        class: "px-1 bg-gray-200 border-l border-gray-300 text-sm",
+                        title: "Show the properties panel",
+                        onclick: move |_| properties_open.set(true),
+                        "«"
+                    }
+                }
+            }
+            div { class: "p-4 text-sm text-gray-600",
+                "Dashed edges route features out of a filter or router port. Nodes downstream of the selection are highlighted. Alt-click a node to select everything connected to it. Use tabs to switch between node and edge operations."
+            }
+
+            if confirming_clear() {
+                ConfirmDialog {
+                    message: "Remove every node and edge of this workflow? This can't be undone.",
+                    confirm_label: "Clear Graph",
+                    on_confirm: clear_confirmed,
+                    on_cancel: move |_| confirming_clear.set(false),
+                }
+            }
+        }
+    }
+}
@@ -1,22 +1,115 @@
-use crate::components::{Edge as GraphEdge, Node as GraphNode};
+use crate::components::canvas::{Canvas, CanvasEdge, CanvasNode};
+use crate::components::confirm::ConfirmDialog;
+use crate::components::context_menu::{
+    apply_menu_action, ContextMenu, MenuAction, MenuItem, MenuTarget,
+};
+use crate::components::edge::{ArrowStyle, DEFAULT_ARROW_SIZE, DEFAULT_HIT_WIDTH};
+use crate::components::layout;
+use crate::components::svg_export::{download_svg, render_svg, SvgStyle};
+use crate::components::theme::{colors_for, Theme};
+use crate::components::viewport::ViewBox;
 use dioxus::prelude::*;
 use petgraph::graph::DiGraph;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
+use std::str::FromStr;
 
-#[derive(PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
 pub struct Point {
     pub x: f64,
     pub y: f64,
 }
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Clone, Debug)]
 pub enum EditingMode {
     Normal,
     AddEdge,
+    /// Drag from one node to another to connect them.
+    ConnectEdge,
     DeleteEdge,
     AddNode,
     DeleteNode,
+    /// Click an edge to split it with a new node. Only offered by `Flow`, which knows how to make nodes.
+    InsertNode,
+}
+
+/// The node whose circle contains `point`, preferring the closest center when circles overlap.
+pub fn node_at(
+    positions: &HashMap<petgraph::graph::NodeIndex, Point>,
+    point: &Point,
+    radius: f64,
+) -> Option<petgraph::graph::NodeIndex> {
+    positions
+        .iter()
+        .map(|(node_idx, position)| {
+            let distance = ((position.x - point.x).powi(2) + (position.y - point.y).powi(2)).sqrt();
+            (*node_idx, distance)
+        })
+        .filter(|(_, distance)| *distance <= radius)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(node_idx, _)| node_idx)
+}
+
+/// The editing mode a keyboard shortcut switches to: `N` adds nodes, `E` adds edges, `D` deletes nodes, `X` deletes
+/// edges and `Escape` goes back to normal. Letters match in either case.
+pub fn mode_for_key(key: &Key) -> Option<EditingMode> {
+    match key {
+        Key::Escape => Some(EditingMode::Normal),
+        Key::Character(c) => match c.to_ascii_lowercase().as_str() {
+            "n" => Some(EditingMode::AddNode),
+            "e" => Some(EditingMode::AddEdge),
+            "d" => Some(EditingMode::DeleteNode),
+            "x" => Some(EditingMode::DeleteEdge),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// CSS cursor shown over the empty canvas in `mode`, hinting at what a click there does.
+pub fn cursor_for_mode(mode: &EditingMode) -> &'static str {
+    match mode {
+        EditingMode::Normal => "default",
+        EditingMode::AddNode => "crosshair",
+        EditingMode::AddEdge
+        | EditingMode::ConnectEdge
+        | EditingMode::DeleteEdge
+        | EditingMode::InsertNode => "pointer",
+        EditingMode::DeleteNode => "not-allowed",
+    }
+}
+
+/// Adds an edge from `source` to `target` whose weight is `weight` parsed into the edge type, or the type's default
+/// when it doesn't parse, as for edge types that aren't numbers.
+pub fn add_weighted_edge<N, E>(
+    graph: &mut DiGraph<N, E>,
+    source: petgraph::graph::NodeIndex,
+    target: petgraph::graph::NodeIndex,
+    weight: i32,
+) -> petgraph::graph::EdgeIndex
+where
+    E: Default + FromStr,
+{
+    let weight = weight.to_string().parse().unwrap_or_default();
+    graph.add_edge(source, target, weight)
+}
+
+/// Why an edge from `source` to `target` can't be added, or `None` if it can: the graph already has that edge, or it
+/// would connect a node to itself while `allow_self_loops` is off.
+pub fn edge_rejection<N, E>(
+    graph: &DiGraph<N, E>,
+    source: petgraph::graph::NodeIndex,
+    target: petgraph::graph::NodeIndex,
+    allow_self_loops: bool,
+) -> Option<&'static str> {
+    if source == target && !allow_self_loops {
+        Some("An edge can't connect a node to itself")
+    } else if graph.find_edge(source, target).is_some() {
+        Some("That edge already exists")
+    } else {
+        None
+    }
 }
 
 #[derive(PartialEq, Clone)]
@@ -26,20 +119,110 @@ pub enum Selection<N, E> {
     None,
 }
 
+/// The selection after `clicked` is clicked in Normal mode: clicking the node or edge that is already selected clears
+/// the selection, anything else becomes the selection. Nodes and edges are compared by index, so one whose data was
+/// edited since it was selected still toggles off.
+pub fn toggle_selection<N, E>(
+    current: &Selection<N, E>,
+    clicked: Selection<N, E>,
+) -> Selection<N, E> {
+    match (current, &clicked) {
+        (Selection::Node((selected, _)), Selection::Node((node_idx, _)))
+            if selected == node_idx =>
+        {
+            Selection::None
+        }
+        (Selection::Edge((selected, _)), Selection::Edge((edge_idx, _)))
+            if selected == edge_idx =>
+        {
+            Selection::None
+        }
+        _ => clicked,
+    }
+}
+
+/// Whether dragging the node at `node_idx` should leave it where it is, because it is anchored.
+pub fn drag_ignored(
+    node_idx: petgraph::graph::NodeIndex,
+    anchored: &HashSet<petgraph::graph::NodeIndex>,
+) -> bool {
+    anchored.contains(&node_idx)
+}
+
+/// The source and target of the hovered edge, which are highlighted while it is hovered. Nothing when no edge is
+/// hovered or it is no longer in the graph.
+pub fn hovered_endpoints<N, E>(
+    graph: &DiGraph<N, E>,
+    hovered: Option<petgraph::graph::EdgeIndex>,
+) -> Vec<petgraph::graph::NodeIndex> {
+    hovered
+        .and_then(|edge_idx| graph.edge_endpoints(edge_idx))
+        .map(|(source, target)| vec![source, target])
+        .unwrap_or_default()
+}
+
+/// Forgets the position and anchor of the node removed from `graph` at `removed`. petgraph fills the freed index
+/// with the graph's last node, so that node's position and anchor move along with it.
+pub fn forget_removed_node<N, E>(
+    graph: &DiGraph<N, E>,
+    positions: &mut HashMap<petgraph::graph::NodeIndex, Point>,
+    anchored: &mut HashSet<petgraph::graph::NodeIndex>,
+    removed: petgraph::graph::NodeIndex,
+) {
+    // The index the last node had before the removal
+    let moved = petgraph::graph::NodeIndex::new(graph.node_count());
+    positions.remove(&removed);
+    anchored.remove(&removed);
+    if let Some(position) = positions.remove(&moved) {
+        positions.insert(removed, position);
+    }
+    if anchored.remove(&moved) {
+        anchored.insert(removed);
+    }
+}
+
+/// Removes every node and edge of `graph`, forgets their positions and clears the selection.
+pub fn clear_graph<N, E>(
+    graph: &mut DiGraph<N, E>,
+    positions: &mut HashMap<petgraph::graph::NodeIndex, Point>,
+    selection: &mut Selection<N, E>,
+) {
+    graph.clear();
+    positions.clear();
+    *selection = Selection::None;
+}
+
 #[derive(PartialEq, Clone)]
 pub enum Tab {
     Node,
     Edge,
+    /// Facts about the workflow as a whole, only shown by `Flow`.
+    Workflow,
 }
 
 #[component]
 pub fn Graph<N, E>(
     mut graph: Signal<DiGraph<N, E>>,
     initial_positions: Option<Signal<HashMap<petgraph::graph::NodeIndex, Point>>>,
+    #[props(default = 25.0)] node_radius: f64,
+    #[props(default)] theme: Theme,
+    /// Width of the area around each edge that catches clicks.
+    #[props(default = DEFAULT_HIT_WIDTH)]
+    edge_hit_width: f64,
+    /// Length of the sides of each edge's arrowhead.
+    #[props(default = DEFAULT_ARROW_SIZE)]
+    arrow_size: f64,
+    #[props(default)] arrow_style: ArrowStyle,
+    /// Weight new edges start with; the Edge tab can change it.
+    #[props(default = 1)]
+    default_edge_weight: i32,
+    /// Lets Add Edge connect a node to itself by clicking it twice.
+    #[props(default)]
+    allow_self_loops: bool,
 ) -> Element
 where
-    N: Clone + Display + Default + 'static,
-    E: Clone + Display + Default + 'static,
+    N: Clone + Display + Default + FromStr + 'static,
+    E: Clone + Display + Default + FromStr + 'static,
 {
     // Store node positions in a signal for dragging, using provided positions or default layout
     let mut node_positions = use_signal(move || {
@@ -48,44 +231,82 @@ where
         let mut positions = std::collections::HashMap::new();
 
         if node_count > 0 {
-            // Use provided positions if available, otherwise create default circular layout
-            if let Some(initial_pos) = initial_positions {
-                positions = (*initial_pos.read()).clone();
+            // Use provided positions if available, placing any nodes they miss, otherwise create default circular layout
+            if let Some(initial_pos) = initial_positions.filter(|pos| !pos.read().is_empty()) {
+                positions = layout::incremental(&initial_pos.read(), &*graph_ref);
             } else {
-                let radius = 150.0;
-                let center_x = 300.0;
-                let center_y = 200.0;
-
-                for (i, node_idx) in graph_ref.node_indices().enumerate() {
-                    let angle = 2.0 * std::f64::consts::PI * i as f64 / node_count as f64;
-                    let x = center_x + radius * angle.cos();
-                    let y = center_y + radius * angle.sin();
-
-                    positions.insert(node_idx, Point { x, y });
-                }
+                let (width, height) = layout::DEFAULT_CANVAS_SIZE;
+                positions = layout::circular(&graph_ref, width, height);
             }
         }
 
         positions
     });
 
+    // Weight given to edges added from now on
+    let mut edge_weight = use_signal(|| default_edge_weight);
+
+    // Size of the canvas element, kept up to date by `onresize`
+    let mut canvas_size = use_signal(|| layout::DEFAULT_CANVAS_SIZE);
+
+    // Whether the default circle still has to be fitted to the canvas once it is first measured
+    let mut circle_awaits_size =
+        use_signal(move || initial_positions.is_none_or(|pos| pos.peek().is_empty()));
+
+    // Keep the provided positions in step with the canvas, so the parent can save them
+    use_effect(move || {
+        let positions = node_positions.read().clone();
+        if let Some(mut shared) = initial_positions {
+            shared.set(positions);
+        }
+    });
+
     // Track which node is currently being dragged
     let mut dragging_node = use_signal(|| None::<petgraph::graph::NodeIndex>);
 
+    // Track an edge being dragged out in connect mode: the source node and the current pointer position
+    let mut connecting = use_signal(|| None::<(petgraph::graph::NodeIndex, Point)>);
+
     // Track the current editing mode
     let mut editing_mode = use_signal(|| EditingMode::Normal);
 
     // Track selected nodes for edge creation
     let mut selected_nodes = use_signal(|| Vec::<petgraph::graph::NodeIndex>::new());
 
+    // Why the last edge couldn't be added, shown until the next edge is attempted or the mode changes
+    let mut edge_notice = use_signal(|| None::<&'static str>);
+
     // Track current selection (for properties panel)
     let mut current_selection = use_signal(|| Selection::<N, E>::None);
 
     // Track current active tab
     let mut active_tab = use_signal(|| Tab::Node);
 
+    // Track the open context menu: what it was opened on and where
+    let mut context_menu = use_signal(|| None::<(MenuTarget, Point)>);
+
+    // Edge under the pointer; its endpoints are highlighted
+    let mut hovered_edge = use_signal(|| None::<petgraph::graph::EdgeIndex>);
+
+    // Nodes locked in place from the context menu
+    let mut anchored = use_signal(HashSet::<petgraph::graph::NodeIndex>::new);
+
+    // Whether the dialog confirming "Clear Graph" is open
+    let mut confirming_clear = use_signal(|| false);
+
     let handle_mousemove = move |event: MouseEvent| {
-        if let Some(node_idx) = *dragging_node.read() {
+        let connecting_from = connecting.read().as_ref().map(|(source, _)| *source);
+        if let Some(source) = connecting_from {
+            let rect = event.data().element_coordinates();
+            let pointer = Point {
+                x: rect.x as f64,
+                y: rect.y as f64,
+            };
+            *connecting.write() = Some((source, pointer));
+        } else if let Some(node_idx) = *dragging_node.read() {
+            if drag_ignored(node_idx, &anchored.read()) {
+                return;
+            }
             let rect = event.data().element_coordinates();
             let x = rect.x as f64;
             let y = rect.y as f64;
@@ -97,53 +318,92 @@ where
 
     let handle_mouseup = move |_| {
         *dragging_node.write() = None;
+
+        // Finish a connect drag: released over another node creates the edge, anywhere else cancels it
+        let finished = connecting.write().take();
+        if let Some((source, pointer)) = finished {
+            let target = node_at(&node_positions.read(), &pointer, node_radius);
+            if let Some(target) = target.filter(|target| *target != source) {
+                let rejection = edge_rejection(&graph.read(), source, target, allow_self_loops);
+                if rejection.is_none() {
+                    add_weighted_edge(&mut graph.write(), source, target, edge_weight());
+                }
+                edge_notice.set(rejection);
+            }
+        }
+    };
+
+    let handle_mouseleave = move |_| {
+        *dragging_node.write() = None;
+        *connecting.write() = None;
     };
 
     let handle_drag_start = move |node_idx: petgraph::graph::NodeIndex| {
-        *dragging_node.write() = Some(node_idx);
+        if *editing_mode.read() == EditingMode::ConnectEdge {
+            let start = node_positions.read().get(&node_idx).cloned();
+            if let Some(start) = start {
+                *connecting.write() = Some((node_idx, start));
+            }
+        } else {
+            *dragging_node.write() = Some(node_idx);
+        }
     };
 
     let handle_node_click = move |node_idx: petgraph::graph::NodeIndex| {
         match *editing_mode.read() {
             EditingMode::Normal => {
-                // Select the node for properties panel
-                let graph_ref = graph.read();
-                if let Some(node_data) = graph_ref.node_weight(node_idx) {
-                    *current_selection.write() = Selection::Node((node_idx, node_data.clone()));
+                // Select the node for properties panel, or deselect it if it already is
+                let node_data = graph.read().node_weight(node_idx).cloned();
+                if let Some(node_data) = node_data {
+                    let next = toggle_selection(
+                        &current_selection.read(),
+                        Selection::Node((node_idx, node_data)),
+                    );
+                    current_selection.set(next);
                 }
             }
             EditingMode::AddEdge => {
                 // Add node to selection for edge creation
                 let mut nodes = selected_nodes.write();
-                if !nodes.contains(&node_idx) {
-                    nodes.push(node_idx);
-                }
+                nodes.push(node_idx);
 
-                // If we have two nodes selected, create an edge
+                // If we have two nodes selected, create an edge unless it's a duplicate or an unwanted self loop
                 if nodes.len() == 2 {
                     let source = nodes[0];
                     let target = nodes[1];
 
-                    // Add edge to the graph with a default value
-                    graph.write().add_edge(source, target, E::default());
+                    // Add edge to the graph with the weight set in the Edge tab
+                    let rejection = edge_rejection(&graph.read(), source, target, allow_self_loops);
+                    if rejection.is_none() {
+                        add_weighted_edge(&mut graph.write(), source, target, edge_weight());
+                    }
+                    edge_notice.set(rejection);
 
                     // Clear selection
                     nodes.clear();
                 }
             }
+            EditingMode::ConnectEdge => {
+                // Edges are created by dragging, see `handle_mouseup`
+            }
             EditingMode::DeleteEdge => {
                 // In delete mode, clicking a node doesn't do anything
                 // Edges are deleted by clicking on them directly
             }
-            EditingMode::AddNode => {
-                // In add node mode, clicking doesn't do anything
+            EditingMode::AddNode | EditingMode::InsertNode => {
+                // In add or insert node mode, clicking a node doesn't do anything
             }
             EditingMode::DeleteNode => {
                 // Remove the node from the graph
                 graph.write().remove_node(node_idx);
 
                 // Remove the node from positions
-                node_positions.write().remove(&node_idx);
+                forget_removed_node(
+                    &graph.read(),
+                    &mut node_positions.write(),
+                    &mut anchored.write(),
+                    node_idx,
+                );
 
                 // Clear selection
                 *current_selection.write() = Selection::None;
@@ -152,29 +412,45 @@ where
     };
 
     let handle_canvas_click = move |event: MouseEvent| {
+        // Clicking empty canvas clears the selection; nodes and edges stop their own clicks
+        if *editing_mode.read() == EditingMode::Normal {
+            current_selection.set(Selection::None);
+        }
         if *editing_mode.read() == EditingMode::AddNode {
             let rect = event.data().element_coordinates();
-            let x = rect.x as f64;
-            let y = rect.y as f64;
+            let click = Point {
+                x: rect.x as f64,
+                y: rect.y as f64,
+            };
+            let (width, height) = canvas_size();
+            let position = layout::new_node_position(
+                &node_positions.read(),
+                click,
+                &ViewBox::new(width, height),
+            );
 
             // Add a new node to the graph with a default value
             let new_node_idx = graph.write().add_node(N::default());
 
             // Add the new node's position
-            node_positions.write().insert(new_node_idx, Point { x, y });
+            node_positions.write().insert(new_node_idx, position);
         }
     };
 
     let handle_edge_click = move |edge_idx: petgraph::graph::EdgeIndex| {
         match *editing_mode.read() {
             EditingMode::Normal => {
-                // Select the edge for properties panel
-                let graph_ref = graph.read();
-                if let Some(edge_data) = graph_ref.edge_weight(edge_idx) {
-                    *current_selection.write() = Selection::Edge((edge_idx, edge_data.clone()));
+                // Select the edge for properties panel, or deselect it if it already is
+                let edge_data = graph.read().edge_weight(edge_idx).cloned();
+                if let Some(edge_data) = edge_data {
+                    let next = toggle_selection(
+                        &current_selection.read(),
+                        Selection::Edge((edge_idx, edge_data)),
+                    );
+                    current_selection.set(next);
                 }
             }
-            EditingMode::AddEdge => {
+            EditingMode::AddEdge | EditingMode::ConnectEdge => {
                 // Do nothing in add edge mode
             }
             EditingMode::DeleteEdge => {
@@ -187,33 +463,131 @@ where
             EditingMode::AddNode => {
                 // In add node mode, clicking doesn't do anything
             }
-            EditingMode::DeleteNode => {
-                // In delete node mode, clicking an edge doesn't do anything
+            EditingMode::DeleteNode | EditingMode::InsertNode => {
+                // In delete node mode, clicking an edge doesn't do anything; Graph has no insert node mode
             }
         }
     };
 
-    let set_normal_mode = move |_| {
-        *editing_mode.write() = EditingMode::Normal;
-        selected_nodes.write().clear();
+    let handle_node_context_menu =
+        move |(node_idx, position): (petgraph::graph::NodeIndex, Point)| {
+            *context_menu.write() = Some((MenuTarget::Node(node_idx), position));
+        };
+
+    let handle_edge_context_menu =
+        move |(edge_idx, position): (petgraph::graph::EdgeIndex, Point)| {
+            *context_menu.write() = Some((MenuTarget::Edge(edge_idx), position));
+        };
+
+    let handle_menu_select = move |item: MenuItem| {
+        let Some((target, _)) = context_menu() else {
+            return;
+        };
+        *context_menu.write() = None;
+
+        match item {
+            MenuItem::Delete => {
+                apply_menu_action(&mut *graph.write(), MenuAction::Delete(target));
+                if let MenuTarget::Node(node_idx) = target {
+                    forget_removed_node(
+                        &graph.read(),
+                        &mut node_positions.write(),
+                        &mut anchored.write(),
+                        node_idx,
+                    );
+                }
+                *current_selection.write() = Selection::None;
+            }
+            MenuItem::Rename(label) => {
+                apply_menu_action(&mut *graph.write(), MenuAction::Rename(target, label));
+                *current_selection.write() = Selection::None;
+            }
+            MenuItem::AddOutgoingEdge => {
+                // The clicked node becomes the source; the next node clicked completes the edge
+                if let MenuTarget::Node(node_idx) = target {
+                    *active_tab.write() = Tab::Edge;
+                    *editing_mode.write() = EditingMode::AddEdge;
+                    *selected_nodes.write() = vec![node_idx];
+                }
+            }
+            MenuItem::ToggleAnchor => {
+                if let MenuTarget::Node(node_idx) = target {
+                    let mut anchored = anchored.write();
+                    if !anchored.remove(&node_idx) {
+                        anchored.insert(node_idx);
+                    }
+                }
+            }
+        }
     };
 
-    let set_add_edge_mode = move |_| {
-        *editing_mode.write() = EditingMode::AddEdge;
-        selected_nodes.write().clear();
+    // Switching to normal or an edge mode drops the nodes picked for a new edge; any mode switch drops the notice
+    let mut set_mode = move |mode: EditingMode| {
+        if !matches!(mode, EditingMode::AddNode | EditingMode::DeleteNode) {
+            selected_nodes.write().clear();
+        }
+        edge_notice.set(None);
+        *editing_mode.write() = mode;
     };
 
-    let set_delete_edge_mode = move |_| {
-        *editing_mode.write() = EditingMode::DeleteEdge;
-        selected_nodes.write().clear();
+    let set_normal_mode = move |_| set_mode(EditingMode::Normal);
+    let set_add_edge_mode = move |_| set_mode(EditingMode::AddEdge);
+    let set_connect_edge_mode = move |_| set_mode(EditingMode::ConnectEdge);
+    let set_delete_edge_mode = move |_| set_mode(EditingMode::DeleteEdge);
+    let set_add_node_mode = move |_| set_mode(EditingMode::AddNode);
+    let set_delete_node_mode = move |_| set_mode(EditingMode::DeleteNode);
+
+    // Mode shortcuts, also showing the tab the mode's buttons are on. Key presses only reach the canvas while it has
+    // focus, so typing into the text inputs outside of it never switches modes
+    let handle_keydown = move |event: KeyboardEvent| {
+        // Leave browser shortcuts such as Ctrl+D alone
+        if event
+            .modifiers()
+            .intersects(Modifiers::CONTROL | Modifiers::META | Modifiers::ALT)
+        {
+            return;
+        }
+        let Some(mode) = mode_for_key(&event.key()) else {
+            return;
+        };
+        event.prevent_default();
+        match mode {
+            EditingMode::AddNode | EditingMode::DeleteNode => *active_tab.write() = Tab::Node,
+            EditingMode::AddEdge
+            | EditingMode::ConnectEdge
+            | EditingMode::DeleteEdge
+            | EditingMode::InsertNode => *active_tab.write() = Tab::Edge,
+            EditingMode::Normal => {}
+        }
+        set_mode(mode);
     };
 
-    let set_add_node_mode = move |_| {
-        *editing_mode.write() = EditingMode::AddNode;
+    let colors = colors_for(theme);
+
+    let export_svg = move |_| {
+        let style = SvgStyle {
+            node_radius,
+            colors,
+            arrow_size,
+            arrow_style,
+            ..Default::default()
+        };
+        let svg = render_svg(&*graph.read(), &node_positions.read(), &style);
+        download_svg(svg, "graph.svg");
     };
 
-    let set_delete_node_mode = move |_| {
-        *editing_mode.write() = EditingMode::DeleteNode;
+    let clear_confirmed = move |_| {
+        clear_graph(
+            &mut graph.write(),
+            &mut node_positions.write(),
+            &mut current_selection.write(),
+        );
+        selected_nodes.write().clear();
+        anchored.write().clear();
+        *dragging_node.write() = None;
+        *connecting.write() = None;
+        *context_menu.write() = None;
+        confirming_clear.set(false);
     };
 
     let switch_to_node_tab = move |_| {
@@ -237,6 +611,63 @@ where
         Selection::None => "No selection".to_string(),
     };
 
+    // Ends of the hovered edge
+    let hover_highlight = hovered_endpoints(&graph.read(), hovered_edge());
+
+    // Placed nodes and the edges between them, as the canvas draws them
+    let canvas_nodes: Vec<CanvasNode> = {
+        let graph_ref = graph.read();
+        let positions_ref = node_positions.read();
+        graph_ref
+            .node_indices()
+            .filter_map(|node_idx| {
+                Some(CanvasNode {
+                    node_idx,
+                    position: positions_ref.get(&node_idx)?.clone(),
+                    label: graph_ref[node_idx].to_string(),
+                    colors,
+                    is_selected: matches!(
+                        *current_selection.read(),
+                        Selection::Node((selected_idx, _)) if selected_idx == node_idx
+                    ),
+                    is_highlighted: hover_highlight.contains(&node_idx),
+                    is_anchored: anchored.read().contains(&node_idx),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    };
+    let canvas_edges: Vec<CanvasEdge> = {
+        let graph_ref = graph.read();
+        let positions_ref = node_positions.read();
+        graph_ref
+            .edge_indices()
+            .filter_map(|edge_idx| {
+                let (source, target) = graph_ref.edge_endpoints(edge_idx)?;
+                Some(CanvasEdge {
+                    edge_idx,
+                    source_pos: positions_ref.get(&source)?.clone(),
+                    target_pos: positions_ref.get(&target)?.clone(),
+                    label: graph_ref[edge_idx].to_string(),
+                    colors,
+                    is_selected: matches!(
+                        *current_selection.read(),
+                        Selection::Edge((selected_idx, _)) if selected_idx == edge_idx
+                    ),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    };
+
+    // Rubber band from the source of an edge being dragged out to the pointer
+    let band = connecting.read().as_ref().and_then(|(source, pointer)| {
+        node_positions
+            .read()
+            .get(source)
+            .map(|start| (start.clone(), pointer.clone()))
+    });
+
     rsx! {
         div { class: "flex flex-col h-screen",
             div { class: "p-4 bg-gray-100",
@@ -244,6 +675,18 @@ where
                 div { class: "mt-2 text-sm text-gray-600",
                     "Generic graph visualization. Drag nodes to reposition them."
                 }
+                div { class: "flex space-x-2 mt-2",
+                    button {
+                        class: "px-3 py-1 rounded text-sm bg-gray-200",
+                        onclick: export_svg,
+                        "Export SVG"
+                    }
+                    button {
+                        class: "px-3 py-1 rounded text-sm bg-gray-200",
+                        onclick: move |_| confirming_clear.set(true),
+                        "Clear Graph"
+                    }
+                }
 
                 // Tab navigation
                 div { class: "flex border-b border-gray-200 mb-4",
@@ -327,6 +770,16 @@ where
                                 button { class: "{btn_class}", onclick: set_add_edge_mode, "Add Edge" }
                             }
                         }
+                        {
+                            let btn_class = if *editing_mode.read() == EditingMode::ConnectEdge {
+                                "px-3 py-1 rounded text-sm bg-green-500 text-white"
+                            } else {
+                                "px-3 py-1 rounded text-sm bg-gray-200"
+                            };
+                            rsx! {
+                                button { class: "{btn_class}", onclick: set_connect_edge_mode, "Connect Edge" }
+                            }
+                        }
                         {
                             let btn_class = if *editing_mode.read() == EditingMode::DeleteEdge {
                                 "px-3 py-1 rounded text-sm bg-red-500 text-white"
@@ -337,6 +790,20 @@ where
                                 button { class: "{btn_class}", onclick: set_delete_edge_mode, "Delete Edge" }
                             }
                         }
+                        label { class: "flex items-center space-x-1 text-sm",
+                            span { "Weight" }
+                            input {
+                                class: "border border-gray-300 rounded px-2 py-1 w-20",
+                                r#type: "number",
+                                value: "{edge_weight}",
+                                // Keep the last valid weight while the field is empty or half typed
+                                oninput: move |evt| {
+                                    if let Ok(weight) = evt.value().parse() {
+                                        edge_weight.set(weight);
+                                    }
+                                },
+                            }
+                        }
                     }
                 }
 
@@ -345,14 +812,19 @@ where
                     let mode_text = match *editing_mode.read() {
                         EditingMode::Normal => "Normal",
                         EditingMode::AddEdge => "Add Edge",
+                        EditingMode::ConnectEdge => "Connect Edge",
                         EditingMode::DeleteEdge => "Delete Edge",
                         EditingMode::AddNode => "Add Node",
                         EditingMode::DeleteNode => "Delete Node",
+                        EditingMode::InsertNode => "Insert Node",
                     };
                     rsx! {
                         div { class: "mt-2 text-sm", "Mode: {mode_text} | {selection_info}" }
                     }
                 }
+                if let Some(notice) = edge_notice() {
+                    div { class: "text-sm text-red-600", "{notice}" }
+                }
                 // Selected nodes for edge creation
                 if *editing_mode.read() == EditingMode::AddEdge && !selected_nodes.read().is_empty() {
                     div { class: "text-sm",
@@ -360,81 +832,82 @@ where
                     }
                 }
             }
-            div { class: "flex-1 relative border-2 border-gray-300 rounded-lg overflow-hidden bg-white",
-                svg {
-                    class: "absolute top-0 left-0 w-full h-full",
-                    onmousemove: handle_mousemove,
-                    onmouseup: handle_mouseup,
-                    onmouseleave: handle_mouseup,
-                    onclick: handle_canvas_click,
-                    // Draw edges with arrows (connecting nodes based on current positions)
-                    for edge_idx in graph.read().edge_indices() {
-                        {
-                            let graph_ref = graph.read();
-                            let positions_ref = node_positions.read();
-                            let (source, target) = graph_ref.edge_endpoints(edge_idx).unwrap();
-                            let source_pos = positions_ref.get(&source);
-                            let target_pos = positions_ref.get(&target);
-
-                            if let (Some(source_pos), Some(target_pos)) = (source_pos, target_pos) {
-                                let edge_data = graph_ref[edge_idx].clone();
-                                rsx! {
-                                    GraphEdge {
-                                        key: "{edge_idx.index()}",
-                                        source_pos: source_pos.clone(),
-                                        target_pos: target_pos.clone(),
-                                        weight: 1, // Default weight for visualization
-                                        edge_idx,
-                                        on_click: handle_edge_click,
-                                        is_selected: matches!(
-                                            *current_selection.read(),
-                                            Selection::Edge((selected_idx, _))
-                                            if selected_idx == edge_idx
-                                        ),
-                                        edge_label: Some(edge_data.to_string()),
-                                    }
-                                }
-                            } else {
-                                rsx! {
-                                    g { key: "{edge_idx.index()}" }
-                                }
-                            }
+            div {
+                class: "flex-1 relative border-2 border-gray-300 rounded-lg overflow-hidden outline-none",
+                style: "background-color: {colors.background};",
+                // Focusable, so it receives the mode shortcuts once clicked
+                tabindex: "0",
+                onkeydown: handle_keydown,
+                onresize: move |evt: Event<ResizeData>| {
+                    if let Ok(size) = evt.data().get_content_box_size() {
+                        canvas_size.set((size.width, size.height));
+                        if circle_awaits_size() {
+                            circle_awaits_size.set(false);
+                            node_positions.set(layout::circular(&graph.peek(), size.width, size.height));
                         }
                     }
+                },
+                // Hint for an empty canvas; clicks pass through to it
+                if graph.read().node_count() == 0 {
+                    div { class: "absolute inset-0 flex items-center justify-center text-gray-400 pointer-events-none",
+                        "No nodes — switch to Add Node mode"
+                    }
+                }
+                Canvas {
+                    nodes: canvas_nodes,
+                    edges: canvas_edges,
+                    band,
+                    node_radius,
+                    colors,
+                    hit_width: edge_hit_width,
+                    arrow_size,
+                    arrow_style,
+                    cursor: cursor_for_mode(&editing_mode.read()),
+                    on_mouse_move: handle_mousemove,
+                    on_mouse_up: handle_mouseup,
+                    on_mouse_leave: handle_mouseleave,
+                    on_click: handle_canvas_click,
+                    on_node_drag_start: handle_drag_start,
+                    on_node_click: handle_node_click,
+                    on_node_context_menu: handle_node_context_menu,
+                    on_edge_click: handle_edge_click,
+                    on_edge_context_menu: handle_edge_context_menu,
+                    on_edge_hover: move |edge_idx| hovered_edge.set(edge_idx),
+                }
+            }
+            div { class: "p-4 text-sm text-gray-600",
+                "Generic directed graph visualization. Drag nodes to reposition them. Use tabs to switch between node and edge operations. Right-click a node or edge for more actions."
+            }
 
-                    // Draw nodes
-                    for node_idx in graph.read().node_indices() {
-                        {
-                            let graph_ref = graph.read();
-                            let positions_ref = node_positions.read();
-                            if let Some(position) = positions_ref.get(&node_idx) {
-                                let node_data = graph_ref[node_idx].clone();
-                                rsx! {
-                                    GraphNode {
-                                        key: "{node_idx.index()}",
-                                        position: position.clone(),
-                                        label: node_data.to_string(),
-                                        node_idx,
-                                        on_drag_start: handle_drag_start,
-                                        on_click: handle_node_click,
-                                        is_selected: matches!(
-                                            *current_selection.read(),
-                                            Selection::Node((selected_idx, _))
-                                            if selected_idx == node_idx
-                                        ),
-                                    }
-                                }
-                            } else {
-                                rsx! {
-                                    g { key: "{node_idx.index()}" }
-                                }
-                            }
+            // Context menu for the right-clicked node or edge
+            {
+                if let Some((target, position)) = context_menu() {
+                    let label = match target {
+                        MenuTarget::Node(node_idx) => graph.read().node_weight(node_idx).map(|n| n.to_string()),
+                        MenuTarget::Edge(edge_idx) => graph.read().edge_weight(edge_idx).map(|e| e.to_string()),
+                    };
+                    rsx! {
+                        ContextMenu {
+                            target,
+                            position,
+                            label: label.unwrap_or_default(),
+                            anchored: matches!(target, MenuTarget::Node(node_idx) if anchored.read().contains(&node_idx)),
+                            on_select: handle_menu_select,
+                            on_close: move |_| *context_menu.write() = None,
                         }
                     }
+                } else {
+                    rsx! {}
                 }
             }
-            div { class: "p-4 text-sm text-gray-600",
-                "Generic directed graph visualization. Drag nodes to reposition them. Use tabs to switch between node and edge operations."
+
+            if confirming_clear() {
+                ConfirmDialog {
+                    message: "Remove every node and edge? This can't be undone.",
+                    confirm_label: "Clear Graph",
+                    on_confirm: clear_confirmed,
+                    on_cancel: move |_| confirming_clear.set(false),
+                }
             }
         }
     }
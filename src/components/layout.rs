@@ -0,0 +1,193 @@
+use crate::components::graph::Point;
+use crate::components::viewport::ViewBox;
+use crate::workflow::Workflow;
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::Direction;
+use std::collections::HashMap;
+
+/// Closest two node centers may be when a new node is placed, about two node diameters.
+pub const NODE_SPACING: f64 = 100.0;
+
+/// Where a new node goes when nothing in the graph has a position yet.
+const ORIGIN: Point = Point { x: 300.0, y: 200.0 };
+
+/// Size assumed for the canvas until it has been measured.
+pub const DEFAULT_CANVAS_SIZE: (f64, f64) = (800.0, 600.0);
+
+/// Width and height of a canvas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl From<(f64, f64)> for Size {
+    fn from((width, height): (f64, f64)) -> Self {
+        Size { width, height }
+    }
+}
+
+/// Radius of the default circle as a share of the canvas's smaller side, leaving room for the nodes on it.
+const CIRCLE_RADIUS_RATIO: f64 = 0.375;
+
+/// The default layout: `node_count` nodes spaced evenly on a circle centered in a `width` by `height` canvas, keyed
+/// by their position in node order. The first node sits rightmost and the rest follow clockwise on screen.
+pub fn circular_layout(node_count: usize, width: f64, height: f64) -> HashMap<usize, Point> {
+    let radius = CIRCLE_RADIUS_RATIO * width.min(height);
+    let (center_x, center_y) = (width / 2.0, height / 2.0);
+    (0..node_count)
+        .map(|i| {
+            let angle = 2.0 * std::f64::consts::PI * i as f64 / node_count as f64;
+            let point = Point {
+                x: center_x + radius * angle.cos(),
+                y: center_y + radius * angle.sin(),
+            };
+            (i, point)
+        })
+        .collect()
+}
+
+/// [`circular_layout`] for the nodes of `graph`, in index order.
+pub fn circular<N, E>(graph: &DiGraph<N, E>, width: f64, height: f64) -> HashMap<NodeIndex, Point> {
+    let mut layout = circular_layout(graph.node_count(), width, height);
+    graph
+        .node_indices()
+        .enumerate()
+        .filter_map(|(i, node_idx)| Some((node_idx, layout.remove(&i)?)))
+        .collect()
+}
+
+/// Nodes in columns by depth across a `width` by `height` canvas: depth 0 in the leftmost column and each deeper layer
+/// one column further right, the columns spread evenly over the width. Within a column nodes are spread evenly top to
+/// bottom in index order. Nodes without a depth are left out.
+pub fn layered(
+    depths: &HashMap<NodeIndex, usize>,
+    width: f64,
+    height: f64,
+) -> HashMap<NodeIndex, Point> {
+    let layer_count = depths.values().max().map_or(0, |deepest| deepest + 1);
+    let mut layers: Vec<Vec<NodeIndex>> = vec![Vec::new(); layer_count];
+    for (node_idx, depth) in depths {
+        layers[*depth].push(*node_idx);
+    }
+
+    let mut positions = HashMap::new();
+    for (depth, layer) in layers.iter_mut().enumerate() {
+        layer.sort();
+        let x = width * (depth + 1) as f64 / (layer_count + 1) as f64;
+        for (row, node_idx) in layer.iter().enumerate() {
+            let y = height * (row + 1) as f64 / (layer.len() + 1) as f64;
+            positions.insert(*node_idx, Point { x, y });
+        }
+    }
+    positions
+}
+
+/// Where the nodes of `workflow` go when nothing says otherwise: an acyclic workflow is laid out in layers by depth,
+/// see [`Workflow::depths`], so it reads left to right in the direction data flows. A workflow with a cycle has no
+/// depths and gets the circle of [`circular`] instead.
+pub fn default_layout(workflow: &Workflow, canvas: Size) -> HashMap<NodeIndex, Point> {
+    match workflow.depths() {
+        Ok(depths) => layered(&depths, canvas.width, canvas.height),
+        Err(_) => circular(&workflow.graph, canvas.width, canvas.height),
+    }
+}
+
+/// Positions for every node of `graph`. Nodes in `existing` keep their position; each other node, in index order, goes
+/// at the average position of its already placed neighbors, or right of everything placed so far when it has none,
+/// moved outwards in rings until it is at least [`NODE_SPACING`] from every placed node.
+pub fn incremental<N, E>(
+    existing: &HashMap<NodeIndex, Point>,
+    graph: &DiGraph<N, E>,
+) -> HashMap<NodeIndex, Point> {
+    let mut positions: HashMap<NodeIndex, Point> = existing
+        .iter()
+        .filter(|(node_idx, _)| graph.node_weight(**node_idx).is_some())
+        .map(|(node_idx, point)| (*node_idx, point.clone()))
+        .collect();
+
+    for node_idx in graph.node_indices() {
+        if positions.contains_key(&node_idx) {
+            continue;
+        }
+        let neighbors: Vec<&Point> = graph
+            .neighbors_directed(node_idx, Direction::Incoming)
+            .chain(graph.neighbors_directed(node_idx, Direction::Outgoing))
+            .filter_map(|neighbor| positions.get(&neighbor))
+            .collect();
+        let anchor = if neighbors.is_empty() {
+            right_of(positions.values())
+        } else {
+            let count = neighbors.len() as f64;
+            Point {
+                x: neighbors.iter().map(|point| point.x).sum::<f64>() / count,
+                y: neighbors.iter().map(|point| point.y).sum::<f64>() / count,
+            }
+        };
+        let spot = free_spot(&anchor, &positions);
+        positions.insert(node_idx, spot);
+    }
+
+    positions
+}
+
+/// A point one spacing right of the rightmost placed node, level with the topmost, or [`ORIGIN`] when nothing is
+/// placed.
+fn right_of<'a>(placed: impl Iterator<Item = &'a Point>) -> Point {
+    placed
+        .fold(None, |bounds: Option<(f64, f64)>, point| match bounds {
+            Some((max_x, min_y)) => Some((max_x.max(point.x), min_y.min(point.y))),
+            None => Some((point.x, point.y)),
+        })
+        .map_or(ORIGIN, |(max_x, min_y)| Point {
+            x: max_x + NODE_SPACING,
+            y: min_y,
+        })
+}
+
+/// `anchor` if it is clear of every placed node, otherwise the first clear point on rings of growing radius around it.
+fn free_spot(anchor: &Point, placed: &HashMap<NodeIndex, Point>) -> Point {
+    let is_clear = |candidate: &Point| {
+        placed.values().all(|point| {
+            let (dx, dy) = (point.x - candidate.x, point.y - candidate.y);
+            (dx * dx + dy * dy).sqrt() >= NODE_SPACING
+        })
+    };
+    if is_clear(anchor) {
+        return anchor.clone();
+    }
+
+    // There are finitely many placed nodes, so some ring lies clear of all of them
+    for ring in 1.. {
+        let slots = 8 * ring;
+        let radius = NODE_SPACING * ring as f64;
+        for slot in 0..slots {
+            let angle = 2.0 * std::f64::consts::PI * slot as f64 / slots as f64;
+            let candidate = Point {
+                x: anchor.x + radius * angle.cos(),
+                y: anchor.y + radius * angle.sin(),
+            };
+            if is_clear(&candidate) {
+                return candidate;
+            }
+        }
+    }
+    unreachable!("rings grow without bound")
+}
+
+/// Where a node added by clicking at `click` goes: the click itself, except that the first node of an empty graph
+/// starts in the middle of `view`, the part of the drawing on screen.
+pub fn new_node_position(
+    positions: &HashMap<NodeIndex, Point>,
+    click: Point,
+    view: &ViewBox,
+) -> Point {
+    if positions.is_empty() {
+        Point {
+            x: view.x + view.width / 2.0,
+            y: view.y + view.height / 2.0,
+        }
+    } else {
+        click
+    }
+}
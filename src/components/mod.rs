@@ -6,8 +6,38 @@
 pub mod graph;
 pub use graph::Graph;
 
+pub mod flow;
+pub use flow::Flow;
+
 mod node;
 pub use node::Node;
 
 mod edge;
 pub use edge::Edge;
+
+pub mod canvas;
+pub use canvas::Canvas;
+
+pub mod clipboard;
+
+pub mod cluster;
+
+pub mod confirm;
+pub use confirm::ConfirmDialog;
+
+pub mod context_menu;
+pub use context_menu::ContextMenu;
+
+pub mod download;
+
+pub mod layout;
+
+pub mod svg_export;
+
+pub mod theme;
+pub use theme::Theme;
+
+pub mod viewport;
+
+#[cfg(test)]
+mod tests;
@@ -1,15 +1,155 @@
 use crate::components::graph::Point;
+use crate::components::theme::ThemeColors;
 use dioxus::prelude::*;
 
+/// Approximate width of one label character at the node's 10px bold font.
+const LABEL_CHAR_WIDTH: f64 = 6.0;
+
+/// Vertical distance between the lines of a wrapped label.
+pub const LABEL_LINE_HEIGHT: f64 = 12.0;
+
+/// How many label characters fit across a node of the given radius.
+fn label_max_chars(radius: f64) -> usize {
+    (2.0 * radius / LABEL_CHAR_WIDTH).floor().max(1.0) as usize
+}
+
+/// Shortens `label` with an ellipsis so it fits across a node circle of the given radius.
+pub fn truncate_label(label: &str, radius: f64) -> String {
+    let max_chars = label_max_chars(radius);
+    if label.chars().count() <= max_chars {
+        label.to_string()
+    } else {
+        let mut truncated: String = label.chars().take(max_chars - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// Splits `text` into lines of at most `max_chars` characters, breaking between words. A word longer than a line is
+/// cut into line-sized pieces, the last of which may be followed by the next word.
+pub fn wrap_label(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let (line_len, word_len) = (line.chars().count(), word.chars().count());
+        if line.is_empty() && word_len <= max_chars {
+            line.push_str(word);
+        } else if !line.is_empty() && line_len + 1 + word_len <= max_chars {
+            line.push(' ');
+            line.push_str(word);
+        } else {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            let chars: Vec<char> = word.chars().collect();
+            let mut pieces: Vec<String> = chars
+                .chunks(max_chars)
+                .map(|piece| piece.iter().collect())
+                .collect();
+            line = pieces.pop().unwrap_or_default();
+            lines.extend(pieces);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// The lines `label` is drawn on inside a node of the given radius: wrapped to its width and, past the lines that
+/// fit its height, cut short with an ellipsis.
+pub fn label_lines(label: &str, radius: f64) -> Vec<String> {
+    let max_lines = (2.0 * radius * RECT_HEIGHT_RATIO / LABEL_LINE_HEIGHT)
+        .floor()
+        .max(1.0) as usize;
+    let mut lines = wrap_label(label, label_max_chars(radius));
+    if lines.len() > max_lines {
+        let rest = lines.split_off(max_lines - 1).join(" ");
+        lines.push(truncate_label(&rest, radius));
+    }
+    lines
+}
+
+/// Opacity of nodes and edges dimmed to bring others forward.
+pub const DIMMED_OPACITY: f64 = 0.2;
+
+/// Radius of the badge marking an entry node.
+const ENTRY_BADGE_RADIUS: f64 = 7.0;
+
+/// Offset of the entry badge and the anchor lock from the node center along each axis, as a fraction of the radius.
+const BADGE_OFFSET: f64 = 0.75;
+
+/// Half-height of a rectangular node as a fraction of its radius, which is its half-width.
+pub const RECT_HEIGHT_RATIO: f64 = 0.7;
+
+/// Outline a node is drawn with. Every shape spans `2 * radius` across.
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum NodeShape {
+    #[default]
+    Circle,
+    Rect,
+    Diamond,
+}
+
+impl NodeShape {
+    /// Distance from a node's center to its outline along the direction `(dx, dy)`, which need not be normalized.
+    pub fn boundary_distance(self, radius: f64, dx: f64, dy: f64) -> f64 {
+        let length = (dx * dx + dy * dy).sqrt();
+        if length == 0.0 {
+            return radius;
+        }
+        let (unit_x, unit_y) = ((dx / length).abs(), (dy / length).abs());
+        match self {
+            NodeShape::Circle => radius,
+            NodeShape::Rect => {
+                let half_height = radius * RECT_HEIGHT_RATIO;
+                // Whichever side the ray crosses first
+                let to_side = if unit_x > 0.0 { radius / unit_x } else { f64::INFINITY };
+                let to_top = if unit_y > 0.0 { half_height / unit_y } else { f64::INFINITY };
+                to_side.min(to_top)
+            }
+            // |x| + |y| = radius
+            NodeShape::Diamond => radius / (unit_x + unit_y),
+        }
+    }
+}
+
 #[component]
 pub fn Node(
     position: Point,
     label: String,
+    description: Option<String>,
     node_idx: petgraph::graph::NodeIndex,
     on_drag_start: EventHandler<petgraph::graph::NodeIndex>,
     on_click: EventHandler<petgraph::graph::NodeIndex>,
     is_selected: bool,
+    #[props(default)] is_highlighted: bool,
+    /// Marks a starting node of the workflow with a badge.
+    #[props(default)] is_entry: bool,
+    /// Shows a lock at the top right for a node that can't be dragged.
+    #[props(default)] is_anchored: bool,
+    /// Fades the node out, see [`DIMMED_OPACITY`].
+    #[props(default)] is_dimmed: bool,
+    #[props(default = 25.0)] radius: f64,
+    #[props(default)] shape: NodeShape,
+    #[props(default)] colors: ThemeColors,
+    on_context_menu: Option<EventHandler<(petgraph::graph::NodeIndex, Point)>>,
+    on_double_click: Option<EventHandler<petgraph::graph::NodeIndex>>,
+    /// Called instead of `on_click` when shift is held, if set.
+    on_shift_click: Option<EventHandler<petgraph::graph::NodeIndex>>,
+    /// Called instead of `on_click` when alt is held, if set.
+    on_alt_click: Option<EventHandler<petgraph::graph::NodeIndex>>,
+    /// Shows a text box in place of the label, for renaming the node.
+    #[props(default)] is_editing: bool,
+    /// Called with the typed text when the rename box is submitted with Enter or loses focus.
+    on_rename: Option<EventHandler<(petgraph::graph::NodeIndex, String)>>,
+    /// Called when the rename box is left with Escape.
+    on_cancel_rename: Option<EventHandler<petgraph::graph::NodeIndex>>,
 ) -> Element {
+    // Text in the rename box, starting from the current label
+    let mut rename_text = use_signal(|| label.clone());
+
     let handle_node_mousedown = move |event: MouseEvent| {
         event.prevent_default();
         event.stop_propagation();
@@ -19,39 +159,195 @@ pub fn Node(
     let handle_node_click = move |event: MouseEvent| {
         event.prevent_default();
         event.stop_propagation();
-        on_click.call(node_idx);
+        let modifiers = event.modifiers();
+        match (on_shift_click, on_alt_click) {
+            (Some(handler), _) if modifiers.contains(Modifiers::SHIFT) => handler.call(node_idx),
+            (_, Some(handler)) if modifiers.contains(Modifiers::ALT) => handler.call(node_idx),
+            _ => on_click.call(node_idx),
+        }
+    };
+
+    let current_label = label.clone();
+    let handle_node_doubleclick = move |event: MouseEvent| {
+        event.prevent_default();
+        event.stop_propagation();
+        // A rename started by this double click begins from the label as it is now
+        rename_text.set(current_label.clone());
+        if let Some(handler) = on_double_click {
+            handler.call(node_idx);
+        }
     };
 
-    // Determine node color based on selection state
-    let fill_color = if is_selected { "lightgreen" } else { "lightblue" };
-    let stroke_color = if is_selected { "darkgreen" } else { "black" };
+    let handle_node_contextmenu = move |event: MouseEvent| {
+        event.prevent_default();
+        event.stop_propagation();
+        if let Some(handler) = on_context_menu {
+            let coords = event.data().client_coordinates();
+            handler.call((
+                node_idx,
+                Point {
+                    x: coords.x as f64,
+                    y: coords.y as f64,
+                },
+            ));
+        }
+    };
+
+    // Determine node color based on selection and highlight state
+    let fill_color = if is_selected { "lightgreen" } else if is_highlighted { "lightyellow" } else { colors.node_fill };
+    let stroke_color = if is_selected { "darkgreen" } else if is_highlighted { "darkorange" } else { colors.node_stroke };
+    // Selected and highlighted fills are light in both themes
+    let text_color = if is_selected || is_highlighted { "black" } else { colors.node_text };
+    // Label lines, centered vertically on the node
+    let lines = label_lines(&label, radius);
+    let first_line_y = position.y - lines.len().saturating_sub(1) as f64 * LABEL_LINE_HEIGHT / 2.0;
 
     rsx! {
         g {
-            // Draggable node circle
-            circle {
-                cx: "{position.x}",
-                cy: "{position.y}",
-                r: "25",
-                fill: fill_color,
-                stroke: stroke_color,
-                stroke_width: "2",
-                cursor: "move",
-                onmousedown: handle_node_mousedown,
-                onclick: handle_node_click,
+            opacity: if is_dimmed { DIMMED_OPACITY } else { 1.0 },
+            ondoubleclick: handle_node_doubleclick,
+            // Draggable node outline
+            {
+                match shape {
+                    NodeShape::Circle => rsx! {
+                        circle {
+                            cx: "{position.x}",
+                            cy: "{position.y}",
+                            r: "{radius}",
+                            fill: fill_color,
+                            stroke: stroke_color,
+                            stroke_width: "2",
+                            cursor: "move",
+                            onmousedown: handle_node_mousedown,
+                            onclick: handle_node_click,
+                            oncontextmenu: handle_node_contextmenu,
+                        }
+                    },
+                    NodeShape::Rect => rsx! {
+                        rect {
+                            x: "{position.x - radius}",
+                            y: "{position.y - radius * RECT_HEIGHT_RATIO}",
+                            width: "{2.0 * radius}",
+                            height: "{2.0 * radius * RECT_HEIGHT_RATIO}",
+                            rx: "4",
+                            fill: fill_color,
+                            stroke: stroke_color,
+                            stroke_width: "2",
+                            cursor: "move",
+                            onmousedown: handle_node_mousedown,
+                            onclick: handle_node_click,
+                            oncontextmenu: handle_node_contextmenu,
+                        }
+                    },
+                    NodeShape::Diamond => rsx! {
+                        polygon {
+                            points: "{position.x},{position.y - radius} {position.x + radius},{position.y} {position.x},{position.y + radius} {position.x - radius},{position.y}",
+                            fill: fill_color,
+                            stroke: stroke_color,
+                            stroke_width: "2",
+                            cursor: "move",
+                            onmousedown: handle_node_mousedown,
+                            onclick: handle_node_click,
+                            oncontextmenu: handle_node_contextmenu,
+                        }
+                    },
+                }
+            }
+            // Entry badge at the top left, drawn over the outline
+            if is_entry {
+                circle {
+                    cx: "{position.x - radius * BADGE_OFFSET}",
+                    cy: "{position.y - radius * BADGE_OFFSET}",
+                    r: "{ENTRY_BADGE_RADIUS}",
+                    fill: "seagreen",
+                    stroke: "white",
+                    stroke_width: "1.5",
+                    pointer_events: "none",
+                }
+                text {
+                    x: "{position.x - radius * BADGE_OFFSET}",
+                    y: "{position.y - radius * BADGE_OFFSET}",
+                    text_anchor: "middle",
+                    dominant_baseline: "central",
+                    font_size: "7",
+                    fill: "white",
+                    pointer_events: "none",
+                    "▶"
+                }
+            }
+            // Lock glyph for an anchored node
+            if is_anchored {
+                text {
+                    x: "{position.x + radius * BADGE_OFFSET}",
+                    y: "{position.y - radius * BADGE_OFFSET}",
+                    text_anchor: "middle",
+                    dominant_baseline: "central",
+                    font_size: "10",
+                    pointer_events: "none",
+                    "🔒"
+                }
+            }
+            // Node label, or the rename box while editing
+            if is_editing {
+                foreignObject {
+                    x: "{position.x - radius}",
+                    y: "{position.y - 10.0}",
+                    width: "{2.0 * radius}",
+                    height: "20",
+                    input {
+                        r#type: "text",
+                        style: "width: 100%; height: 100%; font-size: 10px; box-sizing: border-box;",
+                        value: "{rename_text}",
+                        autofocus: true,
+                        onmousedown: move |evt| evt.stop_propagation(),
+                        onclick: move |evt| evt.stop_propagation(),
+                        ondoubleclick: move |evt| evt.stop_propagation(),
+                        oninput: move |evt| rename_text.set(evt.value()),
+                        onkeydown: move |evt: KeyboardEvent| {
+                            if evt.key() == Key::Enter {
+                                if let Some(handler) = on_rename {
+                                    handler.call((node_idx, rename_text()));
+                                }
+                            } else if evt.key() == Key::Escape {
+                                if let Some(handler) = on_cancel_rename {
+                                    handler.call(node_idx);
+                                }
+                            }
+                        },
+                        onblur: move |_| {
+                            if let Some(handler) = on_rename {
+                                handler.call((node_idx, rename_text()));
+                            }
+                        },
+                    }
+                }
+            } else {
+                text {
+                    x: "{position.x}",
+                    y: "{position.y}",
+                    text_anchor: "middle",
+                    dominant_baseline: "middle",
+                    font_size: "10",
+                    font_weight: "bold",
+                    fill: text_color,
+                    pointer_events: "none", // So clicks go through to the outline
+                    for (i, line) in lines.iter().enumerate() {
+                        tspan {
+                            key: "{i}",
+                            x: "{position.x}",
+                            y: "{first_line_y + i as f64 * LABEL_LINE_HEIGHT}",
+                            "{line}"
+                        }
+                    }
+                }
             }
-            // Node label
-            text {
-                x: "{position.x}",
-                y: "{position.y}",
-                text_anchor: "middle",
-                dominant_baseline: "middle",
-                font_size: "10",
-                font_weight: "bold",
-                fill: "black",
-                pointer_events: "none", // So clicks go through to the circle
-                "{label}"
+            title {
+                if let Some(description) = description {
+                    "{label}\n{description}"
+                } else {
+                    "{label}"
+                }
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,239 @@
+use crate::components::download::download_file;
+use crate::components::edge::{
+    arrow_polygon, arrowhead_angle, arrowhead_at, dash_array, label_position, polyline_points,
+    svg_points, ArrowStyle, DEFAULT_ARROW_SIZE,
+};
+use crate::components::graph::Point;
+use crate::components::node::{label_lines, NodeShape, LABEL_LINE_HEIGHT, RECT_HEIGHT_RATIO};
+use crate::components::theme::ThemeColors;
+use petgraph::graph::{DiGraph, EdgeIndex, NodeIndex};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Write};
+
+/// Space kept around the drawing so node borders and labels aren't clipped.
+const MARGIN: f64 = 20.0;
+
+/// Escapes text for use inside an XML element or attribute.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// How [`render_svg`] draws a graph: the settings the canvas was given, and the per-node and per-edge details the
+/// parent component works out. Nodes and edges missing from the maps get the defaults of the
+/// [`crate::components::Node`] and [`crate::components::Edge`] components.
+#[derive(PartialEq, Clone, Debug)]
+pub struct SvgStyle {
+    pub node_radius: f64,
+    pub colors: ThemeColors,
+    pub arrow_size: f64,
+    pub arrow_style: ArrowStyle,
+    pub shapes: HashMap<NodeIndex, NodeShape>,
+    /// Fill of each node, in place of the theme's node fill.
+    pub node_fills: HashMap<NodeIndex, &'static str>,
+    /// Bends each edge is routed through, in order.
+    pub waypoints: HashMap<EdgeIndex, Vec<Point>>,
+    pub dashed: HashSet<EdgeIndex>,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        SvgStyle {
+            node_radius: 25.0,
+            colors: ThemeColors::default(),
+            arrow_size: DEFAULT_ARROW_SIZE,
+            arrow_style: ArrowStyle::default(),
+            shapes: HashMap::new(),
+            node_fills: HashMap::new(),
+            waypoints: HashMap::new(),
+            dashed: HashSet::new(),
+        }
+    }
+}
+
+/// Renders the graph as a standalone SVG document, with the same geometry and colors as the
+/// [`crate::components::Node`] and [`crate::components::Edge`] components drawn with `style`. Only inline
+/// presentation attributes are used, so the file renders without the app's stylesheets. Nodes or edges without a
+/// position are left out.
+pub fn render_svg<N, E>(
+    graph: &DiGraph<N, E>,
+    positions: &HashMap<NodeIndex, Point>,
+    style: &SvgStyle,
+) -> String
+where
+    N: Display,
+    E: Display,
+{
+    let node_radius = style.node_radius;
+    let colors = &style.colors;
+    let shape_of = |node_idx: NodeIndex| style.shapes.get(&node_idx).copied().unwrap_or_default();
+
+    // The points each placed edge is drawn through
+    let edges: Vec<(EdgeIndex, Vec<Point>)> = graph
+        .edge_indices()
+        .filter_map(|edge_idx| {
+            let (source, target) = graph.edge_endpoints(edge_idx)?;
+            let points = polyline_points(
+                positions.get(&source)?,
+                positions.get(&target)?,
+                style
+                    .waypoints
+                    .get(&edge_idx)
+                    .map_or(&[][..], Vec::as_slice),
+                shape_of(source),
+                shape_of(target),
+                node_radius,
+            );
+            Some((edge_idx, points))
+        })
+        .collect();
+
+    // Node centers and edge bends, which may reach past the nodes
+    let placed: Vec<&Point> = graph
+        .node_indices()
+        .filter_map(|node_idx| positions.get(&node_idx))
+        .chain(edges.iter().flat_map(|(_, points)| points))
+        .collect();
+    let min_x = placed.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+    let min_y = placed.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+    let max_x = placed.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
+    let max_y = placed.iter().map(|p| p.y).fold(f64::NEG_INFINITY, f64::max);
+    let (view_x, view_y, width, height) = if placed.is_empty() {
+        (0.0, 0.0, 0.0, 0.0)
+    } else {
+        let pad = node_radius + MARGIN;
+        (
+            min_x - pad,
+            min_y - pad,
+            max_x - min_x + 2.0 * pad,
+            max_y - min_y + 2.0 * pad,
+        )
+    };
+
+    let mut svg = String::new();
+    let _ = writeln!(svg, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="{view_x} {view_y} {width} {height}">"#
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect x="{view_x}" y="{view_y}" width="{width}" height="{height}" fill="{}"/>"#,
+        colors.background
+    );
+
+    for (edge_idx, points) in &edges {
+        let Some(end) = points.last() else {
+            continue;
+        };
+        let _ = writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke-linejoin="round" stroke="{}" stroke-width="2" stroke-dasharray="{}"/>"#,
+            svg_points(points),
+            colors.edge,
+            dash_array(style.dashed.contains(edge_idx))
+        );
+        let angle = arrowhead_angle(points);
+        match style.arrow_style {
+            ArrowStyle::Open => {
+                let (arrow_1, arrow_2) = arrowhead_at(end, angle, style.arrow_size);
+                for arrow in [arrow_1, arrow_2] {
+                    let _ = writeln!(
+                        svg,
+                        r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="2"/>"#,
+                        end.x, end.y, arrow.x, arrow.y, colors.edge
+                    );
+                }
+            }
+            ArrowStyle::Filled => {
+                let _ = writeln!(
+                    svg,
+                    r#"<polygon points="{}" fill="{}" stroke="{}" stroke-linejoin="round"/>"#,
+                    svg_points(&arrow_polygon(end, angle, style.arrow_size)),
+                    colors.edge,
+                    colors.edge
+                );
+            }
+        }
+        let label = label_position(points);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" fill="{}" font-size="12" font-weight="bold" font-family="sans-serif">{}</text>"#,
+            label.x,
+            label.y,
+            colors.edge_label,
+            escape_xml(&graph[*edge_idx].to_string())
+        );
+    }
+
+    for node_idx in graph.node_indices() {
+        let Some(position) = positions.get(&node_idx) else {
+            continue;
+        };
+        let fill = style
+            .node_fills
+            .get(&node_idx)
+            .copied()
+            .unwrap_or(colors.node_fill);
+        let paint = format!(
+            r#"fill="{}" stroke="{}" stroke-width="2""#,
+            fill, colors.node_stroke
+        );
+        let _ = match shape_of(node_idx) {
+            NodeShape::Circle => writeln!(
+                svg,
+                r#"<circle cx="{}" cy="{}" r="{}" {paint}/>"#,
+                position.x, position.y, node_radius
+            ),
+            NodeShape::Rect => writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" rx="4" {paint}/>"#,
+                position.x - node_radius,
+                position.y - node_radius * RECT_HEIGHT_RATIO,
+                2.0 * node_radius,
+                2.0 * node_radius * RECT_HEIGHT_RATIO
+            ),
+            NodeShape::Diamond => writeln!(
+                svg,
+                r#"<polygon points="{},{} {},{} {},{} {},{}" {paint}/>"#,
+                position.x,
+                position.y - node_radius,
+                position.x + node_radius,
+                position.y,
+                position.x,
+                position.y + node_radius,
+                position.x - node_radius,
+                position.y
+            ),
+        };
+        // Wrapped the same way as on the canvas, centered vertically
+        let lines = label_lines(&graph[node_idx].to_string(), node_radius);
+        let first_line_y =
+            position.y - lines.len().saturating_sub(1) as f64 * LABEL_LINE_HEIGHT / 2.0;
+        let _ = write!(
+            svg,
+            r#"<text text-anchor="middle" dominant-baseline="middle" font-size="10" font-weight="bold" font-family="sans-serif" fill="{}">"#,
+            colors.node_text
+        );
+        for (i, line) in lines.iter().enumerate() {
+            let _ = write!(
+                svg,
+                r#"<tspan x="{}" y="{}">{}</tspan>"#,
+                position.x,
+                first_line_y + i as f64 * LABEL_LINE_HEIGHT,
+                escape_xml(line)
+            );
+        }
+        svg.push_str("</text>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Asks the webview to save `svg` as a file named `file_name`.
+pub fn download_svg(svg: String, file_name: &str) {
+    download_file(&svg, file_name, "image/svg+xml");
+}
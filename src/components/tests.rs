@@ -0,0 +1,1458 @@
+use super::cluster::{cluster_bounds, Rect, CLUSTER_PADDING};
+use super::context_menu::{apply_menu_action, MenuAction, MenuTarget};
+use super::edge::{
+    arrow_polygon, arrowhead_angle, arrowhead_at, dash_array, edge_endpoints, flow_animation,
+    hit_line, polyline_points, svg_points, ArrowStyle, DEFAULT_ARROW_SIZE, DEFAULT_HIT_WIDTH,
+};
+use super::flow::{
+    apply_edge_click, apply_rename, edge_is_dashed, entry_nodes, focus_set, hidden_nodes,
+    legend_entries, node_shape, properties_rows, workflow_svg_style,
+};
+use super::graph::{
+    add_weighted_edge, clear_graph, cursor_for_mode, drag_ignored, edge_rejection,
+    forget_removed_node, hovered_endpoints, mode_for_key, node_at, toggle_selection, EditingMode,
+    Point, Selection,
+};
+use super::layout::{
+    circular, circular_layout, default_layout, incremental, new_node_position, Size, NODE_SPACING,
+};
+use super::node::{label_lines, truncate_label, wrap_label, NodeShape};
+use super::svg_export::{render_svg, SvgStyle};
+use super::theme::{colors_for, Theme, ThemeColors};
+use super::viewport::{center_on, clamp_viewbox, content_bounds, ViewBox, MAX_ZOOM, MIN_ZOOM};
+use crate::layout_io::positions_by_index;
+use crate::workflow::{
+    Edge as WorkflowEdge, EdgeKind, Node as WorkflowNode, NodeAction, NodeKind, Workflow,
+};
+use petgraph::graph::DiGraph;
+use std::collections::{HashMap, HashSet};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workflow_edges_default_to_data() {
+        let edge = WorkflowEdge::default();
+        assert_eq!(edge.edge_kind, EdgeKind::Data);
+        assert!(!edge_is_dashed(&edge));
+    }
+
+    #[test]
+    fn test_control_edges_render_dashed() {
+        let edge = WorkflowEdge {
+            edge_kind: EdgeKind::Control,
+            ..Default::default()
+        };
+        assert!(edge_is_dashed(&edge));
+        assert_eq!(dash_array(edge_is_dashed(&edge)), "6 4");
+        assert_eq!(dash_array(false), "none");
+    }
+
+    fn city_graph() -> DiGraph<String, i32> {
+        let mut graph = DiGraph::new();
+        let nyc = graph.add_node("New York".to_string());
+        let la = graph.add_node("Los Angeles".to_string());
+        graph.add_edge(nyc, la, 100);
+        graph
+    }
+
+    #[test]
+    fn test_menu_delete_removes_node_and_edge() {
+        let mut graph = city_graph();
+        let nyc = graph.node_indices().next().unwrap();
+        apply_menu_action(&mut graph, MenuAction::Delete(MenuTarget::Node(nyc)));
+        assert_eq!(graph.node_count(), 1);
+        assert_eq!(graph.edge_count(), 0);
+
+        let mut graph = city_graph();
+        let edge = graph.edge_indices().next().unwrap();
+        apply_menu_action(&mut graph, MenuAction::Delete(MenuTarget::Edge(edge)));
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 0);
+    }
+
+    #[test]
+    fn test_menu_rename_parses_label() {
+        let mut graph = city_graph();
+        let nyc = graph.node_indices().next().unwrap();
+        let edge = graph.edge_indices().next().unwrap();
+
+        apply_menu_action(
+            &mut graph,
+            MenuAction::Rename(MenuTarget::Node(nyc), "Boston".to_string()),
+        );
+        apply_menu_action(
+            &mut graph,
+            MenuAction::Rename(MenuTarget::Edge(edge), "42".to_string()),
+        );
+        assert_eq!(graph[nyc], "Boston");
+        assert_eq!(graph[edge], 42);
+
+        // Not a valid i32, so the weight is left alone
+        apply_menu_action(
+            &mut graph,
+            MenuAction::Rename(MenuTarget::Edge(edge), "far".to_string()),
+        );
+        assert_eq!(graph[edge], 42);
+    }
+
+    #[test]
+    fn test_truncate_label_fits_radius() {
+        // A radius of 25 fits 8 characters
+        assert_eq!(truncate_label("Reader", 25.0), "Reader");
+        assert_eq!(truncate_label("CsvReader01", 25.0), "CsvRead…");
+        assert_eq!(truncate_label("CsvReader01", 40.0), "CsvReader01");
+    }
+
+    #[test]
+    fn test_edge_endpoints_use_node_radius() {
+        let source = Point { x: 0.0, y: 0.0 };
+        let target = Point { x: 100.0, y: 0.0 };
+
+        let (start, end) =
+            edge_endpoints(&source, &target, NodeShape::Circle, NodeShape::Circle, 40.0);
+        assert_eq!(start, Point { x: 40.0, y: 0.0 });
+        assert_eq!(end, Point { x: 60.0, y: 0.0 });
+    }
+
+    #[test]
+    fn test_self_loop_renders_finite_loop() {
+        let center = Point { x: 50.0, y: 80.0 };
+        let (start, end) =
+            edge_endpoints(&center, &center, NodeShape::Circle, NodeShape::Circle, 20.0);
+        assert_eq!((start, end), (center.clone(), center.clone()));
+
+        for shape in [NodeShape::Circle, NodeShape::Rect, NodeShape::Diamond] {
+            let points = polyline_points(&center, &center, &[], shape, shape, 20.0);
+            assert_eq!(points.len(), 4);
+            let angle = arrowhead_angle(&points);
+            let (arrow_1, arrow_2) =
+                arrowhead_at(points.last().unwrap(), angle, DEFAULT_ARROW_SIZE);
+            for point in points.iter().chain([&arrow_1, &arrow_2]) {
+                assert!(point.x.is_finite() && point.y.is_finite(), "{:?}", point);
+            }
+            // The loop rises above the node and comes back to it
+            assert!(points[1].y < center.y && points[2].y < center.y);
+            assert!(points[0].y < center.y && points[3].y < center.y);
+            assert!(!svg_points(&points).contains("NaN"));
+        }
+    }
+
+    #[test]
+    fn test_render_svg_is_standalone_document() {
+        let mut graph = city_graph();
+        graph.add_node("Fish & Chips <Shop>".to_string());
+        let positions: HashMap<_, _> = graph
+            .node_indices()
+            .enumerate()
+            .map(|(i, node_idx)| {
+                (
+                    node_idx,
+                    Point {
+                        x: 100.0 * i as f64,
+                        y: 50.0,
+                    },
+                )
+            })
+            .collect();
+
+        let svg = render_svg(&graph, &positions, &SvgStyle::default());
+
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains(r#"<svg xmlns="http://www.w3.org/2000/svg""#));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<circle").count(), graph.node_count());
+        // Text is escaped and no stylesheet classes leak in
+        assert!(svg.contains("Fish &amp;"));
+        assert!(!svg.contains("<Shop>"));
+        assert!(!svg.contains("class="));
+        assert_eq!(svg.matches("<text").count(), svg.matches("</text>").count());
+    }
+
+    #[test]
+    fn test_render_svg_matches_canvas_style() {
+        let graph = city_graph();
+        let mut nodes = graph.node_indices();
+        let (nyc, la) = (nodes.next().unwrap(), nodes.next().unwrap());
+        let edge_idx = graph.edge_indices().next().unwrap();
+        let positions = HashMap::from([
+            (nyc, Point { x: 0.0, y: 0.0 }),
+            (la, Point { x: 200.0, y: 0.0 }),
+        ]);
+        let bend = Point { x: 100.0, y: 300.0 };
+        let dark = colors_for(Theme::Dark);
+        let style = SvgStyle {
+            colors: dark,
+            arrow_size: 16.0,
+            arrow_style: ArrowStyle::Filled,
+            shapes: HashMap::from([(nyc, NodeShape::Diamond), (la, NodeShape::Rect)]),
+            node_fills: HashMap::from([(la, "plum")]),
+            waypoints: HashMap::from([(edge_idx, vec![bend.clone()])]),
+            dashed: HashSet::from([edge_idx]),
+            ..Default::default()
+        };
+
+        let svg = render_svg(&graph, &positions, &style);
+
+        // Theme colors throughout, the kind fill where one is given
+        assert!(svg.contains(&format!(r#"fill="{}""#, dark.background)));
+        assert!(svg.contains(&format!(
+            r#"fill="{}" stroke="{}""#,
+            dark.node_fill, dark.node_stroke
+        )));
+        assert!(svg.contains(r#"fill="plum""#));
+        assert!(!svg.contains("lightblue"));
+        // Node shapes rather than circles
+        assert_eq!(svg.matches("<circle").count(), 0);
+        assert_eq!(svg.matches(r#"rx="4""#).count(), 1);
+        // The edge bends through its waypoint, dashed, and ends in a filled arrowhead of the given size
+        let points = polyline_points(
+            &positions[&nyc],
+            &positions[&la],
+            &[bend],
+            NodeShape::Diamond,
+            NodeShape::Rect,
+            25.0,
+        );
+        assert!(svg.contains(&format!(r#"<polyline points="{}""#, svg_points(&points))));
+        assert!(svg.contains(r#"stroke-dasharray="6 4""#));
+        let angle = arrowhead_angle(&points);
+        let arrow = arrow_polygon(points.last().unwrap(), angle, 16.0);
+        assert!(svg.contains(&format!(r#"<polygon points="{}""#, svg_points(&arrow))));
+        // The view reaches down to the bend
+        assert!(svg.contains(r#"height="390""#), "{}", svg);
+    }
+
+    #[test]
+    fn test_workflow_svg_keeps_labels_dark_on_kind_fills() {
+        let mut workflow = Workflow::default();
+        let node_idx = workflow.graph.add_node(WorkflowNode::default());
+        let positions = HashMap::from([(node_idx, Point { x: 0.0, y: 0.0 })]);
+        let dark = colors_for(Theme::Dark);
+        let style = workflow_svg_style(
+            &workflow,
+            &HashMap::new(),
+            SvgStyle {
+                colors: dark,
+                ..Default::default()
+            },
+        );
+
+        let svg = render_svg(&workflow.graph, &positions, &style);
+
+        // The kind fill is light, so the label stays black as on the canvas, while the rest keeps the dark theme
+        assert!(svg.contains(r#"fill="lightblue""#));
+        assert!(
+            svg.contains(r#"font-family="sans-serif" fill="black""#),
+            "{}",
+            svg
+        );
+        assert!(!svg.contains(&format!(r#"fill="{}">"#, dark.node_text)));
+        assert!(svg.contains(&format!(r#"fill="{}""#, dark.background)));
+    }
+
+    #[test]
+    fn test_light_theme_is_default() {
+        assert_eq!(Theme::default(), Theme::Light);
+        assert_eq!(ThemeColors::default(), colors_for(Theme::Light));
+        assert_eq!(colors_for(Theme::Light).background, "white");
+        assert_eq!(colors_for(Theme::Light).node_fill, "lightblue");
+    }
+
+    #[test]
+    fn test_dark_theme_swaps_every_color() {
+        let light = colors_for(Theme::Light);
+        let dark = colors_for(Theme::Dark);
+        assert_ne!(light.background, dark.background);
+        assert_ne!(light.node_fill, dark.node_fill);
+        assert_ne!(light.node_stroke, dark.node_stroke);
+        assert_ne!(light.node_text, dark.node_text);
+        assert_ne!(light.edge, dark.edge);
+        assert_ne!(light.edge_label, dark.edge_label);
+    }
+
+    #[test]
+    fn test_node_at_resolves_release_point() {
+        let graph = city_graph();
+        let mut nodes = graph.node_indices();
+        let (nyc, la) = (nodes.next().unwrap(), nodes.next().unwrap());
+        let positions = HashMap::from([
+            (nyc, Point { x: 100.0, y: 100.0 }),
+            (la, Point { x: 140.0, y: 100.0 }),
+        ]);
+
+        // Inside both circles, the closer center wins
+        assert_eq!(
+            node_at(&positions, &Point { x: 110.0, y: 100.0 }, 25.0),
+            Some(nyc)
+        );
+        assert_eq!(
+            node_at(&positions, &Point { x: 130.0, y: 105.0 }, 25.0),
+            Some(la)
+        );
+        // On the border still counts, empty space doesn't
+        assert_eq!(
+            node_at(&positions, &Point { x: 100.0, y: 125.0 }, 25.0),
+            Some(nyc)
+        );
+        assert_eq!(
+            node_at(&positions, &Point { x: 300.0, y: 300.0 }, 25.0),
+            None
+        );
+        assert_eq!(
+            node_at(&HashMap::new(), &Point { x: 0.0, y: 0.0 }, 25.0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_legend_entries_count_node_kinds() {
+        let mut graph = DiGraph::<WorkflowNode, WorkflowEdge>::new();
+        graph.add_node(WorkflowNode::default());
+        graph.add_node(WorkflowNode::default());
+        graph.add_node(WorkflowNode {
+            kind: NodeKind::SubGraph("other-graph".to_string()),
+            ..Default::default()
+        });
+        let routing_with = serde_yaml::from_str("routingPort: yearPort").unwrap();
+        graph.add_node(WorkflowNode {
+            with: Some(routing_with),
+            ..Default::default()
+        });
+
+        let entries = legend_entries(&graph);
+        let summary: Vec<(&str, usize)> = entries
+            .iter()
+            .map(|(kind, _, count)| (kind.as_str(), *count))
+            .collect();
+        assert_eq!(summary, vec![("Action", 2), ("SubGraph", 1), ("Router", 1)]);
+
+        // Every kind has its own swatch color
+        let mut colors: Vec<&str> = entries.iter().map(|(_, color, _)| color.as_str()).collect();
+        colors.dedup();
+        assert_eq!(colors.len(), 3);
+    }
+
+    #[test]
+    fn test_animated_edge_has_moving_dash() {
+        let animation = flow_animation(true).expect("animated edges get a moving dash");
+        assert_eq!(animation.dash_array, "8 6");
+        assert!(animation.style.contains("animation: edge-flow"));
+        assert!(animation.style.contains("infinite"));
+    }
+
+    #[test]
+    fn test_static_edge_has_no_animation() {
+        assert_eq!(flow_animation(false), None);
+    }
+
+    #[test]
+    fn test_center_on_puts_node_in_middle_of_canvas() {
+        let graph = city_graph();
+        let la = graph.node_indices().nth(1).unwrap();
+        let positions = HashMap::from([(
+            la,
+            Point {
+                x: 1000.0,
+                y: 400.0,
+            },
+        )]);
+
+        let view = center_on(&positions, la, (800.0, 600.0)).unwrap();
+        assert_eq!(
+            view,
+            ViewBox {
+                x: 600.0,
+                y: 100.0,
+                width: 800.0,
+                height: 600.0,
+            }
+        );
+        assert_eq!(view.to_string(), "600 100 800 600");
+        // The canvas center maps back onto the node
+        assert_eq!(
+            view.to_drawing(Point { x: 400.0, y: 300.0 }, (800.0, 600.0)),
+            Point {
+                x: 1000.0,
+                y: 400.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_center_on_unplaced_node() {
+        let graph = city_graph();
+        let nyc = graph.node_indices().next().unwrap();
+        assert_eq!(center_on(&HashMap::new(), nyc, (800.0, 600.0)), None);
+    }
+
+    #[test]
+    fn test_cluster_bounds_for_two_clusters() {
+        let [a, b, c] = [0, 1, 2].map(petgraph::graph::NodeIndex::new);
+        let positions = HashMap::from([
+            (a, Point { x: 100.0, y: 100.0 }),
+            (b, Point { x: 200.0, y: 150.0 }),
+            (c, Point { x: 500.0, y: 300.0 }),
+        ]);
+        let groups = HashMap::from([
+            ("reader-graph".to_string(), vec![a, b]),
+            ("main-graph".to_string(), vec![c]),
+        ]);
+
+        let bounds = cluster_bounds(&positions, &groups);
+        let pad = CLUSTER_PADDING;
+        assert_eq!(
+            bounds,
+            vec![
+                (
+                    "main-graph".to_string(),
+                    Rect {
+                        x: 500.0 - pad,
+                        y: 300.0 - pad,
+                        width: 2.0 * pad,
+                        height: 2.0 * pad,
+                    }
+                ),
+                (
+                    "reader-graph".to_string(),
+                    Rect {
+                        x: 100.0 - pad,
+                        y: 100.0 - pad,
+                        width: 100.0 + 2.0 * pad,
+                        height: 50.0 + 2.0 * pad,
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cluster_bounds_skips_unplaced_groups() {
+        let groups = HashMap::from([(
+            "empty".to_string(),
+            vec![petgraph::graph::NodeIndex::new(0)],
+        )]);
+        assert!(cluster_bounds(&HashMap::new(), &groups).is_empty());
+    }
+
+    /// Asserts two distances agree to within floating point error.
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_circle_boundary_is_radius_in_every_direction() {
+        for (dx, dy) in [(1.0, 0.0), (0.0, -3.0), (2.0, 2.0), (-5.0, 1.0)] {
+            assert_close(NodeShape::Circle.boundary_distance(25.0, dx, dy), 25.0);
+        }
+    }
+
+    #[test]
+    fn test_rect_boundary_hits_nearest_side() {
+        // Half-width 20, half-height 14
+        assert_close(NodeShape::Rect.boundary_distance(20.0, 1.0, 0.0), 20.0);
+        assert_close(NodeShape::Rect.boundary_distance(20.0, 0.0, -1.0), 14.0);
+        // Along the diagonal the top is reached before the side
+        assert_close(
+            NodeShape::Rect.boundary_distance(20.0, 1.0, 1.0),
+            14.0 * std::f64::consts::SQRT_2,
+        );
+        // A shallow ray exits through the side
+        assert_close(
+            NodeShape::Rect.boundary_distance(20.0, 4.0, 1.0),
+            20.0 * 17.0_f64.sqrt() / 4.0,
+        );
+    }
+
+    #[test]
+    fn test_diamond_boundary_reaches_corners_and_edge_midpoints() {
+        assert_close(NodeShape::Diamond.boundary_distance(20.0, 1.0, 0.0), 20.0);
+        assert_close(NodeShape::Diamond.boundary_distance(20.0, 0.0, 7.0), 20.0);
+        // The middle of a diamond side is radius / sqrt(2) from the center
+        assert_close(
+            NodeShape::Diamond.boundary_distance(20.0, -1.0, 1.0),
+            20.0 / std::f64::consts::SQRT_2,
+        );
+    }
+
+    #[test]
+    fn test_edge_endpoints_follow_node_shapes() {
+        let source = Point { x: 0.0, y: 0.0 };
+        let target = Point { x: 0.0, y: 100.0 };
+
+        // Vertically a rectangle is shallower than its radius, a diamond is not
+        let (start, end) =
+            edge_endpoints(&source, &target, NodeShape::Rect, NodeShape::Diamond, 20.0);
+        assert_close(start.y, 14.0);
+        assert_close(end.y, 80.0);
+    }
+
+    #[test]
+    fn test_node_shape_by_kind() {
+        let sub_graph = WorkflowNode {
+            kind: NodeKind::SubGraph("other-graph".to_string()),
+            ..Default::default()
+        };
+        let router = WorkflowNode {
+            with: Some(serde_yaml::from_str("routingPort: yearPort").unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(node_shape(&WorkflowNode::default()), NodeShape::Circle);
+        assert_eq!(node_shape(&sub_graph), NodeShape::Rect);
+        assert_eq!(node_shape(&router), NodeShape::Diamond);
+    }
+
+    /// A main graph holding an action and a sub graph node for `outer`, which holds an action and a sub graph node
+    /// for `inner`, which holds one action.
+    fn nested_sub_graphs() -> DiGraph<WorkflowNode, WorkflowEdge> {
+        let mut graph = DiGraph::new();
+        for (id, subgraph, kind) in [
+            ("main-action", "main", NodeKind::Action(Default::default())),
+            (
+                "outer-node",
+                "main",
+                NodeKind::SubGraph("outer".to_string()),
+            ),
+            (
+                "outer-action",
+                "outer",
+                NodeKind::Action(Default::default()),
+            ),
+            (
+                "inner-node",
+                "outer",
+                NodeKind::SubGraph("inner".to_string()),
+            ),
+            (
+                "inner-action",
+                "inner",
+                NodeKind::Action(Default::default()),
+            ),
+        ] {
+            graph.add_node(WorkflowNode {
+                id: id.to_string(),
+                subgraph: subgraph.to_string(),
+                kind,
+                ..Default::default()
+            });
+        }
+        graph
+    }
+
+    fn hidden_ids(graph: &DiGraph<WorkflowNode, WorkflowEdge>, collapsed: &[&str]) -> Vec<String> {
+        let collapsed: HashSet<String> = collapsed.iter().map(|id| id.to_string()).collect();
+        let mut ids: Vec<String> = hidden_nodes(graph, &collapsed)
+            .into_iter()
+            .map(|node_idx| graph[node_idx].id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_nothing_hidden_when_expanded() {
+        assert!(hidden_ids(&nested_sub_graphs(), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_collapsing_hides_nested_contents() {
+        let graph = nested_sub_graphs();
+        assert_eq!(
+            hidden_ids(&graph, &["outer-node"]),
+            vec!["inner-action", "inner-node", "outer-action"]
+        );
+        assert_eq!(hidden_ids(&graph, &["inner-node"]), vec!["inner-action"]);
+        // Action ids in the set are not sub graphs and hide nothing
+        assert!(hidden_ids(&graph, &["main-action"]).is_empty());
+    }
+
+    #[test]
+    fn test_incremental_layout_keeps_existing_positions() {
+        let mut graph = city_graph();
+        let nyc = graph.node_indices().next().unwrap();
+        let la = graph.node_indices().nth(1).unwrap();
+        let existing = HashMap::from([
+            (nyc, Point { x: 10.0, y: 20.0 }),
+            (la, Point { x: 400.0, y: 20.0 }),
+        ]);
+        // One new node connected to New York, two unconnected ones
+        let chicago = graph.add_node("Chicago".to_string());
+        graph.add_edge(nyc, chicago, 50);
+        let denver = graph.add_node("Denver".to_string());
+        let boston = graph.add_node("Boston".to_string());
+
+        let positions = incremental(&existing, &graph);
+        assert_eq!(positions.len(), 5);
+        assert_eq!(positions[&nyc], existing[&nyc]);
+        assert_eq!(positions[&la], existing[&la]);
+
+        // Every node ends up clear of every other
+        let all: Vec<&Point> = positions.values().collect();
+        for (i, a) in all.iter().enumerate() {
+            for b in &all[i + 1..] {
+                let distance = ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt();
+                assert!(
+                    distance >= NODE_SPACING - 1e-9,
+                    "{:?} and {:?} overlap",
+                    a,
+                    b
+                );
+            }
+        }
+        // The connected node lands next to its neighbor
+        let near = &positions[&chicago];
+        let distance = ((near.x - 10.0).powi(2) + (near.y - 20.0).powi(2)).sqrt();
+        assert!(
+            distance <= NODE_SPACING + 1e-9,
+            "Chicago placed at {:?}",
+            near
+        );
+        assert_ne!(positions[&denver], positions[&boston]);
+    }
+
+    #[test]
+    fn test_incremental_layout_drops_removed_nodes() {
+        let graph = city_graph();
+        let gone = petgraph::graph::NodeIndex::new(7);
+        let existing = HashMap::from([(gone, Point { x: 0.0, y: 0.0 })]);
+
+        let positions = incremental(&existing, &graph);
+        assert!(!positions.contains_key(&gone));
+        assert_eq!(positions.len(), 2);
+    }
+
+    #[test]
+    fn test_clamp_viewbox_keeps_view_that_shows_content() {
+        let content = Rect {
+            x: 0.0,
+            y: 0.0,
+            width: 400.0,
+            height: 300.0,
+        };
+        let view = ViewBox {
+            x: -100.0,
+            y: 50.0,
+            width: 800.0,
+            height: 600.0,
+        };
+        assert_eq!(clamp_viewbox(view, content), view);
+    }
+
+    #[test]
+    fn test_clamp_viewbox_pulls_lost_content_back_into_view() {
+        let content = Rect {
+            x: 0.0,
+            y: 0.0,
+            width: 400.0,
+            height: 300.0,
+        };
+        // Panned far to the right and down: the view stops once half the content is showing
+        let far = ViewBox {
+            x: 5000.0,
+            y: 5000.0,
+            width: 800.0,
+            height: 600.0,
+        };
+        assert_eq!(
+            clamp_viewbox(far, content),
+            ViewBox {
+                x: 200.0,
+                y: 150.0,
+                width: 800.0,
+                height: 600.0,
+            }
+        );
+        // Zoomed in and panned left of the content: half the view still overlaps it
+        let near = ViewBox {
+            x: -1000.0,
+            y: 100.0,
+            width: 100.0,
+            height: 100.0,
+        };
+        assert_eq!(clamp_viewbox(near, content).x, -50.0);
+        assert_eq!(clamp_viewbox(near, content).y, 100.0);
+    }
+
+    #[test]
+    fn test_zoom_is_limited_and_keeps_anchor() {
+        let view = ViewBox::new(800.0, 600.0);
+        let anchor = Point { x: 400.0, y: 300.0 };
+
+        let zoomed = view.zoom(2.0, &anchor, 800.0);
+        assert_eq!(zoomed.width, 400.0);
+        assert_eq!(
+            zoomed.to_drawing(Point { x: 400.0, y: 300.0 }, (800.0, 600.0)),
+            anchor
+        );
+
+        assert_eq!(view.zoom(100.0, &anchor, 800.0).width, 800.0 / MAX_ZOOM);
+        assert_eq!(view.zoom(0.001, &anchor, 800.0).width, 800.0 / MIN_ZOOM);
+    }
+
+    #[test]
+    fn test_content_bounds_pad_placed_nodes() {
+        let positions = HashMap::from([
+            (
+                petgraph::graph::NodeIndex::new(0),
+                Point { x: 10.0, y: 20.0 },
+            ),
+            (
+                petgraph::graph::NodeIndex::new(1),
+                Point { x: 110.0, y: -30.0 },
+            ),
+        ]);
+        assert_eq!(
+            content_bounds(&positions, 25.0),
+            Some(Rect {
+                x: -15.0,
+                y: -55.0,
+                width: 150.0,
+                height: 100.0,
+            })
+        );
+        assert_eq!(content_bounds(&HashMap::new(), 25.0), None);
+    }
+
+    #[test]
+    fn test_first_node_is_centered_in_view() {
+        let click = Point { x: 0.0, y: 0.0 };
+        let view = ViewBox::new(800.0, 600.0);
+        assert_eq!(
+            new_node_position(&HashMap::new(), click.clone(), &view),
+            Point { x: 400.0, y: 300.0 }
+        );
+
+        // Panned views center on what is on screen
+        let panned = ViewBox {
+            x: 1000.0,
+            y: -200.0,
+            width: 400.0,
+            height: 200.0,
+        };
+        assert_eq!(
+            new_node_position(&HashMap::new(), click, &panned),
+            Point {
+                x: 1200.0,
+                y: -100.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_later_nodes_go_where_clicked() {
+        let placed = HashMap::from([(
+            petgraph::graph::NodeIndex::new(0),
+            Point { x: 400.0, y: 300.0 },
+        )]);
+        let click = Point { x: 12.0, y: 34.0 };
+        assert_eq!(
+            new_node_position(&placed, click.clone(), &ViewBox::new(800.0, 600.0)),
+            click
+        );
+    }
+
+    fn single_node_workflow() -> Workflow {
+        let mut workflow = Workflow::default();
+        workflow.graph.add_node(WorkflowNode {
+            name: "Reader".to_string(),
+            ..Default::default()
+        });
+        workflow
+    }
+
+    #[test]
+    fn test_apply_rename_trims_text() {
+        let mut workflow = single_node_workflow();
+        let node_idx = workflow.graph.node_indices().next().unwrap();
+        apply_rename(&mut workflow, node_idx, "  CityReader ").unwrap();
+        assert_eq!(workflow.graph[node_idx].name, "CityReader");
+    }
+
+    #[test]
+    fn test_apply_rename_rejects_blank_text() {
+        let mut workflow = single_node_workflow();
+        let node_idx = workflow.graph.node_indices().next().unwrap();
+        assert!(apply_rename(&mut workflow, node_idx, "").is_err());
+        assert!(apply_rename(&mut workflow, node_idx, "   ").is_err());
+        assert_eq!(workflow.graph[node_idx].name, "Reader");
+    }
+
+    #[test]
+    fn test_mode_for_key_maps_shortcuts() {
+        use dioxus::prelude::Key;
+        let character = |c: &str| Key::Character(c.to_string());
+        assert_eq!(mode_for_key(&character("n")), Some(EditingMode::AddNode));
+        assert_eq!(mode_for_key(&character("E")), Some(EditingMode::AddEdge));
+        assert_eq!(mode_for_key(&character("d")), Some(EditingMode::DeleteNode));
+        assert_eq!(mode_for_key(&character("x")), Some(EditingMode::DeleteEdge));
+        assert_eq!(mode_for_key(&Key::Escape), Some(EditingMode::Normal));
+    }
+
+    #[test]
+    fn test_mode_for_key_ignores_other_keys() {
+        use dioxus::prelude::Key;
+        assert_eq!(mode_for_key(&Key::Character("q".to_string())), None);
+        assert_eq!(mode_for_key(&Key::Enter), None);
+    }
+
+    #[test]
+    fn test_entry_nodes_are_sources_of_the_entry_graph() {
+        let mut workflow = Workflow {
+            entry_graph_ids: vec!["main".to_string()],
+            ..Default::default()
+        };
+        let node_in = |subgraph: &str| WorkflowNode {
+            subgraph: subgraph.to_string(),
+            ..Default::default()
+        };
+        let source = workflow.graph.add_node(node_in("main"));
+        let sink = workflow.graph.add_node(node_in("main"));
+        let lone = workflow.graph.add_node(node_in("main"));
+        workflow.graph.add_node(node_in("other"));
+        workflow
+            .graph
+            .add_edge(source, sink, WorkflowEdge::default());
+
+        assert_eq!(entry_nodes(&workflow), vec![source, lone]);
+    }
+
+    #[test]
+    fn test_add_weighted_edge_uses_given_weight() {
+        let mut graph = city_graph();
+        let (nyc, la) = (
+            graph.node_indices().next().unwrap(),
+            graph.node_indices().nth(1).unwrap(),
+        );
+        let edge_idx = add_weighted_edge(&mut graph, la, nyc, 7);
+        assert_eq!(graph[edge_idx], 7);
+        assert_eq!(graph.edge_endpoints(edge_idx), Some((la, nyc)));
+    }
+
+    #[test]
+    fn test_add_weighted_edge_falls_back_to_default() {
+        // A weight of 7 doesn't parse as a bool
+        let mut graph = DiGraph::<String, bool>::new();
+        let a = graph.add_node("a".to_string());
+        let b = graph.add_node("b".to_string());
+        let edge_idx = add_weighted_edge(&mut graph, a, b, 7);
+        assert!(!graph[edge_idx]);
+    }
+
+    #[test]
+    fn test_clear_graph_empties_everything() {
+        let mut graph = city_graph();
+        let nyc = graph.node_indices().next().unwrap();
+        let mut positions: HashMap<_, _> = graph
+            .node_indices()
+            .map(|node_idx| (node_idx, Point { x: 0.0, y: 0.0 }))
+            .collect();
+        let mut selection = Selection::Node((nyc, graph[nyc].clone()));
+
+        clear_graph(&mut graph, &mut positions, &mut selection);
+
+        assert_eq!(graph.node_count(), 0);
+        assert_eq!(graph.edge_count(), 0);
+        assert!(positions.is_empty());
+        assert!(selection == Selection::None);
+    }
+
+    #[test]
+    fn test_graph_positions_survive_node_removal() {
+        let mut graph = city_graph();
+        let chicago = graph.add_node("Chicago".to_string());
+        // Each placed node's name and x, by name
+        let names = |graph: &DiGraph<String, i32>,
+                     positions: &HashMap<petgraph::graph::NodeIndex, Point>| {
+            let mut placed: Vec<(String, f64)> = positions
+                .iter()
+                .map(|(node_idx, point)| (graph[*node_idx].clone(), point.x))
+                .collect();
+            placed.sort_by(|a, b| a.0.cmp(&b.0));
+            placed
+        };
+        let mut positions: HashMap<_, _> = graph
+            .node_indices()
+            .map(|node_idx| {
+                let x = node_idx.index() as f64 * 100.0;
+                (node_idx, Point { x, y: 0.0 })
+            })
+            .collect();
+        let mut anchored = HashSet::from([chicago]);
+
+        // Removing New York moves Chicago, the last node, into its index
+        let nyc = graph.node_indices().next().unwrap();
+        apply_menu_action(&mut graph, MenuAction::Delete(MenuTarget::Node(nyc)));
+        forget_removed_node(&graph, &mut positions, &mut anchored, nyc);
+
+        assert_eq!(positions.len(), 2);
+        assert_eq!(
+            names(&graph, &positions),
+            vec![
+                ("Chicago".to_string(), 200.0),
+                ("Los Angeles".to_string(), 100.0)
+            ]
+        );
+        assert_eq!(graph[nyc], "Chicago");
+        assert_eq!(anchored, HashSet::from([nyc]));
+
+        // Removing the last node leaves the others alone
+        let last = graph.node_indices().last().unwrap();
+        graph.remove_node(last);
+        forget_removed_node(&graph, &mut positions, &mut anchored, last);
+        assert_eq!(
+            names(&graph, &positions),
+            vec![("Chicago".to_string(), 200.0)]
+        );
+        assert_eq!(anchored, HashSet::from([nyc]));
+    }
+
+    #[test]
+    fn test_drag_ignored_only_for_anchored_nodes() {
+        let graph = city_graph();
+        let (nyc, la) = (
+            graph.node_indices().next().unwrap(),
+            graph.node_indices().nth(1).unwrap(),
+        );
+        let anchored = HashSet::from([nyc]);
+        assert!(drag_ignored(nyc, &anchored));
+        assert!(!drag_ignored(la, &anchored));
+        assert!(!drag_ignored(nyc, &HashSet::new()));
+    }
+
+    #[test]
+    fn test_hovered_endpoints_of_an_edge() {
+        let graph = city_graph();
+        let edge_idx = graph.edge_indices().next().unwrap();
+        let (nyc, la) = graph.edge_endpoints(edge_idx).unwrap();
+        assert_eq!(hovered_endpoints(&graph, Some(edge_idx)), vec![nyc, la]);
+    }
+
+    #[test]
+    fn test_hovered_endpoints_without_an_edge() {
+        let graph = city_graph();
+        assert!(hovered_endpoints(&graph, None).is_empty());
+        let removed = petgraph::graph::EdgeIndex::new(5);
+        assert!(hovered_endpoints(&graph, Some(removed)).is_empty());
+    }
+
+    #[test]
+    fn test_circular_layout_fits_canvas() {
+        let points = circular_layout(4, 600.0, 400.0);
+        assert_eq!(points.len(), 4);
+
+        // Centered in the canvas with a radius of 150, the first node rightmost
+        let first = &points[&0];
+        assert!((first.x - 450.0).abs() < 1e-9 && (first.y - 200.0).abs() < 1e-9);
+        let third = &points[&2];
+        assert!((third.x - 150.0).abs() < 1e-9 && (third.y - 200.0).abs() < 1e-9);
+        for point in points.values() {
+            let distance = ((point.x - 300.0).powi(2) + (point.y - 200.0).powi(2)).sqrt();
+            assert!((distance - 150.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_circular_layout_follows_canvas_size() {
+        assert!(circular_layout(0, 800.0, 600.0).is_empty());
+
+        // A wider canvas moves the center and scales the radius to its smaller side
+        let points = circular_layout(1, 1000.0, 800.0);
+        let only = &points[&0];
+        assert!((only.x - 800.0).abs() < 1e-9 && (only.y - 400.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_wrap_label_breaks_between_words() {
+        assert_eq!(
+            wrap_label("Solar Radiation Reader", 10),
+            vec!["Solar", "Radiation", "Reader"]
+        );
+        assert_eq!(wrap_label("Csv to Json", 11), vec!["Csv to Json"]);
+        assert_eq!(wrap_label("a b c d", 3), vec!["a b", "c d"]);
+        assert!(wrap_label("   ", 5).is_empty());
+    }
+
+    #[test]
+    fn test_wrap_label_cuts_long_words() {
+        assert_eq!(
+            wrap_label("FeatureCityGmlReader", 8),
+            vec!["FeatureC", "ityGmlRe", "ader"]
+        );
+        // The last piece of a cut word shares its line with the next word
+        assert_eq!(
+            wrap_label("AttributeMap v2", 8),
+            vec!["Attribut", "eMap v2"]
+        );
+        assert_eq!(wrap_label("Reader", 0), vec!["R", "e", "a", "d", "e", "r"]);
+    }
+
+    #[test]
+    fn test_label_lines_fit_node() {
+        // A radius of 25 fits 8 characters across and 2 lines
+        assert_eq!(label_lines("Reader", 25.0), vec!["Reader"]);
+        assert_eq!(label_lines("Csv Reader", 25.0), vec!["Csv", "Reader"]);
+        assert_eq!(
+            label_lines("Solar Radiation Reader", 25.0),
+            vec!["Solar", "Radiati…"]
+        );
+    }
+
+    #[test]
+    fn test_cursor_for_mode_covers_every_mode() {
+        assert_eq!(cursor_for_mode(&EditingMode::Normal), "default");
+        assert_eq!(cursor_for_mode(&EditingMode::AddNode), "crosshair");
+        assert_eq!(cursor_for_mode(&EditingMode::AddEdge), "pointer");
+        assert_eq!(cursor_for_mode(&EditingMode::ConnectEdge), "pointer");
+        assert_eq!(cursor_for_mode(&EditingMode::DeleteEdge), "pointer");
+        assert_eq!(cursor_for_mode(&EditingMode::DeleteNode), "not-allowed");
+        assert_eq!(cursor_for_mode(&EditingMode::InsertNode), "pointer");
+    }
+
+    #[test]
+    fn test_focus_set_follows_paths_through_node() {
+        // a -> b -> c with a side branch a -> s; focusing b leaves s out
+        let mut workflow = Workflow::default();
+        let [a, b, c, s] = ["a", "b", "c", "s"].map(|id| {
+            workflow.graph.add_node(WorkflowNode {
+                id: id.to_string(),
+                ..Default::default()
+            })
+        });
+        for (from, to) in [(a, b), (b, c), (a, s)] {
+            workflow.graph.add_edge(from, to, WorkflowEdge::default());
+        }
+
+        assert_eq!(focus_set(&workflow, b), HashSet::from([a, b, c]));
+        assert_eq!(focus_set(&workflow, s), HashSet::from([a, s]));
+        assert_eq!(focus_set(&workflow, a), HashSet::from([a, b, c, s]));
+    }
+
+    #[test]
+    fn test_edge_rejection_skips_duplicates() {
+        // New York -> Los Angeles already exists
+        let graph = city_graph();
+        let (nyc, la) = (
+            graph.node_indices().next().unwrap(),
+            graph.node_indices().nth(1).unwrap(),
+        );
+
+        assert_eq!(
+            edge_rejection(&graph, nyc, la, false),
+            Some("That edge already exists")
+        );
+        assert_eq!(
+            edge_rejection(&graph, nyc, la, true),
+            Some("That edge already exists")
+        );
+        // The reverse direction is a different edge
+        assert_eq!(edge_rejection(&graph, la, nyc, false), None);
+    }
+
+    #[test]
+    fn test_edge_rejection_prevents_self_loops_unless_allowed() {
+        let mut graph = city_graph();
+        let nyc = graph.node_indices().next().unwrap();
+
+        assert_eq!(
+            edge_rejection(&graph, nyc, nyc, false),
+            Some("An edge can't connect a node to itself")
+        );
+        assert_eq!(edge_rejection(&graph, nyc, nyc, true), None);
+
+        // An allowed self loop still isn't added twice
+        graph.add_edge(nyc, nyc, 1);
+        assert_eq!(
+            edge_rejection(&graph, nyc, nyc, true),
+            Some("That edge already exists")
+        );
+    }
+
+    fn chain_workflow() -> (
+        Workflow,
+        [petgraph::graph::NodeIndex; 2],
+        petgraph::graph::EdgeIndex,
+    ) {
+        let mut workflow = Workflow::default();
+        let [a, b] = ["a", "b"].map(|id| {
+            workflow.graph.add_node(WorkflowNode {
+                id: id.to_string(),
+                subgraph: "main".to_string(),
+                ..Default::default()
+            })
+        });
+        let edge_idx = workflow.graph.add_edge(a, b, WorkflowEdge::default());
+        (workflow, [a, b], edge_idx)
+    }
+
+    #[test]
+    fn test_insert_node_mode_click_splits_edge() {
+        let (mut workflow, [a, b], edge_idx) = chain_workflow();
+        let mut positions = HashMap::from([
+            (workflow.graph[a].id.clone(), Point { x: 0.0, y: 0.0 }),
+            (workflow.graph[b].id.clone(), Point { x: 100.0, y: 50.0 }),
+        ]);
+
+        let inserted = apply_edge_click(
+            &EditingMode::InsertNode,
+            &mut workflow,
+            &mut positions,
+            edge_idx,
+        )
+        .unwrap()
+        .expect("a node is inserted");
+
+        assert_eq!(workflow.graph.node_count(), 3);
+        assert_eq!(workflow.graph[inserted].subgraph, "main");
+        assert!(workflow.graph.find_edge(a, b).is_none());
+        assert!(workflow.graph.find_edge(a, inserted).is_some());
+        assert!(workflow.graph.find_edge(inserted, b).is_some());
+        assert_eq!(
+            positions[&workflow.graph[inserted].id],
+            Point { x: 50.0, y: 25.0 }
+        );
+    }
+
+    #[test]
+    fn test_edge_click_in_other_modes_does_not_insert() {
+        for mode in [
+            EditingMode::Normal,
+            EditingMode::AddEdge,
+            EditingMode::ConnectEdge,
+            EditingMode::AddNode,
+            EditingMode::DeleteNode,
+        ] {
+            let (mut workflow, _, edge_idx) = chain_workflow();
+            let mut positions = HashMap::new();
+            let inserted =
+                apply_edge_click(&mode, &mut workflow, &mut positions, edge_idx).unwrap();
+            assert_eq!(inserted, None, "{:?}", mode);
+            assert_eq!(workflow.graph.node_count(), 2, "{:?}", mode);
+            assert_eq!(workflow.graph.edge_count(), 1, "{:?}", mode);
+        }
+
+        let (mut workflow, _, edge_idx) = chain_workflow();
+        let deleted = apply_edge_click(
+            &EditingMode::DeleteEdge,
+            &mut workflow,
+            &mut HashMap::new(),
+            edge_idx,
+        );
+        assert_eq!(deleted.unwrap(), None);
+        assert_eq!(workflow.graph.edge_count(), 0);
+    }
+
+    #[test]
+    fn test_hit_line_aligns_with_visible_line() {
+        let source = Point { x: 0.0, y: 0.0 };
+        let target = Point { x: 120.0, y: 90.0 };
+        for (source_shape, target_shape) in [
+            (NodeShape::Circle, NodeShape::Circle),
+            (NodeShape::Rect, NodeShape::Diamond),
+        ] {
+            let (start, end) = edge_endpoints(&source, &target, source_shape, target_shape, 25.0);
+            let points = polyline_points(&source, &target, &[], source_shape, target_shape, 25.0);
+            assert_eq!(points, vec![start.clone(), end.clone()]);
+
+            // The hit area is drawn over the visible line's own points attribute
+            let hit = hit_line(&points, DEFAULT_HIT_WIDTH);
+            assert_eq!(hit.points, svg_points(&points));
+            assert_eq!(
+                hit.points,
+                format!("{},{} {},{}", start.x, start.y, end.x, end.y)
+            );
+            assert_eq!(hit.stroke, "transparent");
+            assert_eq!(hit.stroke_width, 10.0);
+        }
+
+        let origin = [Point { x: 0.0, y: 0.0 }];
+        assert_eq!(hit_line(&origin, 24.0).stroke_width, 24.0);
+        assert_eq!(hit_line(&origin, -3.0).stroke_width, 0.0);
+    }
+
+    #[test]
+    fn test_polyline_points_through_waypoints() {
+        let source = Point { x: 0.0, y: 0.0 };
+        let target = Point { x: 200.0, y: 100.0 };
+        let bends = [Point { x: 100.0, y: 0.0 }, Point { x: 100.0, y: 100.0 }];
+        let points = polyline_points(
+            &source,
+            &target,
+            &bends,
+            NodeShape::Circle,
+            NodeShape::Circle,
+            20.0,
+        );
+
+        // Leaves the source towards the first bend and enters the target from the last one
+        assert_eq!(
+            points,
+            vec![
+                Point { x: 20.0, y: 0.0 },
+                Point { x: 100.0, y: 0.0 },
+                Point { x: 100.0, y: 100.0 },
+                Point { x: 180.0, y: 100.0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arrowhead_follows_last_segment() {
+        // The first segment heads right, the last one straight down
+        let points = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 100.0, y: 0.0 },
+            Point { x: 100.0, y: 100.0 },
+        ];
+        let angle = arrowhead_angle(&points);
+        assert!((angle - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+
+        // Both outer points sit above the tip, one either side of the line
+        let (left, right) = arrowhead_at(&points[2], angle, DEFAULT_ARROW_SIZE);
+        assert!(left.y < 100.0 && right.y < 100.0);
+        assert!((left.x - 100.0) * (right.x - 100.0) < 0.0);
+
+        // A straight edge points along its only segment
+        assert_eq!(arrowhead_angle(&points[..2]), 0.0);
+        assert_eq!(arrowhead_angle(&points[..1]), 0.0);
+    }
+
+    #[test]
+    fn test_positions_by_id_survive_node_removal() {
+        let mut workflow = Workflow::default();
+        let [a, _, _] = ["a", "b", "c"].map(|id| {
+            workflow.graph.add_node(WorkflowNode {
+                id: id.to_string(),
+                subgraph: "main".to_string(),
+                ..Default::default()
+            })
+        });
+        let positions = HashMap::from([
+            ("a".to_string(), Point { x: 0.0, y: 0.0 }),
+            ("b".to_string(), Point { x: 100.0, y: 0.0 }),
+            ("c".to_string(), Point { x: 200.0, y: 0.0 }),
+        ]);
+        assert_eq!(workflow.stable_id(a), Some("a"));
+
+        // Removing the first node moves the last one into its index
+        workflow.remove_node(a);
+        let by_index = positions_by_index(&workflow, &positions);
+        assert_eq!(by_index.len(), 2);
+        for node_idx in workflow.graph.node_indices() {
+            let id = workflow.stable_id(node_idx).unwrap();
+            assert_eq!(by_index[&node_idx], positions[id], "{}", id);
+        }
+        assert_eq!(workflow.stable_id(a), Some("c"));
+        assert_eq!(workflow.stable_id(petgraph::graph::NodeIndex::new(2)), None);
+    }
+
+    fn assert_near(actual: &Point, expected: (f64, f64)) {
+        assert!(
+            (actual.x - expected.0).abs() < 1e-9 && (actual.y - expected.1).abs() < 1e-9,
+            "{:?} is not near {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_arrow_polygon_vertices() {
+        let tip = Point { x: 100.0, y: 50.0 };
+        let half_width = 20.0 * (std::f64::consts::PI / 6.0).sin();
+        let depth = 20.0 * (std::f64::consts::PI / 6.0).cos();
+
+        // Pointing right, the sides reach back to the left of the tip
+        let [first, side_1, side_2] = arrow_polygon(&tip, 0.0, 20.0);
+        assert_eq!(first, tip);
+        assert_near(&side_1, (100.0 - depth, 50.0 + half_width));
+        assert_near(&side_2, (100.0 - depth, 50.0 - half_width));
+
+        // Pointing down, they reach back above it
+        let [first, side_1, side_2] = arrow_polygon(&tip, std::f64::consts::FRAC_PI_2, 20.0);
+        assert_eq!(first, tip);
+        assert_near(&side_1, (100.0 - half_width, 50.0 - depth));
+        assert_near(&side_2, (100.0 + half_width, 50.0 - depth));
+
+        // The sides match the open arrowhead's lines
+        let (open_1, open_2) = arrowhead_at(&tip, 1.0, DEFAULT_ARROW_SIZE);
+        let [_, side_1, side_2] = arrow_polygon(&tip, 1.0, DEFAULT_ARROW_SIZE);
+        assert_eq!((side_1, side_2), (open_1, open_2));
+    }
+
+    #[test]
+    fn test_toggle_selection() {
+        let nyc = petgraph::graph::NodeIndex::new(0);
+        let la = petgraph::graph::NodeIndex::new(1);
+        let route = petgraph::graph::EdgeIndex::new(0);
+        let node =
+            |node_idx, name: &str| Selection::<String, i32>::Node((node_idx, name.to_string()));
+
+        // Clicking something new selects it
+        assert!(toggle_selection(&Selection::None, node(nyc, "New York")) == node(nyc, "New York"));
+        assert!(
+            toggle_selection(&node(nyc, "New York"), node(la, "Los Angeles"))
+                == node(la, "Los Angeles")
+        );
+        assert!(
+            toggle_selection(&node(nyc, "New York"), Selection::Edge((route, 100)))
+                == Selection::Edge((route, 100))
+        );
+
+        // Clicking the selection again clears it, even if its data changed meanwhile
+        assert!(toggle_selection(&node(nyc, "New York"), node(nyc, "NYC")) == Selection::None);
+        assert!(
+            toggle_selection(
+                &Selection::Edge((route, 100)),
+                Selection::Edge((route, 250))
+            ) == Selection::None
+        );
+    }
+
+    fn rows(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(label, value)| (label.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_properties_rows_for_node() {
+        let node = WorkflowNode {
+            id: "router".to_string(),
+            name: "Split by year".to_string(),
+            subgraph: "main".to_string(),
+            kind: NodeKind::Action(NodeAction::OutputRouter),
+            with: Some(serde_yaml::from_str("routingPort: yearPort\nthreshold: 3").unwrap()),
+            description: Some("Routes features by year".to_string()),
+            node_type: None,
+        };
+        let selection = Selection::Node((petgraph::graph::NodeIndex::new(0), node));
+        assert_eq!(
+            properties_rows(&selection),
+            rows(&[
+                ("Id", "router"),
+                ("Name", "Split by year"),
+                ("Type", "action"),
+                ("Action", "OutputRouter"),
+                ("Graph", "main"),
+                ("Ports", "yearPort"),
+                ("Description", "Routes features by year"),
+                ("routingPort", "yearPort"),
+                ("threshold", "3"),
+            ])
+        );
+
+        let sub_graph = WorkflowNode {
+            id: "outer-node".to_string(),
+            name: "Outer".to_string(),
+            subgraph: "main".to_string(),
+            kind: NodeKind::SubGraph("outer".to_string()),
+            ..Default::default()
+        };
+        let selection = Selection::Node((petgraph::graph::NodeIndex::new(1), sub_graph));
+        assert_eq!(
+            properties_rows(&selection),
+            rows(&[
+                ("Id", "outer-node"),
+                ("Name", "Outer"),
+                ("Type", "subGraph"),
+                ("Sub graph", "outer"),
+                ("Graph", "main"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_properties_rows_for_edge() {
+        let edge = WorkflowEdge {
+            id: "e1".to_string(),
+            name: "default".to_string(),
+            from_port: "yearPort".to_string(),
+            to_port: "default".to_string(),
+            edge_kind: EdgeKind::Control,
+            weight: Some(2.5),
+            with: Some(serde_yaml::from_str("mapping: id").unwrap()),
+        };
+        let selection = Selection::Edge((petgraph::graph::EdgeIndex::new(0), edge));
+        assert_eq!(
+            properties_rows(&selection),
+            rows(&[
+                ("Id", "e1"),
+                ("Name", "default"),
+                ("From port", "yearPort"),
+                ("To port", "default"),
+                ("Kind", "Control"),
+                ("Weight", "2.5"),
+                ("mapping", "id"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_properties_rows_for_no_selection() {
+        assert!(properties_rows(&Selection::None).is_empty());
+    }
+
+    /// A workflow running a -> b -> c -> d, with x joining at c, and the indices of those nodes by id.
+    fn layered_workflow() -> (Workflow, HashMap<&'static str, petgraph::graph::NodeIndex>) {
+        let mut workflow = Workflow::default();
+        let nodes: HashMap<&'static str, petgraph::graph::NodeIndex> = ["a", "b", "c", "d", "x"]
+            .into_iter()
+            .map(|id| {
+                let node_idx = workflow.graph.add_node(WorkflowNode {
+                    id: id.to_string(),
+                    subgraph: "main".to_string(),
+                    ..Default::default()
+                });
+                (id, node_idx)
+            })
+            .collect();
+        for (from, to) in [("a", "b"), ("b", "c"), ("c", "d"), ("x", "c")] {
+            workflow
+                .graph
+                .add_edge(nodes[from], nodes[to], WorkflowEdge::default());
+        }
+        (workflow, nodes)
+    }
+
+    #[test]
+    fn test_default_layout_layers_acyclic_workflow() {
+        let (workflow, nodes) = layered_workflow();
+        let layout = default_layout(
+            &workflow,
+            Size {
+                width: 800.0,
+                height: 600.0,
+            },
+        );
+        assert_eq!(layout.len(), workflow.graph.node_count());
+
+        // Left to right along a -> b -> c -> d, with x joining at c
+        let x = |id: &str| layout[&nodes[id]].x;
+        assert!(x("a") < x("b") && x("b") < x("c") && x("c") < x("d"));
+        assert_eq!(x("x"), x("a"));
+        for point in layout.values() {
+            assert!((0.0..=800.0).contains(&point.x) && (0.0..=600.0).contains(&point.y));
+        }
+
+        // Nodes sharing a layer are stacked rather than drawn on top of each other
+        let y = |id: &str| layout[&nodes[id]].y;
+        assert_ne!(y("a"), y("x"));
+    }
+
+    #[test]
+    fn test_default_layout_falls_back_to_circle_on_cycle() {
+        let (mut workflow, nodes) = layered_workflow();
+        workflow
+            .graph
+            .add_edge(nodes["d"], nodes["a"], WorkflowEdge::default());
+
+        let layout = default_layout(&workflow, (800.0, 600.0).into());
+        assert_eq!(layout, circular(&workflow.graph, 800.0, 600.0));
+    }
+}
@@ -0,0 +1,46 @@
+/// Color scheme of the graph canvas.
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+}
+
+/// Colors used to draw the canvas. Selection and highlight colors are shared by both themes.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ThemeColors {
+    pub background: &'static str,
+    pub node_fill: &'static str,
+    pub node_stroke: &'static str,
+    pub node_text: &'static str,
+    pub edge: &'static str,
+    pub edge_label: &'static str,
+}
+
+impl Default for ThemeColors {
+    fn default() -> Self {
+        colors_for(Theme::default())
+    }
+}
+
+/// The colors of `theme`.
+pub fn colors_for(theme: Theme) -> ThemeColors {
+    match theme {
+        Theme::Light => ThemeColors {
+            background: "white",
+            node_fill: "lightblue",
+            node_stroke: "black",
+            node_text: "black",
+            edge: "blue",
+            edge_label: "red",
+        },
+        Theme::Dark => ThemeColors {
+            background: "#1f2937",
+            node_fill: "#1e3a8a",
+            node_stroke: "#e5e7eb",
+            node_text: "#f9fafb",
+            edge: "#93c5fd",
+            edge_label: "#fca5a5",
+        },
+    }
+}
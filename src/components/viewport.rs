@@ -0,0 +1,121 @@
+use crate::components::cluster::Rect;
+use crate::components::graph::Point;
+use petgraph::graph::NodeIndex;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Furthest the canvas zooms out, in canvas pixels per drawing unit.
+pub const MIN_ZOOM: f64 = 0.2;
+
+/// Furthest the canvas zooms in, in canvas pixels per drawing unit.
+pub const MAX_ZOOM: f64 = 5.0;
+
+/// The region of the drawing shown on the canvas, in drawing coordinates.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ViewBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl ViewBox {
+    /// A view of a `width` x `height` canvas at the origin, showing the drawing unscaled.
+    pub fn new(width: f64, height: f64) -> Self {
+        ViewBox {
+            x: 0.0,
+            y: 0.0,
+            width,
+            height,
+        }
+    }
+
+    /// Converts a point on the canvas element to drawing coordinates.
+    pub fn to_drawing(&self, point: Point, canvas: (f64, f64)) -> Point {
+        let (canvas_width, canvas_height) = canvas;
+        let scale_x = if canvas_width > 0.0 {
+            self.width / canvas_width
+        } else {
+            1.0
+        };
+        let scale_y = if canvas_height > 0.0 {
+            self.height / canvas_height
+        } else {
+            1.0
+        };
+        Point {
+            x: self.x + point.x * scale_x,
+            y: self.y + point.y * scale_y,
+        }
+    }
+
+    /// The view zoomed by `factor` around `anchor`, a point in drawing coordinates that stays put on the canvas. The
+    /// zoom level is kept between [`MIN_ZOOM`] and [`MAX_ZOOM`] for a canvas `canvas_width` pixels wide.
+    pub fn zoom(&self, factor: f64, anchor: &Point, canvas_width: f64) -> ViewBox {
+        let current = canvas_width / self.width;
+        let scale = current / (current * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        ViewBox {
+            x: anchor.x - (anchor.x - self.x) * scale,
+            y: anchor.y - (anchor.y - self.y) * scale,
+            width: self.width * scale,
+            height: self.height * scale,
+        }
+    }
+}
+
+/// Formats as the value of an SVG `viewBox` attribute.
+impl fmt::Display for ViewBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} {}", self.x, self.y, self.width, self.height)
+    }
+}
+
+/// The unscaled view of a `canvas` (width, height) that puts `node` in the middle. `None` if the node has no position.
+pub fn center_on(
+    positions: &HashMap<NodeIndex, Point>,
+    node: NodeIndex,
+    canvas: (f64, f64),
+) -> Option<ViewBox> {
+    let (width, height) = canvas;
+    let center = positions.get(&node)?;
+    Some(ViewBox {
+        x: center.x - width / 2.0,
+        y: center.y - height / 2.0,
+        width,
+        height,
+    })
+}
+
+/// The bounding box of every placed node, grown by `padding` on each side. `None` if no node is placed.
+pub fn content_bounds(positions: &HashMap<NodeIndex, Point>, padding: f64) -> Option<Rect> {
+    let mut points = positions.values();
+    let first = points.next()?;
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (first.x, first.y, first.x, first.y);
+    for point in points {
+        min_x = min_x.min(point.x);
+        min_y = min_y.min(point.y);
+        max_x = max_x.max(point.x);
+        max_y = max_y.max(point.y);
+    }
+    Some(Rect {
+        x: min_x - padding,
+        y: min_y - padding,
+        width: max_x - min_x + 2.0 * padding,
+        height: max_y - min_y + 2.0 * padding,
+    })
+}
+
+/// `view` moved as little as possible so that, along each axis, it overlaps `content` by at least half the smaller of
+/// the two extents. Panning and zooming can then never leave the drawing entirely out of sight.
+pub fn clamp_viewbox(view: ViewBox, content: Rect) -> ViewBox {
+    // The lowest and highest start of a `view_len` span overlapping `start..start + len` by the required amount
+    let clamp_axis = |view_start: f64, view_len: f64, start: f64, len: f64| {
+        let overlap = view_len.min(len) / 2.0;
+        view_start.clamp(start + overlap - view_len, start + len - overlap)
+    };
+    ViewBox {
+        x: clamp_axis(view.x, view.width, content.x, content.width),
+        y: clamp_axis(view.y, view.height, content.y, content.height),
+        ..view
+    }
+}
@@ -0,0 +1,67 @@
+use crate::components::graph::Point;
+use crate::Result;
+use petgraph::graph::{DiGraph, NodeIndex};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// A graph together with the canvas positions of its nodes, as stored on disk.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphDocument<N, E> {
+    pub graph: DiGraph<N, E>,
+    /// Sorted by node index so the output is stable.
+    pub positions: Vec<(NodeIndex, Point)>,
+}
+
+/// Serializes `graph` and `positions` to pretty-printed JSON.
+pub fn to_json<N, E>(graph: &DiGraph<N, E>, positions: &HashMap<NodeIndex, Point>) -> Result<String>
+where
+    N: Serialize + Clone,
+    E: Serialize + Clone,
+{
+    let mut positions: Vec<(NodeIndex, Point)> = positions
+        .iter()
+        .map(|(node_idx, point)| (*node_idx, point.clone()))
+        .collect();
+    positions.sort_by_key(|(node_idx, _)| *node_idx);
+    let document = GraphDocument {
+        graph: graph.clone(),
+        positions,
+    };
+    Ok(serde_json::to_string_pretty(&document)?)
+}
+
+/// Parses JSON written by [`to_json`].
+pub fn from_json<N, E>(json: &str) -> Result<(DiGraph<N, E>, HashMap<NodeIndex, Point>)>
+where
+    N: DeserializeOwned,
+    E: DeserializeOwned,
+{
+    let document: GraphDocument<N, E> = serde_json::from_str(json)?;
+    Ok((document.graph, document.positions.into_iter().collect()))
+}
+
+/// Writes `graph` and `positions` to `path` as JSON.
+pub fn save_to_path<N, E>(
+    path: &Path,
+    graph: &DiGraph<N, E>,
+    positions: &HashMap<NodeIndex, Point>,
+) -> Result<()>
+where
+    N: Serialize + Clone,
+    E: Serialize + Clone,
+{
+    fs::write(path, to_json(graph, positions)?)?;
+    Ok(())
+}
+
+/// Reads a graph and its positions saved with [`save_to_path`].
+pub fn load_from_path<N, E>(path: &Path) -> Result<(DiGraph<N, E>, HashMap<NodeIndex, Point>)>
+where
+    N: DeserializeOwned,
+    E: DeserializeOwned,
+{
+    from_json(&fs::read_to_string(path)?)
+}
@@ -0,0 +1,6 @@
+#![allow(unused)]
+mod json;
+#[cfg(test)]
+mod tests;
+
+pub use json::{from_json, load_from_path, save_to_path, to_json, GraphDocument};
@@ -0,0 +1,94 @@
+use super::{from_json, load_from_path, save_to_path, to_json};
+use crate::components::graph::Point;
+use petgraph::graph::{DiGraph, NodeIndex};
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn city_graph() -> (DiGraph<String, i32>, HashMap<NodeIndex, Point>) {
+        let mut graph = DiGraph::new();
+        let nyc = graph.add_node("New York".to_string());
+        let la = graph.add_node("Los Angeles".to_string());
+        let chicago = graph.add_node("Chicago".to_string());
+        graph.add_edge(nyc, la, 100);
+        graph.add_edge(nyc, chicago, 50);
+
+        let positions = HashMap::from([
+            (nyc, Point { x: 10.0, y: 20.0 }),
+            (la, Point { x: 30.0, y: 40.0 }),
+            (chicago, Point { x: 50.0, y: 60.0 }),
+        ]);
+        (graph, positions)
+    }
+
+    fn labels_and_weights(
+        graph: &DiGraph<String, i32>,
+    ) -> (Vec<String>, Vec<(String, String, i32)>) {
+        let labels = graph.node_weights().cloned().collect();
+        let weights = graph
+            .edge_indices()
+            .map(|edge_idx| {
+                let (source, target) = graph.edge_endpoints(edge_idx).unwrap();
+                (
+                    graph[source].clone(),
+                    graph[target].clone(),
+                    graph[edge_idx],
+                )
+            })
+            .collect();
+        (labels, weights)
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let (graph, positions) = city_graph();
+        let json = to_json(&graph, &positions).unwrap();
+        let (loaded, loaded_positions) = from_json::<String, i32>(&json).unwrap();
+
+        assert_eq!(labels_and_weights(&loaded), labels_and_weights(&graph));
+        assert_eq!(loaded_positions, positions);
+    }
+
+    #[test]
+    fn test_save_edited_graph_and_reload() {
+        let (mut graph, mut positions) = city_graph();
+
+        // Edit: rename a node, reweight an edge, add a node with an edge and move it
+        let la = NodeIndex::new(1);
+        graph[la] = "LA".to_string();
+        let first_edge = graph.edge_indices().next().unwrap();
+        graph[first_edge] = 250;
+        let houston = graph.add_node("Houston".to_string());
+        graph.add_edge(la, houston, 120);
+        positions.insert(houston, Point { x: 70.0, y: 80.0 });
+
+        let path = std::env::temp_dir().join(format!("graph-io-{}.json", uuid::Uuid::new_v4()));
+        save_to_path(&path, &graph, &positions).unwrap();
+        let reloaded = load_from_path::<String, i32>(&path);
+        std::fs::remove_file(&path).ok();
+        let (loaded, loaded_positions) = reloaded.unwrap();
+
+        let (labels, weights) = labels_and_weights(&loaded);
+        assert_eq!(labels, vec!["New York", "LA", "Chicago", "Houston"]);
+        assert_eq!(
+            weights,
+            vec![
+                ("New York".to_string(), "LA".to_string(), 250),
+                ("New York".to_string(), "Chicago".to_string(), 50),
+                ("LA".to_string(), "Houston".to_string(), 120),
+            ]
+        );
+        assert_eq!(
+            loaded_positions.get(&houston),
+            Some(&Point { x: 70.0, y: 80.0 })
+        );
+    }
+
+    #[test]
+    fn test_load_missing_file_fails() {
+        let path = std::env::temp_dir().join(format!("graph-io-{}.json", uuid::Uuid::new_v4()));
+        assert!(load_from_path::<String, i32>(&path).is_err());
+    }
+}
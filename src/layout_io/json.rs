@@ -0,0 +1,61 @@
+use crate::components::graph::Point;
+use crate::workflow::Workflow;
+use crate::Result;
+use petgraph::graph::NodeIndex;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Serializes a layout to pretty-printed JSON: an object mapping each node id to its `{ "x", "y" }` position, sorted
+/// by id so the file diffs cleanly under version control.
+pub fn to_json(layout: &HashMap<String, Point>) -> Result<String> {
+    let sorted: BTreeMap<&String, &Point> = layout.iter().collect();
+    Ok(serde_json::to_string_pretty(&sorted)?)
+}
+
+/// Parses JSON written by [`to_json`].
+pub fn from_json(json: &str) -> Result<HashMap<String, Point>> {
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Writes the node positions in `layout`, keyed by node id, to `path`.
+pub fn save_layout(path: &Path, layout: &HashMap<String, Point>) -> Result<()> {
+    fs::write(path, to_json(layout)?)?;
+    Ok(())
+}
+
+/// Reads a layout saved with [`save_layout`].
+pub fn load_layout(path: &Path) -> Result<HashMap<String, Point>> {
+    from_json(&fs::read_to_string(path)?)
+}
+
+/// Canvas positions keyed by the id of their node in `workflow`, ready to be saved. Positions of nodes no longer in
+/// the graph are dropped.
+pub fn positions_by_id(
+    workflow: &Workflow,
+    positions: &HashMap<NodeIndex, Point>,
+) -> HashMap<String, Point> {
+    positions
+        .iter()
+        .filter_map(|(node_idx, point)| {
+            let node = workflow.graph.node_weight(*node_idx)?;
+            Some((node.id.clone(), point.clone()))
+        })
+        .collect()
+}
+
+/// Canvas positions for the nodes of `workflow` found in `layout`. Ids without a node are ignored, and nodes without
+/// an entry are left for the canvas to place.
+pub fn positions_by_index(
+    workflow: &Workflow,
+    layout: &HashMap<String, Point>,
+) -> HashMap<NodeIndex, Point> {
+    workflow
+        .graph
+        .node_indices()
+        .filter_map(|node_idx| {
+            let point = layout.get(&workflow.graph[node_idx].id)?;
+            Some((node_idx, point.clone()))
+        })
+        .collect()
+}
@@ -0,0 +1,6 @@
+#![allow(unused)]
+mod json;
+#[cfg(test)]
+mod tests;
+
+pub use json::{from_json, load_layout, positions_by_id, positions_by_index, save_layout, to_json};
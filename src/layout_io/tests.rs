@@ -0,0 +1,69 @@
+use super::{from_json, load_layout, positions_by_id, positions_by_index, save_layout, to_json};
+use crate::components::graph::Point;
+use crate::workflow::{Node, Workflow};
+use petgraph::graph::NodeIndex;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> HashMap<String, Point> {
+        HashMap::from([
+            ("reader".to_string(), Point { x: 10.0, y: 20.0 }),
+            ("sink".to_string(), Point { x: 30.5, y: -4.0 }),
+        ])
+    }
+
+    #[test]
+    fn test_layout_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("layout-io-{}.json", uuid::Uuid::new_v4()));
+        save_layout(&path, &layout()).unwrap();
+        let reloaded = load_layout(&path);
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(reloaded.unwrap(), layout());
+    }
+
+    #[test]
+    fn test_layout_json_is_keyed_by_sorted_id() {
+        let json = to_json(&layout()).unwrap();
+        let reader = json.find("\"reader\"").unwrap();
+        let sink = json.find("\"sink\"").unwrap();
+        assert!(reader < sink);
+        assert!(json.contains("\"x\": 30.5"));
+        assert_eq!(from_json(&json).unwrap(), layout());
+    }
+
+    #[test]
+    fn test_positions_convert_between_ids_and_indices() {
+        let mut workflow = Workflow::default();
+        let reader = workflow.graph.add_node(Node {
+            id: "reader".to_string(),
+            ..Default::default()
+        });
+        let filter = workflow.graph.add_node(Node {
+            id: "filter".to_string(),
+            ..Default::default()
+        });
+
+        // "sink" has no node, and "filter" has no saved position
+        let by_index = positions_by_index(&workflow, &layout());
+        assert_eq!(
+            by_index,
+            HashMap::from([(reader, Point { x: 10.0, y: 20.0 })])
+        );
+
+        let mut positions = by_index.clone();
+        positions.insert(filter, Point { x: 1.0, y: 2.0 });
+        positions.insert(NodeIndex::new(9), Point { x: 0.0, y: 0.0 });
+        let by_id = positions_by_id(&workflow, &positions);
+        assert_eq!(
+            by_id,
+            HashMap::from([
+                ("reader".to_string(), Point { x: 10.0, y: 20.0 }),
+                ("filter".to_string(), Point { x: 1.0, y: 2.0 }),
+            ])
+        );
+    }
+}
@@ -1,5 +1,8 @@
 mod error;
+mod graph_io;
+mod layout_io;
 mod mytracer;
+mod uri;
 mod workflow;
 
 pub use error::{Error, Result};
@@ -0,0 +1,6 @@
+#![allow(unused)]
+mod uri;
+#[cfg(test)]
+mod tests;
+
+pub use uri::{Protocol, Uri};
@@ -0,0 +1,219 @@
+use super::{Protocol, Uri};
+use std::path::Path;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_file_uri() {
+        let uri = Uri::parse_str("file:///data/solar/../workflow.yaml").unwrap();
+        assert_eq!(uri.protocol(), Protocol::File);
+        assert_eq!(uri.as_str(), "file:///data/workflow.yaml");
+        assert_eq!(uri.extension(), Some("yaml"));
+        assert!(!uri.is_dir());
+    }
+
+    #[test]
+    fn test_parse_relative_path_is_absolute() {
+        let uri = Uri::parse_str("documents/solar-radiation/").unwrap();
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(
+            uri.to_path_buf().unwrap(),
+            cwd.join("documents").join("solar-radiation")
+        );
+        assert!(uri.is_dir());
+        assert_eq!(uri.file_name(), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_nul_byte() {
+        let err = Uri::parse_str("file:///data/work\0flow.yaml").unwrap_err();
+        assert!(
+            matches!(&err, crate::Error::Uri(msg) if msg.contains("NUL")),
+            "unexpected error: {}",
+            err
+        );
+        assert!(Uri::parse_str("data/\0.yaml").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_whitespace_in_host() {
+        let err = Uri::parse_str("https://example .com/workflow.yaml").unwrap_err();
+        assert!(
+            matches!(&err, crate::Error::Uri(msg) if msg.contains("whitespace")),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_allows_spaces_in_file_paths() {
+        let uri = Uri::parse_str("file:///my data/solar workflow.yaml").unwrap();
+        assert_eq!(uri.as_str(), "file:///my data/solar workflow.yaml");
+        assert_eq!(uri.file_name(), Some("solar workflow.yaml"));
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_protocol_and_empty() {
+        assert!(matches!(
+            Uri::parse_str("ftp://example.com/a.yaml"),
+            Err(crate::Error::Uri(_))
+        ));
+        assert!(matches!(Uri::parse_str(""), Err(crate::Error::Uri(_))));
+    }
+
+    #[test]
+    fn test_with_extension_replaces_extension() {
+        let uri = Uri::parse_str("file:///data/solar/radiation.csv").unwrap();
+        let replaced = uri.with_extension("parquet");
+        assert_eq!(replaced.as_str(), "file:///data/solar/radiation.parquet");
+        assert_eq!(replaced.protocol(), Protocol::File);
+        assert_eq!(replaced.extension(), Some("parquet"));
+    }
+
+    #[test]
+    fn test_with_extension_adds_missing_extension() {
+        let uri = Uri::parse_str("https://example.com/data/radiation").unwrap();
+        assert_eq!(
+            uri.with_extension("csv").as_str(),
+            "https://example.com/data/radiation.csv"
+        );
+    }
+
+    #[test]
+    fn test_with_extension_on_directory_is_noop() {
+        let uri = Uri::parse_str("file:///data/solar/").unwrap();
+        assert_eq!(uri.with_extension("csv"), uri);
+    }
+
+    #[test]
+    fn test_has_extension_ignores_case() {
+        let uri = Uri::parse_str("file:///data/Workflow.YAML").unwrap();
+        assert!(uri.has_extension("yaml"));
+        assert!(uri.has_extension(".Yaml"));
+        assert!(!uri.has_extension("yml"));
+    }
+
+    #[test]
+    fn test_has_extension_without_extension() {
+        let file = Uri::parse_str("file:///data/Makefile").unwrap();
+        assert!(!file.has_extension("yaml"));
+        assert!(!file.has_extension(""));
+
+        let dir = Uri::parse_str("file:///data.yaml/").unwrap();
+        assert!(!dir.has_extension("yaml"));
+    }
+
+    #[test]
+    fn test_same_resource_ignores_trailing_separator() {
+        let dir = Uri::parse_str("file:///a/b/").unwrap();
+        let file = Uri::parse_str("file:///a/b").unwrap();
+        assert_ne!(dir, file);
+        assert!(dir.same_resource(&file));
+        assert!(file.same_resource(&dir));
+        assert!(!file.same_resource(&Uri::parse_str("file:///a/bc").unwrap()));
+        assert!(!file.same_resource(&Uri::parse_str("https://a/b").unwrap()));
+    }
+
+    #[test]
+    fn test_same_resource_case_only_when_asked() {
+        let upper = Uri::parse_str("file:///A").unwrap();
+        let lower = Uri::parse_str("file:///a/").unwrap();
+        assert!(!upper.same_resource(&lower));
+        assert!(!upper.same_resource_with(&lower, false));
+        assert!(upper.same_resource_with(&lower, true));
+    }
+
+    #[test]
+    fn test_display_relative_to_shortens_under_base() {
+        let uri =
+            Uri::parse_str("file:///home/u/documents/solar-radiation/x/workflow.yaml").unwrap();
+        assert_eq!(
+            uri.display_relative_to(Path::new("/home/u/documents")),
+            "solar-radiation/x/workflow.yaml"
+        );
+        assert_eq!(
+            uri.display_relative_to(Path::new("/home/u/documents/")),
+            "solar-radiation/x/workflow.yaml"
+        );
+
+        let dir = Uri::parse_str("file:///home/u/documents/solar-radiation/").unwrap();
+        assert_eq!(
+            dir.display_relative_to(Path::new("/home/u/documents")),
+            "solar-radiation/"
+        );
+    }
+
+    #[test]
+    fn test_display_relative_to_falls_back_outside_base() {
+        let uri = Uri::parse_str("file:///home/u/documents/workflow.yaml").unwrap();
+        assert_eq!(
+            uri.display_relative_to(Path::new("/home/u/other")),
+            "file:///home/u/documents/workflow.yaml"
+        );
+        // Only whole components count, `/home/u/doc` is not a parent of `/home/u/documents`
+        assert_eq!(
+            uri.display_relative_to(Path::new("/home/u/doc")),
+            "file:///home/u/documents/workflow.yaml"
+        );
+
+        let remote = Uri::parse_str("https://example.com/workflow.yaml").unwrap();
+        assert_eq!(
+            remote.display_relative_to(Path::new("/")),
+            "https://example.com/workflow.yaml"
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_existing_resolves_file() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        let file = dir.join("workflow.yaml");
+        std::fs::write(&file, "id: wf\n").unwrap();
+
+        let written = format!("file://{}/nested/../workflow.yaml", dir.display());
+        let canonical = Uri::parse_str(&written).unwrap().canonicalize_existing();
+        let expected = std::fs::canonicalize(&file).unwrap();
+        let dir_uri = Uri::parse_str(&format!("file://{}/nested", dir.display()))
+            .unwrap()
+            .canonicalize_existing();
+        // A symlink resolves to the file it points at
+        #[cfg(unix)]
+        let linked = {
+            let link = dir.join("link.yaml");
+            std::os::unix::fs::symlink(&file, &link).unwrap();
+            Uri::parse_str(&format!("file://{}", link.display()))
+                .unwrap()
+                .canonicalize_existing()
+        };
+        std::fs::remove_dir_all(&dir).ok();
+
+        let canonical = canonical.unwrap();
+        assert_eq!(canonical.to_path_buf(), Some(expected));
+        assert!(!canonical.is_dir());
+        // Directories come back in directory form
+        assert!(dir_uri.unwrap().is_dir());
+        #[cfg(unix)]
+        assert_eq!(linked.unwrap(), canonical);
+    }
+
+    #[test]
+    fn test_canonicalize_existing_rejects_missing_file() {
+        let missing = std::env::temp_dir().join(format!("{}.yaml", uuid::Uuid::new_v4()));
+        let uri = Uri::parse_str(&format!("file://{}", missing.display())).unwrap();
+        assert!(matches!(
+            uri.canonicalize_existing(),
+            Err(crate::Error::Uri(msg)) if msg.contains("cannot canonicalize")
+        ));
+    }
+
+    #[test]
+    fn test_canonicalize_existing_rejects_other_protocols() {
+        let uri = Uri::parse_str("https://example.com/workflow.yaml").unwrap();
+        assert!(matches!(
+            uri.canonicalize_existing(),
+            Err(crate::Error::Uri(msg)) if msg.contains("only file uris")
+        ));
+    }
+}
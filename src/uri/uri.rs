@@ -0,0 +1,261 @@
+use crate::{Error, Result};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+/// The schemes a [`Uri`] can have.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq, Hash)]
+pub enum Protocol {
+    File,
+    Http,
+    Https,
+}
+
+impl Protocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Protocol::File => "file",
+            Protocol::Http => "http",
+            Protocol::Https => "https",
+        }
+    }
+
+    fn from_scheme(scheme: &str) -> Option<Self> {
+        match scheme.to_ascii_lowercase().as_str() {
+            "file" => Some(Protocol::File),
+            "http" => Some(Protocol::Http),
+            "https" => Some(Protocol::Https),
+            _ => None,
+        }
+    }
+}
+
+/// A location of a workflow or dataset, always stored with its scheme, e.g. `file:///data/workflow.yaml`. Plain paths
+/// are turned into absolute `file://` URIs. A trailing `/` marks a directory.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Uri {
+    uri: String,
+    protocol: Protocol,
+}
+
+impl Uri {
+    /// Parses `s` as either `<scheme>://...` or a local path, which is resolved against the current directory.
+    ///
+    /// Rejects empty input, NUL bytes, unknown schemes and, for network protocols, a missing host or whitespace in
+    /// it. Spaces are fine in `file://` paths.
+    pub fn parse_str(s: &str) -> Result<Self> {
+        if s.is_empty() {
+            return Err(Error::Uri("uri is empty".to_string()));
+        }
+        if s.contains('\0') {
+            return Err(Error::Uri(format!(
+                "uri {:?} contains a NUL byte",
+                s.replace('\0', "\\0")
+            )));
+        }
+
+        let Some((scheme, rest)) = s.split_once("://") else {
+            let path = std::env::current_dir()?.join(s);
+            return Ok(Self::from_file_path(&path, is_dir_form(s)));
+        };
+
+        let protocol = Protocol::from_scheme(scheme)
+            .ok_or_else(|| Error::Uri(format!("unsupported protocol `{}` in {}", scheme, s)))?;
+        match protocol {
+            Protocol::File => {
+                if !rest.starts_with('/') {
+                    return Err(Error::Uri(format!(
+                        "file uri {} is not an absolute path",
+                        s
+                    )));
+                }
+                Ok(Self::from_file_path(Path::new(rest), is_dir_form(rest)))
+            }
+            Protocol::Http | Protocol::Https => {
+                let host = rest.split('/').next().unwrap_or_default();
+                if host.is_empty() {
+                    return Err(Error::Uri(format!("uri {} has no host", s)));
+                }
+                if host.chars().any(char::is_whitespace) {
+                    return Err(Error::Uri(format!(
+                        "host `{}` in {} contains whitespace",
+                        host, s
+                    )));
+                }
+                Ok(Uri {
+                    uri: format!("{}://{}", protocol.as_str(), rest),
+                    protocol,
+                })
+            }
+        }
+    }
+
+    /// A `file://` URI for `path`, with `.` and `..` components resolved without touching the filesystem.
+    fn from_file_path(path: &Path, dir: bool) -> Self {
+        let mut normalized = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    normalized.pop();
+                }
+                other => normalized.push(other),
+            }
+        }
+        let mut path = normalized.to_string_lossy().replace('\\', "/");
+        if !path.starts_with('/') {
+            path.insert(0, '/');
+        }
+        if dir && !path.ends_with('/') {
+            path.push('/');
+        }
+        Uri {
+            uri: format!("file://{}", path),
+            protocol: Protocol::File,
+        }
+    }
+
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.uri
+    }
+
+    /// Everything after `<scheme>://`: the absolute path for `file://`, host and path otherwise.
+    pub fn path(&self) -> &str {
+        &self.uri[self.protocol.as_str().len() + "://".len()..]
+    }
+
+    /// Whether this is a directory URI, written with a trailing `/`.
+    pub fn is_dir(&self) -> bool {
+        self.uri.ends_with('/')
+    }
+
+    /// The last path component, or `None` for directories.
+    pub fn file_name(&self) -> Option<&str> {
+        if self.is_dir() {
+            return None;
+        }
+        self.path()
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+    }
+
+    /// The extension of the file name, without the dot.
+    pub fn extension(&self) -> Option<&str> {
+        let file_name = self.file_name()?;
+        match file_name.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() && !ext.is_empty() => Some(ext),
+            _ => None,
+        }
+    }
+
+    /// Whether the extension is `ext` (with or without the leading dot), ignoring ASCII case.
+    pub fn has_extension(&self, ext: &str) -> bool {
+        self.extension()
+            .is_some_and(|current| current.eq_ignore_ascii_case(ext.trim_start_matches('.')))
+    }
+
+    /// A copy with the file name's extension replaced by `ext`, or added if there is none. Directory URIs are
+    /// returned unchanged.
+    pub fn with_extension(&self, ext: &str) -> Uri {
+        let Some(file_name) = self.file_name() else {
+            return self.clone();
+        };
+        let stem = match self.extension() {
+            Some(current) => &file_name[..file_name.len() - current.len() - 1],
+            None => file_name,
+        };
+        let dir = &self.uri[..self.uri.len() - file_name.len()];
+        let ext = ext.trim_start_matches('.');
+        let uri = if ext.is_empty() {
+            format!("{}{}", dir, stem)
+        } else {
+            format!("{}{}.{}", dir, stem, ext)
+        };
+        Uri {
+            uri,
+            protocol: self.protocol,
+        }
+    }
+
+    /// Whether both URIs name the same resource: the same protocol and the same path once a trailing `/` is dropped,
+    /// so a directory matches the same location written as a file. Unlike `==`, which compares the exact text.
+    pub fn same_resource(&self, other: &Uri) -> bool {
+        self.same_resource_with(other, false)
+    }
+
+    /// [`Uri::same_resource`], additionally ignoring ASCII case in the path when `ignore_case` is set, as on
+    /// case-insensitive filesystems.
+    pub fn same_resource_with(&self, other: &Uri, ignore_case: bool) -> bool {
+        if self.protocol != other.protocol {
+            return false;
+        }
+        let (path, other_path) = (
+            self.path().trim_end_matches('/'),
+            other.path().trim_end_matches('/'),
+        );
+        if ignore_case {
+            path.eq_ignore_ascii_case(other_path)
+        } else {
+            path == other_path
+        }
+    }
+
+    /// The path of a `file://` URI relative to the absolute directory `base`, e.g.
+    /// `solar-radiation/solar-potential/workflow.yaml` against the `documents` directory, keeping the trailing `/` of
+    /// directories. The URI itself when it isn't under `base`.
+    pub fn display_relative_to(&self, base: &Path) -> String {
+        let relative = self
+            .to_path_buf()
+            .and_then(|path| Some(path.strip_prefix(base).ok()?.to_path_buf()));
+        match relative {
+            Some(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+            Some(relative) => {
+                let mut shown = relative.to_string_lossy().replace('\\', "/");
+                if self.is_dir() {
+                    shown.push('/');
+                }
+                shown
+            }
+            None => self.uri.clone(),
+        }
+    }
+
+    /// The local path of a `file://` URI.
+    pub fn to_path_buf(&self) -> Option<PathBuf> {
+        (self.protocol == Protocol::File).then(|| PathBuf::from(self.path()))
+    }
+
+    /// The `file://` URI of the file or directory this one names, as the filesystem resolves it: symlinks followed
+    /// and every component checked to exist. Unlike [`Uri::parse_str`], which only normalizes the text. Fails for a
+    /// path that doesn't exist and for protocols other than `file://`.
+    pub fn canonicalize_existing(&self) -> Result<Uri> {
+        let Some(path) = self.to_path_buf() else {
+            return Err(Error::Uri(format!(
+                "cannot canonicalize {}: only file uris can be checked on the filesystem",
+                self.uri
+            )));
+        };
+        let canonical = std::fs::canonicalize(&path)
+            .map_err(|e| Error::Uri(format!("cannot canonicalize {}: {}", self.uri, e)))?;
+        Ok(Self::from_file_path(&canonical, canonical.is_dir()))
+    }
+}
+
+impl fmt::Display for Uri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.uri)
+    }
+}
+
+/// Whether a path is written in directory form, ending in a separator.
+fn is_dir_form(path: &str) -> bool {
+    path.ends_with('/') || path.ends_with(std::path::MAIN_SEPARATOR)
+}
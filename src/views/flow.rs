@@ -1,15 +1,159 @@
-use crate::components::graph::Graph;
-use crate::workflow::Workflow;
+use crate::components::download::download_file;
+use crate::components::graph::Point;
+use crate::components::Flow;
+use crate::layout_io;
+use crate::uri::Uri;
+use crate::workflow::{Warning, Workflow};
+use crate::Error;
 use dioxus::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::{fs, path::Path};
 
+/// Reads the size of the workflow file at `path` and loads it. This is synchronous; the page runs it on a blocking
+/// task so large multi-include workflows don't freeze the UI.
+pub fn load(path: &Path) -> crate::Result<(u64, Workflow)> {
+    load_reporting(path, |_| {}).map(|(size, workflow, _)| (size, workflow))
+}
+
+/// Like [`load`], calling `on_file` with each file as it is read and also returning the include-expanded YAML that
+/// was parsed, see [`Workflow::load_with_progress`].
+pub fn load_reporting(
+    path: &Path,
+    on_file: impl FnMut(&Path),
+) -> crate::Result<(u64, Workflow, String)> {
+    let size = fs::metadata(path)?.len();
+    let (workflow, resolved) = Workflow::load_with_progress(path.to_path_buf(), on_file)?;
+    Ok((size, workflow, resolved))
+}
+
+/// What the page hears about a workflow being loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadEvent {
+    /// A new load began.
+    Started,
+    /// The loader read this file, the workflow file or one of its includes.
+    Read(String),
+}
+
+/// The files processed so far once `event` is applied to `processed`: a new load starts over and each file read is
+/// appended, so the last entry is the file being worked on.
+pub fn reduce_progress(processed: &[String], event: LoadEvent) -> Vec<String> {
+    match event {
+        LoadEvent::Started => Vec::new(),
+        LoadEvent::Read(path) => processed
+            .iter()
+            .cloned()
+            .chain(std::iter::once(path))
+            .collect(),
+    }
+}
+
+/// Checks that `path` names an existing `.yaml`/`.yml` file, returning a hint for the user otherwise.
+pub fn validate_path(path: &str) -> Result<(), String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("Enter the path to a workflow file".to_string());
+    }
+    let path = Path::new(trimmed);
+    if !path.exists() {
+        return Err(format!("{} does not exist", trimmed));
+    }
+    if !path.is_file() {
+        return Err(format!("{} is not a file", trimmed));
+    }
+    let uri = Uri::parse_str(trimmed).map_err(|e| e.to_string())?;
+    if uri.has_extension("yaml") || uri.has_extension("yml") {
+        Ok(())
+    } else {
+        Err(format!("{} is not a .yaml or .yml file", trimmed))
+    }
+}
+
+/// Where the layout of the workflow at `workflow_path` is saved: next to it, as `<name>.layout.json`.
+pub fn layout_path(workflow_path: &Path) -> PathBuf {
+    workflow_path.with_extension("layout.json")
+}
+
+/// `path` as shown to the user: relative to the current directory when it is under it, unless `absolute` is set.
+fn shown_path(path: &str, absolute: bool) -> String {
+    match (Uri::parse_str(path), std::env::current_dir()) {
+        (Ok(uri), Ok(cwd)) if !absolute => uri.display_relative_to(&cwd),
+        (Ok(uri), _) => uri.to_string(),
+        (Err(_), _) => path.to_string(),
+    }
+}
+
+/// How many recently loaded paths are remembered.
+const HISTORY_CAP: usize = 10;
+
+/// `localStorage` key the history is persisted under.
+const HISTORY_STORAGE_KEY: &str = "dx-solar-flow.workflow-history";
+
+/// Puts `path` at the front of `list`, dropping any earlier occurrence and anything beyond `cap` entries.
+pub fn push_history(list: &[String], path: &str, cap: usize) -> Vec<String> {
+    std::iter::once(path.to_string())
+        .chain(list.iter().filter(|entry| *entry != path).cloned())
+        .take(cap)
+        .collect()
+}
+
+/// Reads the persisted history. A missing or unreadable entry gives an empty history.
+async fn read_history() -> Vec<String> {
+    let script = format!(
+        "return JSON.parse(localStorage.getItem({}) || \"[]\");",
+        serde_json::to_string(HISTORY_STORAGE_KEY).unwrap_or_default()
+    );
+    document::eval(&script)
+        .join::<Vec<String>>()
+        .await
+        .unwrap_or_default()
+}
+
+/// Persists the history. Failures are ignored, the history then only lives for this session.
+fn write_history(history: &[String]) {
+    let script = format!(
+        "localStorage.setItem({}, JSON.stringify({}));",
+        serde_json::to_string(HISTORY_STORAGE_KEY).unwrap_or_default(),
+        serde_json::to_string(history).unwrap_or_default()
+    );
+    document::eval(&script);
+}
+
 #[component]
 pub fn FlowPage() -> Element {
     let mut workflow_file_path = use_signal(|| String::new());
     let mut file_info = use_signal(|| None::<Result<u64, String>>);
     let mut is_loading = use_signal(|| false);
-    let mut workflow = use_signal::<Option<Workflow>>(|| None);
+    // The loaded workflow file with its includes spliced in, as it was parsed
+    let mut resolved_yaml = use_signal(|| None::<String>);
+    // Files read by the load in progress, see `reduce_progress`
+    let mut processed = use_signal(Vec::<String>::new);
+    let mut workflow = use_signal(Workflow::default);
+    let mut workflow_loaded = use_signal(|| false);
     let mut workflow_err = use_signal(|| None);
+    let mut workflow_warnings = use_signal(Vec::<Warning>::new);
+    // Checks the loaded workflow fails. It is still shown, so the problems can be fixed on the canvas
+    let mut workflow_problems = use_signal(Vec::<String>::new);
+    let mut history = use_signal(Vec::<String>::new);
+    // Path of the workflow on the canvas, which its layout file is named after
+    let mut loaded_path = use_signal(String::new);
+    // Shared with the canvas, which keeps it up to date as nodes are dragged. Keyed by node id, like the layout file
+    let mut positions = use_signal(HashMap::<String, Point>::new);
+    let mut layout_status = use_signal(|| None::<Result<String, String>>);
+    // Bumped on every layout load so the canvas remounts with the loaded positions
+    let mut layout_count = use_signal(|| 0);
+    // Whether the loaded path is shown in full rather than relative to the current directory
+    let mut show_absolute_path = use_signal(|| false);
+
+    // Restore the persisted history once
+    use_future(move || async move {
+        let restored = read_history().await;
+        history.set(restored);
+    });
+
+    let path_check = validate_path(&workflow_file_path.read());
+    let can_load = !*is_loading.read() && path_check.is_ok();
 
     rsx! {
         div { class: "container mx-auto p-4",
@@ -29,7 +173,31 @@ pub fn FlowPage() -> Element {
                 }
             }
 
-            div { class: "mb-4",
+            // Checks the loaded workflow fails
+            if !workflow_problems.read().is_empty() {
+                div { class: "mb-4 p-3 bg-red-100 text-red-700 rounded",
+                    div { class: "font-bold", "Workflow Problems" }
+                    ul { class: "list-disc ml-5",
+                        for (i, problem) in workflow_problems.read().iter().enumerate() {
+                            li { key: "{i}", "{problem}" }
+                        }
+                    }
+                }
+            }
+
+            // Non-fatal issues found in the loaded workflow
+            if !workflow_warnings.read().is_empty() {
+                div { class: "mb-4 p-3 bg-yellow-100 text-yellow-800 rounded",
+                    div { class: "font-bold", "Workflow Warnings" }
+                    ul { class: "list-disc ml-5",
+                        for (i, warning) in workflow_warnings.read().iter().enumerate() {
+                            li { key: "{i}", "{warning}" }
+                        }
+                    }
+                }
+            }
+
+            div { class: "mb-4 flex space-x-2",
                 input {
                     class: "border border-gray-300 rounded px-3 py-2 w-full max-w-md",
                     r#type: "text",
@@ -38,39 +206,87 @@ pub fn FlowPage() -> Element {
                     disabled: *is_loading.read(),
                     oninput: move |evt| workflow_file_path.set(evt.value().to_string()),
                 }
+                // Recently loaded paths; picking one fills in the field
+                if !history.read().is_empty() {
+                    select {
+                        class: "border border-gray-300 rounded px-2 py-2 max-w-xs",
+                        disabled: *is_loading.read(),
+                        value: "",
+                        onchange: move |evt| {
+                            let picked = evt.value();
+                            if !picked.is_empty() {
+                                workflow_file_path.set(picked);
+                            }
+                        },
+                        option { value: "", "Recent files" }
+                        for path in history.read().iter() {
+                            option { key: "{path}", value: "{path}", "{path}" }
+                        }
+                    }
+                }
+            }
+            if let Err(hint) = &path_check {
+                div { class: "-mt-3 mb-4 text-sm text-gray-500", "{hint}" }
             }
 
             div { class: "mb-4",
                 button {
-                    class: if *is_loading.read() { "bg-gray-400 text-white font-bold py-2 px-4 rounded cursor-not-allowed" } else { "bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded" },
-                    disabled: *is_loading.read(),
+                    class: if can_load { "bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded" } else { "bg-gray-400 text-white font-bold py-2 px-4 rounded cursor-not-allowed" },
+                    disabled: !can_load,
                     onclick: move |_| {
-                        let workflow_path = workflow_file_path.read().clone();
+                        let workflow_path = workflow_file_path.read().trim().to_string();
                         let workflow_path = Path::new(&workflow_path).to_path_buf();
-                        if workflow_path.is_file() {
+                        if validate_path(&workflow_file_path.read()).is_ok() {
                             is_loading.set(true);
+                            let restarted = reduce_progress(&processed.read(), LoadEvent::Started);
+                            processed.set(restarted);
+                            let loaded_path = workflow_path.display().to_string();
+                            let loaded_path_for_layout = loaded_path.clone();
 
-                            // Attempt to read file metadata
-                            match fs::metadata(&workflow_path) {
-                                Ok(metadata) => {
-                                    let size = metadata.len();
-                                    file_info.set(Some(Ok(size)));
-                                    match Workflow::load_from_path(workflow_path) {
-                                        Ok(flow) => {
-                                            workflow.set(Some(flow));
-                                            workflow_err.set(None); // Clear any previous error
-                                        }
-                                        Err(e) => {
-                                            println!("{}", e);
-                                            workflow_err.set(Some(e));
-                                        }
-                                    }
+                            spawn(async move {
+                                // The loader reports each file it reads over a channel, which closes once it is done
+                                let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+                                let task = tokio::task::spawn_blocking(move || {
+                                    load_reporting(&workflow_path, |file| {
+                                        let _ = sender.send(file.display().to_string());
+                                    })
+                                });
+                                while let Some(file) = receiver.recv().await {
+                                    let updated = reduce_progress(&processed.read(), LoadEvent::Read(file));
+                                    processed.set(updated);
                                 }
-                                Err(e) => {
-                                    file_info.set(Some(Err(e.to_string())));
+                                let result = task.await.unwrap_or_else(|e| {
+                                    Err(Error::Msg(format!("workflow loading task failed: {}", e)))
+                                });
+                                match result {
+                                    Ok((size, flow, resolved)) => {
+                                        file_info.set(Some(Ok(size)));
+                                        resolved_yaml.set(Some(resolved));
+                                        workflow_warnings.set(flow.lint());
+                                        workflow_problems.set(flow.validation_problems());
+                                        workflow.set(flow);
+                                        workflow_loaded.set(true);
+                                        workflow_err.set(None); // Clear any previous error
+                                        // A new workflow starts from the default layout
+                                        positions.set(HashMap::new());
+                                        layout_status.set(None);
+                                        loaded_path.set(loaded_path_for_layout);
+
+                                        let updated = push_history(&history.read(), &loaded_path, HISTORY_CAP);
+                                        write_history(&updated);
+                                        history.set(updated);
+                                    }
+                                    Err(e) => {
+                                        println!("{}", e);
+                                        file_info.set(None);
+                                        workflow_warnings.set(Vec::new());
+                                        workflow_problems.set(Vec::new());
+                                        resolved_yaml.set(None);
+                                        workflow_err.set(Some(e));
+                                    }
                                 }
-                            }
-                            is_loading.set(false);
+                                is_loading.set(false);
+                            });
                         }
 
                     },
@@ -85,18 +301,52 @@ pub fn FlowPage() -> Element {
             // Display file info or error
             {
                 if *is_loading.read() {
+                    let current = processed
+                        .read()
+                        .last()
+                        .cloned()
+                        .unwrap_or_else(|| workflow_file_path.read().trim().to_string());
+                    let current = shown_path(&current, show_absolute_path());
+                    let count = processed.read().len();
                     rsx! {
-                        div { class: "text-blue-600", "Loading file: {workflow_file_path.read()}" }
+                        div { class: "flex items-center gap-2 text-blue-600",
+                            div { class: "h-4 w-4 animate-spin rounded-full border-2 border-blue-600 border-t-transparent" }
+                            span { "Loading {current}" }
+                            if count > 1 {
+                                span { class: "text-sm text-gray-500", "({count} files read)" }
+                            }
+                        }
                     }
                 } else if let Some(result) = file_info.read().as_ref() {
                     match result {
                         Ok(size) => {
                             // Show success message if workflow loaded successfully
-                            if let Some(wf) = workflow.read().as_ref() {
-                                let node_count = wf.graph.node_count();
-                                let edge_count = wf.graph.edge_count();
+                            if *workflow_loaded.read() {
+                                let node_count = workflow.read().graph.node_count();
+                                let edge_count = workflow.read().graph.edge_count();
+                                let shown = shown_path(&loaded_path.read(), show_absolute_path());
                                 rsx! {
                                     div { class: "text-green-600", "Workflow loaded successfully: {node_count} nodes, {edge_count} edges" }
+                                    div { class: "text-sm text-gray-600",
+                                        "{shown} "
+                                        button {
+                                            class: "text-blue-600 hover:underline",
+                                            onclick: move |_| show_absolute_path.toggle(),
+                                            if show_absolute_path() {
+                                                "Show relative"
+                                            } else {
+                                                "Show absolute"
+                                            }
+                                        }
+                                    }
+                                    if let Some(resolved) = resolved_yaml.read().as_ref() {
+                                        details { class: "mt-2 text-sm",
+                                            summary { class: "cursor-pointer text-gray-600", "Resolved YAML" }
+                                            pre { class: "mt-1 max-h-64 overflow-auto rounded border border-gray-300 bg-gray-50 p-2 font-mono text-xs select-text",
+                                                "{resolved}"
+                                            }
+                                        }
+                                    }
                                 }
                             } else {
                                 rsx! {
@@ -118,17 +368,64 @@ pub fn FlowPage() -> Element {
 
             }
 
-            // Render the Graph component if workflow is loaded successfully
-            {
-                if let Some(wf) = workflow.read().as_ref() {
-                    let workflow_signal = use_signal(move || wf.graph.clone());
-                    rsx! {
-                        div { class: "mt-6 w-full h-[600px]",
-                            Graph { graph: workflow_signal }
+            // Render the Flow component if workflow is loaded successfully. Keying on the id remounts it, resetting
+            // the layout, whenever a different workflow is loaded, and on the layout count when a layout is loaded.
+            if *workflow_loaded.read() {
+                button {
+                    class: "mt-4 bg-gray-200 hover:bg-gray-300 py-1 px-3 rounded text-sm",
+                    onclick: move |_| {
+                        let workflow_ref = workflow.read();
+                        download_file(
+                            &workflow_ref.nodes_to_csv(),
+                            &format!("{}-nodes.csv", workflow_ref.name),
+                            "text/csv",
+                        );
+                    },
+                    "Export Nodes as CSV"
+                }
+                button {
+                    class: "mt-4 ml-2 bg-gray-200 hover:bg-gray-300 py-1 px-3 rounded text-sm",
+                    onclick: move |_| {
+                        let path = layout_path(Path::new(&*loaded_path.read()));
+                        match layout_io::save_layout(&path, &positions.read()) {
+                            Ok(()) => layout_status.set(Some(Ok(format!("Saved layout to {}", path.display())))),
+                            Err(e) => layout_status.set(Some(Err(e.to_string()))),
+                        }
+                    },
+                    "Save Layout"
+                }
+                button {
+                    class: "mt-4 ml-2 bg-gray-200 hover:bg-gray-300 py-1 px-3 rounded text-sm",
+                    onclick: move |_| {
+                        let path = layout_path(Path::new(&*loaded_path.read()));
+                        match layout_io::load_layout(&path) {
+                            Ok(layout) => {
+                                positions.set(layout);
+                                layout_count += 1;
+                                layout_status.set(Some(Ok(format!("Loaded layout from {}", path.display()))));
+                            }
+                            Err(e) => layout_status.set(Some(Err(e.to_string()))),
                         }
+                    },
+                    "Load Layout"
+                }
+                {
+                    match layout_status.read().as_ref() {
+                        Some(Ok(message)) => rsx! {
+                            div { class: "mt-2 text-sm text-green-600", "{message}" }
+                        },
+                        Some(Err(error_msg)) => rsx! {
+                            div { class: "mt-2 text-sm text-red-600", "Error: {error_msg}" }
+                        },
+                        None => rsx! {},
+                    }
+                }
+                div { class: "mt-6 w-full h-[600px]",
+                    Flow {
+                        key: "{workflow.read().id}-{layout_count}",
+                        workflow,
+                        initial_positions: Some(positions),
                     }
-                } else {
-                    rsx! {}
                 }
             }
 
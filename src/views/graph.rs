@@ -1,10 +1,15 @@
+use crate::components::graph::Point;
 use crate::components::Graph;
+use crate::graph_io;
 use dioxus::prelude::*;
+use petgraph::graph::NodeIndex;
 use petgraph::Graph as PetGraph;
+use std::collections::HashMap;
+use std::path::Path;
 
 #[component]
 pub fn GraphPage() -> Element {
-    let graph = use_signal(|| {
+    let mut graph = use_signal(|| {
         let mut g = PetGraph::<String, i32>::new();
 
         // Add some example nodes (cities)
@@ -22,11 +27,72 @@ pub fn GraphPage() -> Element {
 
         g
     });
+    // Shared with the canvas, which keeps it up to date as nodes are dragged
+    let mut positions = use_signal(HashMap::<NodeIndex, Point>::new);
+    let mut graph_file_path = use_signal(String::new);
+    let mut status = use_signal(|| None::<Result<String, String>>);
+    // Bumped on every load so the canvas remounts with the loaded layout
+    let mut load_count = use_signal(|| 0);
+
+    let has_path = !graph_file_path.read().trim().is_empty();
+    let button_class = if has_path {
+        "bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded"
+    } else {
+        "bg-gray-400 text-white font-bold py-2 px-4 rounded cursor-not-allowed"
+    };
+
+    let load_graph = move |_| {
+        let path = graph_file_path.read().trim().to_string();
+        match graph_io::load_from_path::<String, i32>(Path::new(&path)) {
+            Ok((loaded_graph, loaded_positions)) => {
+                let (nodes, edges) = (loaded_graph.node_count(), loaded_graph.edge_count());
+                graph.set(loaded_graph);
+                positions.set(loaded_positions);
+                load_count += 1;
+                status.set(Some(Ok(format!(
+                    "Loaded {} nodes, {} edges from {}",
+                    nodes, edges, path
+                ))));
+            }
+            Err(e) => status.set(Some(Err(e.to_string()))),
+        }
+    };
+
+    let save_graph = move |_| {
+        let path = graph_file_path.read().trim().to_string();
+        match graph_io::save_to_path(Path::new(&path), &graph.read(), &positions.read()) {
+            Ok(()) => status.set(Some(Ok(format!("Saved to {}", path)))),
+            Err(e) => status.set(Some(Err(e.to_string()))),
+        }
+    };
 
     rsx! {
+        div { class: "p-4 flex space-x-2 items-center",
+            input {
+                class: "border border-gray-300 rounded px-3 py-2 w-full max-w-md",
+                r#type: "text",
+                placeholder: "path to graph JSON file",
+                value: "{graph_file_path}",
+                oninput: move |evt| graph_file_path.set(evt.value()),
+            }
+            button { class: button_class, disabled: !has_path, onclick: load_graph, "Load" }
+            button { class: button_class, disabled: !has_path, onclick: save_graph, "Save" }
+        }
+        {
+            match status.read().as_ref() {
+                Some(Ok(message)) => rsx! {
+                    div { class: "px-4 text-sm text-green-600", "{message}" }
+                },
+                Some(Err(error_msg)) => rsx! {
+                    div { class: "px-4 text-sm text-red-600", "Error: {error_msg}" }
+                },
+                None => rsx! {},
+            }
+        }
         Graph {
+            key: "{load_count}",
             graph: graph,
-            initial_positions: None,
+            initial_positions: Some(positions),
         }
     }
 }
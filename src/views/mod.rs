@@ -22,3 +22,6 @@ pub use flow::FlowPage;
 
 mod graph;
 pub use graph::GraphPage;
+
+#[cfg(test)]
+mod tests;
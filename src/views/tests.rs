@@ -0,0 +1,140 @@
+use super::flow::{
+    layout_path, load, load_reporting, push_history, reduce_progress, validate_path, LoadEvent,
+};
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        std::env::current_dir()
+            .expect("Failed to get current directory")
+            .join("documents")
+            .join("solar-radiation")
+            .join(name)
+            .join("workflow.yaml")
+    }
+
+    #[test]
+    fn test_load_returns_size_and_workflow() {
+        let (size, workflow) = load(&fixture("calculate-cloud-correction")).unwrap();
+        assert!(size > 0);
+        assert_eq!(workflow.name, "CalculateCloudCorrectionFactor");
+        assert!(workflow.graph.node_count() > 0);
+    }
+
+    #[test]
+    fn test_load_reports_missing_file() {
+        assert!(load(&fixture("does-not-exist")).is_err());
+    }
+
+    fn paths(list: &[&str]) -> Vec<String> {
+        list.iter().map(|path| path.to_string()).collect()
+    }
+
+    #[test]
+    fn test_push_history_puts_newest_first() {
+        let history = push_history(&[], "a.yaml", 3);
+        let history = push_history(&history, "b.yaml", 3);
+        assert_eq!(history, paths(&["b.yaml", "a.yaml"]));
+    }
+
+    #[test]
+    fn test_push_history_deduplicates() {
+        let history = paths(&["b.yaml", "a.yaml", "c.yaml"]);
+        assert_eq!(
+            push_history(&history, "a.yaml", 3),
+            paths(&["a.yaml", "b.yaml", "c.yaml"])
+        );
+    }
+
+    #[test]
+    fn test_push_history_caps_length() {
+        let history = paths(&["c.yaml", "b.yaml", "a.yaml"]);
+        assert_eq!(
+            push_history(&history, "d.yaml", 3),
+            paths(&["d.yaml", "c.yaml", "b.yaml"])
+        );
+    }
+
+    #[test]
+    fn test_validate_path_rejects_empty() {
+        assert!(validate_path("").is_err());
+        assert!(validate_path("   ").is_err());
+    }
+
+    #[test]
+    fn test_validate_path_rejects_missing_file() {
+        let missing = fixture("does-not-exist");
+        let err = validate_path(missing.to_str().unwrap()).unwrap_err();
+        assert!(err.contains("does not exist"), "unexpected hint: {}", err);
+    }
+
+    #[test]
+    fn test_validate_path_rejects_directory() {
+        let dir = std::env::current_dir().unwrap().join("documents");
+        let err = validate_path(dir.to_str().unwrap()).unwrap_err();
+        assert!(err.contains("is not a file"), "unexpected hint: {}", err);
+    }
+
+    #[test]
+    fn test_validate_path_rejects_other_extensions() {
+        let manifest = std::env::current_dir().unwrap().join("Cargo.toml");
+        let err = validate_path(manifest.to_str().unwrap()).unwrap_err();
+        assert!(err.contains(".yaml or .yml"), "unexpected hint: {}", err);
+    }
+
+    #[test]
+    fn test_validate_path_accepts_yaml_and_yml() {
+        let yaml = fixture("calculate-cloud-correction");
+        assert_eq!(validate_path(yaml.to_str().unwrap()), Ok(()));
+
+        let yml = std::env::current_dir()
+            .unwrap()
+            .join("documents")
+            .join("solar-radiation")
+            .join("time-to-time-value")
+            .join("workflow.yml");
+        assert_eq!(validate_path(yml.to_str().unwrap()), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_path_accepts_upper_case_extension() {
+        let path = std::env::temp_dir().join(format!("{}.YAML", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "id: wf\n").unwrap();
+        let result = validate_path(path.to_str().unwrap());
+        std::fs::remove_file(&path).ok();
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn test_layout_path_sits_next_to_workflow() {
+        assert_eq!(
+            layout_path(&PathBuf::from("/data/solar/workflow.yaml")),
+            PathBuf::from("/data/solar/workflow.layout.json")
+        );
+    }
+
+    #[test]
+    fn test_reduce_progress_appends_processed_paths() {
+        let processed = reduce_progress(&[], LoadEvent::Read("workflow.yaml".to_string()));
+        let processed = reduce_progress(&processed, LoadEvent::Read("reader.yml".to_string()));
+        assert_eq!(processed, paths(&["workflow.yaml", "reader.yml"]));
+
+        // A new load starts over
+        assert!(reduce_progress(&processed, LoadEvent::Started).is_empty());
+    }
+
+    #[test]
+    fn test_load_reporting_reads_workflow_file_then_includes() {
+        let path = fixture("solar-potential");
+        let mut processed = Vec::new();
+        load_reporting(&path, |file| {
+            processed = reduce_progress(&processed, LoadEvent::Read(file.display().to_string()));
+        })
+        .unwrap();
+        assert_eq!(processed.first(), Some(&path.display().to_string()));
+        assert!(processed.len() > 1);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{Error, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+
+pub const ACTION_NODE_TYPE: &str = "action";
+pub const SUB_GRAPH_NODE_TYPE: &str = "subGraph";
+
+/// Values accepted for a node's `type`. Starts with the built-in types and grows through [`register_node_type`].
+static NODE_TYPES: Lazy<RwLock<HashSet<String>>> = Lazy::new(|| {
+    RwLock::new(HashSet::from([
+        ACTION_NODE_TYPE.to_string(),
+        SUB_GRAPH_NODE_TYPE.to_string(),
+    ]))
+});
+
+/// Registers an additional node `type` so workflows using it pass validation. Nodes of a registered type are loaded
+/// like action nodes and keep their type, see [`super::Node::node_type`]. The registry is shared by the whole process
+/// and append-only: a type can't be unregistered, so tests register names no other test relies on being unknown.
+pub fn register_node_type(node_type: &str) {
+    NODE_TYPES
+        .write()
+        .expect("node type registry poisoned")
+        .insert(node_type.to_string());
+}
+
+/// The top level of a workflow YAML file.
+///
+/// `graphs` is kept as raw YAML values so each graph can be deserialized on its own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowDefinition {
+    pub id: String,
+    pub name: String,
+    /// Written as `entryGraphId`, either a single id or a list of them.
+    #[serde(
+        rename = "entryGraphId",
+        default,
+        deserialize_with = "one_or_many",
+        serialize_with = "scalar_if_single"
+    )]
+    pub entry_graph_ids: Vec<String>,
+    #[serde(default)]
+    pub with: Option<HashMap<String, serde_yaml::Value>>,
+    #[serde(default)]
+    pub graphs: Vec<serde_yaml::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub nodes: Vec<NodeDefinition>,
+    #[serde(default)]
+    pub edges: Vec<EdgeDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_graph_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub with: Option<HashMap<String, serde_yaml::Value>>,
+    /// Free-form note for readers of the workflow, written as `description` or `note`.
+    #[serde(alias = "note", skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl NodeDefinition {
+    /// Checks that `node_type` is one of the known node types.
+    pub fn validate_type(&self) -> Result<()> {
+        let known = NODE_TYPES.read().expect("node type registry poisoned");
+        if known.contains(&self.node_type) {
+            Ok(())
+        } else {
+            Err(Error::Input(format!(
+                "unknown node type `{}` for node {}",
+                self.node_type, self.id
+            )))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeDefinition {
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    pub from_port: String,
+    pub to_port: String,
+    /// Cost or priority of the edge, for workflows that model one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with: Option<HashMap<String, serde_yaml::Value>>,
+}
+
+/// Accepts either `key: value` or `key: [a, b]`.
+fn one_or_many<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match Option::<OneOrMany>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(OneOrMany::One(value)) => vec![value],
+        Some(OneOrMany::Many(values)) => values,
+    })
+}
+
+/// Writes a single id as a scalar, so single-entry workflows keep their original shape.
+fn scalar_if_single<S>(values: &[String], serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match values {
+        [value] => serializer.serialize_str(value),
+        _ => values.serialize(serializer),
+    }
+}
@@ -1,15 +1,38 @@
+use std::collections::HashMap;
 use std::fmt;
 
+/// Whether an edge carries features between nodes or routes them out of a filter/router port.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+#[derive(Default)]
+pub enum EdgeKind {
+    #[default]
+    Data,
+    Control,
+}
+
 #[derive(Debug)]
 #[derive(Clone)]
 #[derive(Default)]
 pub struct Edge {
     pub id: String,
     pub name: String,
+    pub from_port: String,
+    pub to_port: String,
+    pub edge_kind: EdgeKind,
+    /// Cost or priority given in the workflow file, if any.
+    pub weight: Option<f64>,
+    /// Parameters given under the edge's `with` key, such as port mappings.
+    pub with: Option<HashMap<String, serde_yaml::Value>>,
 }
 
+/// The edge's name, followed by its weight in parentheses when it has one, as drawn on the canvas.
 impl fmt::Display for Edge {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.name)
+        match self.weight {
+            Some(weight) => write!(f, "{} ({})", self.name, weight),
+            None => write!(f, "{}", self.name),
+        }
     }
 }
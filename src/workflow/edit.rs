@@ -0,0 +1,286 @@
+use super::{Edge, Node, NodeAction, NodeKind, Warning, Workflow};
+use crate::{Error, Result};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::HashMap;
+
+impl Workflow {
+    /// Adds a copy of the node at `node_idx` with a fresh id and a `" copy"` name suffix. Edges are not copied.
+    ///
+    /// Panics if `node_idx` is not in the graph.
+    pub fn duplicate_node(&mut self, node_idx: NodeIndex) -> NodeIndex {
+        let original = &self.graph[node_idx];
+        let copy = Node {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: format!("{} copy", original.name),
+            ..original.clone()
+        };
+        self.graph.add_node(copy)
+    }
+
+    /// Renames the node at `node_idx`. Blank names are rejected.
+    pub fn rename_node(&mut self, node_idx: NodeIndex, name: String) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(Error::Input("node name cannot be empty".to_string()));
+        }
+        let node = self
+            .graph
+            .node_weight_mut(node_idx)
+            .ok_or_else(|| Error::Input(format!("node {} does not exist", node_idx.index())))?;
+        node.name = name;
+        Ok(())
+    }
+
+    /// Renames every node whose id is a key of `mapping` to the name it maps to. Other nodes keep their names.
+    pub fn relabel(&mut self, mapping: &HashMap<String, String>) {
+        for node in self.graph.node_weights_mut() {
+            if let Some(name) = mapping.get(&node.id) {
+                node.name = name.clone();
+            }
+        }
+    }
+
+    /// Renames the edge at `edge_idx`. Blank names are rejected.
+    pub fn rename_edge(&mut self, edge_idx: EdgeIndex, name: String) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(Error::Input("edge name cannot be empty".to_string()));
+        }
+        let edge = self
+            .graph
+            .edge_weight_mut(edge_idx)
+            .ok_or_else(|| Error::Input(format!("edge {} does not exist", edge_idx.index())))?;
+        edge.name = name;
+        Ok(())
+    }
+
+    /// Swaps the action an action node runs, keeping its id, name and `with` parameters. Sub graph nodes are rejected.
+    pub fn replace_node_action(&mut self, node_idx: NodeIndex, action: NodeAction) -> Result<()> {
+        let node = self
+            .graph
+            .node_weight_mut(node_idx)
+            .ok_or_else(|| Error::Input(format!("node {} does not exist", node_idx.index())))?;
+        match &mut node.kind {
+            NodeKind::Action(current) => {
+                *current = action;
+                Ok(())
+            }
+            NodeKind::SubGraph(_) => Err(Error::Input(format!(
+                "node {} is a sub graph and has no action",
+                node.id
+            ))),
+        }
+    }
+
+    /// Renames the graph `old` to `new` everywhere it is referenced: the nodes it holds, the sub graph nodes expanding
+    /// into it, the entry graphs and the graph names. Fails if there is no graph `old` or `new` is already taken.
+    pub fn rename_graph_id(&mut self, old: &str, new: &str) -> Result<()> {
+        let exists = |workflow: &Self, id: &str| {
+            workflow.graph_names.contains_key(id)
+                || workflow.graph_ids().iter().any(|known| known == id)
+        };
+        if !exists(self, old) {
+            return Err(Error::Input(format!("graph {} does not exist", old)));
+        }
+        if new.trim().is_empty() {
+            return Err(Error::Input("graph id cannot be empty".to_string()));
+        }
+        if old != new && exists(self, new) {
+            return Err(Error::Input(format!("graph {} already exists", new)));
+        }
+
+        for node in self.graph.node_weights_mut() {
+            if node.subgraph == old {
+                node.subgraph = new.to_string();
+            }
+            if let NodeKind::SubGraph(sub_graph_id) = &mut node.kind {
+                if sub_graph_id == old {
+                    *sub_graph_id = new.to_string();
+                }
+            }
+        }
+        for entry in &mut self.entry_graph_ids {
+            if entry == old {
+                *entry = new.to_string();
+            }
+        }
+        if let Some(name) = self.graph_names.remove(old) {
+            self.graph_names.insert(new.to_string(), name);
+        }
+        Ok(())
+    }
+
+    /// Removes one edge from `from` to `to`, if there is any, returning whether one was removed. Of several parallel
+    /// edges only the first found goes; edges from `to` back to `from` are left alone.
+    pub fn remove_edge_between(&mut self, from: NodeIndex, to: NodeIndex) -> bool {
+        match self.graph.find_edge(from, to) {
+            Some(edge_idx) => self.graph.remove_edge(edge_idx).is_some(),
+            None => false,
+        }
+    }
+
+    /// Splits the edge at `edge_idx` by putting `node` in the middle of it. The edge is replaced by one from its source
+    /// into `node` and one from `node` to its target, named after the original edge and the inserted node. The first
+    /// keeps the original source port and the second the original target port; both keep its kind and weight. Returns
+    /// the new node and the two new edges.
+    pub fn insert_node_on_edge(
+        &mut self,
+        edge_idx: EdgeIndex,
+        node: Node,
+    ) -> Result<(NodeIndex, EdgeIndex, EdgeIndex)> {
+        let (source, target) = self
+            .graph
+            .edge_endpoints(edge_idx)
+            .ok_or_else(|| Error::Input(format!("edge {} does not exist", edge_idx.index())))?;
+        let original = self
+            .graph
+            .remove_edge(edge_idx)
+            .expect("edge endpoints were found");
+
+        let incoming = Edge {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: format!("{} to {}", original.name, node.name),
+            from_port: original.from_port.clone(),
+            to_port: "default".to_string(),
+            edge_kind: original.edge_kind,
+            weight: original.weight,
+            with: original.with.clone(),
+        };
+        let outgoing = Edge {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: format!("{} from {}", original.name, node.name),
+            from_port: "default".to_string(),
+            to_port: original.to_port,
+            edge_kind: original.edge_kind,
+            weight: original.weight,
+            with: original.with,
+        };
+        let node_idx = self.graph.add_node(node);
+        let incoming_idx = self.graph.add_edge(source, node_idx, incoming);
+        let outgoing_idx = self.graph.add_edge(node_idx, target, outgoing);
+        Ok((node_idx, incoming_idx, outgoing_idx))
+    }
+
+    /// Removes the node at `node_idx` together with its edges. Recorded execution states follow the node petgraph
+    /// moves into the freed index, so they stay attached to the right nodes. Removing the last node of an entry graph
+    /// updates the entry graphs, see [`Workflow::replace_empty_entry`].
+    pub fn remove_node(&mut self, node_idx: NodeIndex) -> Option<Node> {
+        let last_idx = NodeIndex::new(self.graph.node_count().checked_sub(1)?);
+        let removed = self.graph.remove_node(node_idx)?;
+        self.node_states.remove(&node_idx);
+        if last_idx != node_idx {
+            if let Some(state) = self.node_states.remove(&last_idx) {
+                self.node_states.insert(node_idx, state);
+            }
+        }
+        self.replace_empty_entry(&removed.subgraph);
+        Some(removed)
+    }
+
+    /// Keeps the workflow from starting from `graph_id` once that entry graph has no nodes left. It is dropped when
+    /// other entry graphs remain, replaced by the first graph that still has nodes otherwise, and dropped if there is
+    /// none. Each change is recorded in [`Workflow::edit_warnings`].
+    fn replace_empty_entry(&mut self, graph_id: &str) {
+        let Some(position) = self.entry_graph_ids.iter().position(|id| id == graph_id) else {
+            return;
+        };
+        if self
+            .graph
+            .node_weights()
+            .any(|node| node.subgraph == graph_id)
+        {
+            return;
+        }
+
+        let replacement = if self.entry_graph_ids.len() > 1 {
+            None
+        } else {
+            self.graph
+                .node_weights()
+                .map(|node| node.subgraph.clone())
+                .find(|id| !self.entry_graph_ids.contains(id))
+        };
+        let message = match replacement {
+            Some(replacement) => {
+                let message = format!(
+                    "entry graph {} has no nodes left, the workflow now starts from graph {}",
+                    graph_id, replacement
+                );
+                self.entry_graph_ids[position] = replacement;
+                message
+            }
+            None => {
+                self.entry_graph_ids.remove(position);
+                format!(
+                    "entry graph {} has no nodes left and is no longer an entry graph",
+                    graph_id
+                )
+            }
+        };
+        self.edit_warnings.push(Warning {
+            message,
+            node: None,
+            edge: None,
+        });
+    }
+
+    /// Removes every node in [`Workflow::unreachable_nodes`] with its edges, returning the ids of the removed nodes
+    /// in index order. Removing a node moves the last node into its index, so the other nodes' indices may change;
+    /// callers holding on to indices, such as canvas positions, should re-key them by node id.
+    pub fn prune_unreachable(&mut self) -> Vec<String> {
+        let unreachable = self.unreachable_nodes();
+        let ids = unreachable
+            .iter()
+            .map(|&node_idx| self.graph[node_idx].id.clone())
+            .collect();
+        // From the highest index down, so the last node moving into a freed index is never one still to be removed
+        for &node_idx in unreachable.iter().rev() {
+            self.remove_node(node_idx);
+        }
+        ids
+    }
+
+    /// Like [`Workflow::remove_node`], but first connects every predecessor of the node to every successor so the
+    /// flow stays connected. Each bridging edge is named after the removed node and keeps the port, kind and weight of
+    /// the predecessor's edge into it and the port of the edge out of it to the successor. No bridge is added from a
+    /// node to itself or between nodes already connected by an edge.
+    pub fn remove_node_bridging(&mut self, node_idx: NodeIndex) -> Option<Node> {
+        let name = self.graph.node_weight(node_idx)?.name.clone();
+        let mut incoming: Vec<(NodeIndex, Edge)> = Vec::new();
+        for edge in self.graph.edges_directed(node_idx, Direction::Incoming) {
+            if edge.source() != node_idx
+                && !incoming.iter().any(|(source, _)| *source == edge.source())
+            {
+                incoming.push((edge.source(), edge.weight().clone()));
+            }
+        }
+        let mut outgoing: Vec<(NodeIndex, Edge)> = Vec::new();
+        for edge in self.graph.edges_directed(node_idx, Direction::Outgoing) {
+            if edge.target() != node_idx
+                && !outgoing.iter().any(|(target, _)| *target == edge.target())
+            {
+                outgoing.push((edge.target(), edge.weight().clone()));
+            }
+        }
+
+        for (source, edge_in) in &incoming {
+            for (target, edge_out) in &outgoing {
+                // A bridge would be a self-loop, or the two are already connected
+                if source == target || self.graph.find_edge(*source, *target).is_some() {
+                    continue;
+                }
+                let bridge = Edge {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    name: name.clone(),
+                    from_port: edge_in.from_port.clone(),
+                    to_port: edge_out.to_port.clone(),
+                    edge_kind: edge_in.edge_kind,
+                    weight: edge_in.weight,
+                    with: edge_in.with.clone(),
+                };
+                self.graph.add_edge(*source, *target, bridge);
+            }
+        }
+        self.remove_node(node_idx)
+    }
+}
@@ -0,0 +1,55 @@
+use super::Workflow;
+use crate::{Error, Result};
+use petgraph::graph::NodeIndex;
+
+/// Where a node is in a workflow run.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+#[derive(Default)]
+pub enum ExecutionState {
+    #[default]
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl Workflow {
+    /// The state of the node at `node_idx`. Nodes without a recorded state are pending.
+    pub fn execution_state(&self, node_idx: NodeIndex) -> ExecutionState {
+        self.node_states.get(&node_idx).copied().unwrap_or_default()
+    }
+
+    /// Records that the node at `node_idx` has started running.
+    pub fn mark_running(&mut self, node_idx: NodeIndex) -> Result<()> {
+        self.set_state(node_idx, ExecutionState::Running)
+    }
+
+    /// Records that the node at `node_idx` finished successfully.
+    pub fn mark_succeeded(&mut self, node_idx: NodeIndex) -> Result<()> {
+        self.set_state(node_idx, ExecutionState::Succeeded)
+    }
+
+    /// Records that the node at `node_idx` failed.
+    pub fn mark_failed(&mut self, node_idx: NodeIndex) -> Result<()> {
+        self.set_state(node_idx, ExecutionState::Failed)
+    }
+
+    /// Forgets every recorded state, putting all nodes back to pending.
+    pub fn reset_states(&mut self) {
+        self.node_states.clear();
+    }
+
+    /// Records `state` for the node at `node_idx`, refusing nodes that are not in the graph.
+    fn set_state(&mut self, node_idx: NodeIndex, state: ExecutionState) -> Result<()> {
+        if self.graph.node_weight(node_idx).is_none() {
+            return Err(Error::Input(format!(
+                "node {} does not exist",
+                node_idx.index()
+            )));
+        }
+        self.node_states.insert(node_idx, state);
+        Ok(())
+    }
+}
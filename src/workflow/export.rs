@@ -0,0 +1,186 @@
+use super::definition::{ACTION_NODE_TYPE, SUB_GRAPH_NODE_TYPE};
+use super::{
+    EdgeDefinition, GraphDefinition, Node, NodeDefinition, NodeKind, Workflow, WorkflowDefinition,
+};
+use crate::Result;
+use petgraph::algo::is_cyclic_directed;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Quotes `field` per RFC 4180 when it contains a comma, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// `node` in the workflow file's node form.
+fn node_definition(node: &Node) -> NodeDefinition {
+    let (node_type, action, sub_graph_id) = match &node.kind {
+        NodeKind::Action(action) => (
+            node.node_type.as_deref().unwrap_or(ACTION_NODE_TYPE),
+            Some(action.to_string()),
+            None,
+        ),
+        NodeKind::SubGraph(sub_graph_id) => (SUB_GRAPH_NODE_TYPE, None, Some(sub_graph_id.clone())),
+    };
+    NodeDefinition {
+        id: node.id.clone(),
+        name: node.name.clone(),
+        node_type: node_type.to_string(),
+        action,
+        sub_graph_id,
+        with: node.with.clone(),
+        description: node.description.clone(),
+    }
+}
+
+/// `node` as a YAML snippet in the workflow file's node form, ready to paste into a graph's `nodes`. Absent fields
+/// are left out.
+pub fn node_to_yaml(node: &Node) -> Result<String> {
+    Ok(serde_yaml::to_string(&node_definition(node))?)
+}
+
+impl Workflow {
+    /// The typed definition this workflow would be loaded from, so `Workflow::from_definition(workflow.to_definition())`
+    /// gives back an equal workflow. Graphs are ordered by id and hold their nodes and edges ordered by id; an edge
+    /// goes with the graph of its source node.
+    pub fn to_definition(&self) -> WorkflowDefinition {
+        let mut graphs: BTreeMap<&str, GraphDefinition> = BTreeMap::new();
+        let graph_for = |id: &str| {
+            let name = self.graph_name(id).unwrap_or_default().to_string();
+            GraphDefinition {
+                id: id.to_string(),
+                name,
+                nodes: Vec::new(),
+                edges: Vec::new(),
+            }
+        };
+        for id in self.graph_names.keys() {
+            graphs.insert(id, graph_for(id));
+        }
+        for node_idx in self.nodes_sorted_by_id() {
+            let node = &self.graph[node_idx];
+            graphs
+                .entry(&node.subgraph)
+                .or_insert_with(|| graph_for(&node.subgraph))
+                .nodes
+                .push(node_definition(node));
+        }
+        for edge_idx in self.graph.edge_indices() {
+            let Some((source, target)) = self.graph.edge_endpoints(edge_idx) else {
+                continue;
+            };
+            let edge = &self.graph[edge_idx];
+            let subgraph = &self.graph[source].subgraph;
+            graphs
+                .entry(subgraph)
+                .or_insert_with(|| graph_for(subgraph))
+                .edges
+                .push(EdgeDefinition {
+                    id: edge.id.clone(),
+                    from: self.graph[source].id.clone(),
+                    to: self.graph[target].id.clone(),
+                    from_port: edge.from_port.clone(),
+                    to_port: edge.to_port.clone(),
+                    weight: edge.weight,
+                    with: edge.with.clone(),
+                });
+        }
+
+        WorkflowDefinition {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            entry_graph_ids: self.entry_graph_ids.clone(),
+            with: None,
+            graphs: graphs
+                .into_values()
+                .map(|mut graph| {
+                    graph.edges.sort_by(|a, b| a.id.cmp(&b.id));
+                    serde_yaml::to_value(graph).expect("graph definitions serialize to YAML values")
+                })
+                .collect(),
+        }
+    }
+
+    /// The workflow as the text of a workflow YAML file, see [`Workflow::to_definition`].
+    pub fn to_yaml(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(&self.to_definition())?)
+    }
+
+    /// One CSV row per node, ordered by id, with the columns `id,name,type,action,subgraph`, after a header row.
+    /// Rows end in CRLF.
+    pub fn nodes_to_csv(&self) -> String {
+        let mut csv = String::from("id,name,type,action,subgraph\r\n");
+        for node_idx in self.nodes_sorted_by_id() {
+            let node = &self.graph[node_idx];
+            let (node_type, action) = match &node.kind {
+                NodeKind::Action(action) => (
+                    node.node_type.as_deref().unwrap_or(ACTION_NODE_TYPE),
+                    action.to_string(),
+                ),
+                NodeKind::SubGraph(_) => (SUB_GRAPH_NODE_TYPE, String::new()),
+            };
+            let row = [
+                node.id.as_str(),
+                node.name.as_str(),
+                node_type,
+                action.as_str(),
+                node.subgraph.as_str(),
+            ]
+            .map(csv_field)
+            .join(",");
+            csv.push_str(&row);
+            csv.push_str("\r\n");
+        }
+        csv
+    }
+
+    /// A few lines summing the workflow up for logs and the command line: its name, id, entry graphs, node and edge
+    /// counts, whether it is free of cycles, and then one indented line per action with its node count, see
+    /// [`Workflow::actions_summary`].
+    pub fn describe(&self) -> String {
+        let entry = if self.entry_graph_ids.is_empty() {
+            "none".to_string()
+        } else {
+            self.entry_graph_ids.join(", ")
+        };
+        let acyclic = if is_cyclic_directed(&self.graph) {
+            "no"
+        } else {
+            "yes"
+        };
+        let mut description = format!(
+            "Workflow: {}\nId: {}\nEntry: {}\nNodes: {}\nEdges: {}\nAcyclic: {}\nActions:\n",
+            self.name,
+            self.id,
+            entry,
+            self.graph.node_count(),
+            self.graph.edge_count(),
+            acyclic
+        );
+        for (action, count) in self.actions_summary() {
+            let _ = writeln!(description, "  {}: {}", action, count);
+        }
+        description
+    }
+
+    /// Each node's id mapped to the ids of its successors, for tools that take a graph as adjacency lists. Both the
+    /// nodes and their successors are sorted, and nodes without outgoing edges map to an empty list.
+    pub fn to_adjacency_list(&self) -> BTreeMap<String, Vec<String>> {
+        self.graph
+            .node_indices()
+            .map(|node_idx| {
+                let mut successors: Vec<String> = self
+                    .graph
+                    .neighbors(node_idx)
+                    .map(|successor| self.graph[successor].id.clone())
+                    .collect();
+                successors.sort();
+                (self.graph[node_idx].id.clone(), successors)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,105 @@
+use super::Workflow;
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A problem worth pointing out that doesn't stop the workflow from loading, found by [`Workflow::lint`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    pub message: String,
+    /// The node the warning is about, if any.
+    pub node: Option<NodeIndex>,
+    /// The edge the warning is about, if any.
+    pub edge: Option<EdgeIndex>,
+}
+
+impl Warning {
+    fn for_node(node: NodeIndex, message: String) -> Self {
+        Warning {
+            message,
+            node: Some(node),
+            edge: None,
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Workflow {
+    /// Non-fatal issues, in node order:
+    /// - orphans, see [`Workflow::is_orphan`];
+    /// - unreachable nodes, which are connected but can't be reached from the entry graphs, e.g. a cycle nothing
+    ///   feeds into or a graph no sub graph node expands into, see [`Workflow::unreachable_nodes`].
+    ///
+    /// Then come the names shared by several nodes, see [`Workflow::validate_unique_names`].
+    pub fn lint(&self) -> Vec<Warning> {
+        let unreachable: HashSet<NodeIndex> = self.unreachable_nodes().into_iter().collect();
+
+        let mut warnings = Vec::new();
+        for node_idx in self.graph.node_indices() {
+            let node = &self.graph[node_idx];
+            if self.is_orphan(node_idx) {
+                warnings.push(Warning::for_node(
+                    node_idx,
+                    format!(
+                        "node {} ({}) is not connected to any other node",
+                        node.id, node.name
+                    ),
+                ));
+            } else if unreachable.contains(&node_idx) {
+                warnings.push(Warning::for_node(
+                    node_idx,
+                    format!(
+                        "node {} ({}) cannot be reached from the entry graph",
+                        node.id, node.name
+                    ),
+                ));
+            }
+        }
+        warnings.extend(self.validate_unique_names());
+        warnings
+    }
+
+    /// One warning per name shared by nodes with different ids, listing their ids and pointing at the first of
+    /// them, in the order the names first appear. Duplicate names can be intended, e.g. two readers of different
+    /// files, but make nodes hard to tell apart.
+    pub fn validate_unique_names(&self) -> Vec<Warning> {
+        let mut names: Vec<&str> = Vec::new();
+        let mut nodes_by_name: HashMap<&str, Vec<NodeIndex>> = HashMap::new();
+        for node_idx in self.graph.node_indices() {
+            let name = self.graph[node_idx].name.as_str();
+            let nodes = nodes_by_name.entry(name).or_default();
+            if nodes.is_empty() {
+                names.push(name);
+            }
+            if nodes
+                .iter()
+                .all(|&other| self.graph[other].id != self.graph[node_idx].id)
+            {
+                nodes.push(node_idx);
+            }
+        }
+
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let nodes = &nodes_by_name[name];
+                if nodes.len() < 2 {
+                    return None;
+                }
+                let ids: Vec<&str> = nodes
+                    .iter()
+                    .map(|&node_idx| self.graph[node_idx].id.as_str())
+                    .collect();
+                Some(Warning::for_node(
+                    nodes[0],
+                    format!("nodes {} share the name {}", ids.join(", "), name),
+                ))
+            })
+            .collect()
+    }
+}
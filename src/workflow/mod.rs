@@ -1,10 +1,24 @@
 #![allow(unused)]
+mod definition;
 mod edge;
+mod edit;
+mod execution;
+mod export;
+mod lint;
 mod node;
+mod query;
+mod validation;
 mod workflow;
 #[cfg(test)]
 mod tests;
 
-pub use edge::Edge;
-pub use node::Node;
-pub use workflow::Workflow;
+pub use definition::{
+    register_node_type, EdgeDefinition, GraphDefinition, NodeDefinition, WorkflowDefinition,
+};
+pub use edge::{Edge, EdgeKind};
+pub use execution::ExecutionState;
+pub use export::node_to_yaml;
+pub use lint::Warning;
+pub use node::{Node, NodeAction, NodeKind};
+pub use validation::validate_node_params;
+pub use workflow::{LoadOptions, LoadTimings, Workflow, DEFAULT_MAX_INCLUDE_DEPTH};
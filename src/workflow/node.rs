@@ -1,4 +1,107 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
 use std::fmt;
+use std::str::FromStr;
+
+/// The actions used by the solar workflows. Anything else is kept verbatim in [`NodeAction::Other`].
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq, Eq, Hash)]
+pub enum NodeAction {
+    AreaCalculator,
+    AttributeAggregator,
+    AttributeManager,
+    AttributeMapper,
+    CsvReader,
+    FeatureCityGmlReader,
+    FeatureCreator,
+    FeatureFilter,
+    FeatureMerger,
+    GeometryCoercer,
+    GeometryExtractor,
+    GeometryPartExtractor,
+    HorizontalReprojector,
+    NoopSink,
+    OutputRouter,
+    PolygonNormalExtractor,
+    StatisticsCalculator,
+    Other(String),
+}
+
+impl Default for NodeAction {
+    fn default() -> Self {
+        NodeAction::Other(String::new())
+    }
+}
+
+impl FromStr for NodeAction {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let action = match s {
+            "AreaCalculator" => NodeAction::AreaCalculator,
+            "AttributeAggregator" => NodeAction::AttributeAggregator,
+            "AttributeManager" => NodeAction::AttributeManager,
+            "AttributeMapper" => NodeAction::AttributeMapper,
+            "CsvReader" => NodeAction::CsvReader,
+            "FeatureCityGmlReader" => NodeAction::FeatureCityGmlReader,
+            "FeatureCreator" => NodeAction::FeatureCreator,
+            "FeatureFilter" => NodeAction::FeatureFilter,
+            "FeatureMerger" => NodeAction::FeatureMerger,
+            "GeometryCoercer" => NodeAction::GeometryCoercer,
+            "GeometryExtractor" => NodeAction::GeometryExtractor,
+            "GeometryPartExtractor" => NodeAction::GeometryPartExtractor,
+            "HorizontalReprojector" => NodeAction::HorizontalReprojector,
+            "NoopSink" => NodeAction::NoopSink,
+            "OutputRouter" => NodeAction::OutputRouter,
+            "PolygonNormalExtractor" => NodeAction::PolygonNormalExtractor,
+            "StatisticsCalculator" => NodeAction::StatisticsCalculator,
+            other => NodeAction::Other(other.to_string()),
+        };
+        Ok(action)
+    }
+}
+
+impl fmt::Display for NodeAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NodeAction::AreaCalculator => "AreaCalculator",
+            NodeAction::AttributeAggregator => "AttributeAggregator",
+            NodeAction::AttributeManager => "AttributeManager",
+            NodeAction::AttributeMapper => "AttributeMapper",
+            NodeAction::CsvReader => "CsvReader",
+            NodeAction::FeatureCityGmlReader => "FeatureCityGmlReader",
+            NodeAction::FeatureCreator => "FeatureCreator",
+            NodeAction::FeatureFilter => "FeatureFilter",
+            NodeAction::FeatureMerger => "FeatureMerger",
+            NodeAction::GeometryCoercer => "GeometryCoercer",
+            NodeAction::GeometryExtractor => "GeometryExtractor",
+            NodeAction::GeometryPartExtractor => "GeometryPartExtractor",
+            NodeAction::HorizontalReprojector => "HorizontalReprojector",
+            NodeAction::NoopSink => "NoopSink",
+            NodeAction::OutputRouter => "OutputRouter",
+            NodeAction::PolygonNormalExtractor => "PolygonNormalExtractor",
+            NodeAction::StatisticsCalculator => "StatisticsCalculator",
+            NodeAction::Other(name) => name,
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub enum NodeKind {
+    Action(NodeAction),
+    /// Holds the id of the graph this node expands into.
+    SubGraph(String),
+}
+
+impl Default for NodeKind {
+    fn default() -> Self {
+        NodeKind::Action(NodeAction::default())
+    }
+}
 
 #[derive(Debug)]
 #[derive(Clone)]
@@ -8,6 +111,38 @@ pub struct Node {
     pub name: String,
     /// A node belongs to one subgraph, because in a graph it could contains multiple graphs as subgraph.
     pub subgraph: String,
+    pub kind: NodeKind,
+    pub with: Option<HashMap<String, serde_yaml::Value>>,
+    /// Human-readable note carried over from the node definition.
+    pub description: Option<String>,
+    /// The `type` of a node loaded from a registered custom type, see [`super::register_node_type`]. Such nodes load
+    /// as actions and are written back with this type. `None` for the built-in types.
+    pub node_type: Option<String>,
+}
+
+impl Node {
+    /// Ports this node routes features to, declared through `with.conditions[].outputPort` or `with.routingPort`.
+    /// Returns `None` for nodes that don't route.
+    pub fn routing_ports(&self) -> Option<Vec<String>> {
+        let with = self.with.as_ref()?;
+        let mut ports: Vec<String> = with
+            .get("conditions")
+            .and_then(|conditions| conditions.as_sequence())
+            .into_iter()
+            .flatten()
+            .filter_map(|condition| condition.get("outputPort")?.as_str())
+            .map(str::to_string)
+            .collect();
+        if let Some(port) = with.get("routingPort").and_then(|port| port.as_str()) {
+            ports.push(port.to_string());
+        }
+
+        if ports.is_empty() {
+            None
+        } else {
+            Some(ports)
+        }
+    }
 }
 
 impl fmt::Display for Node {
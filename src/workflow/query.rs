@@ -0,0 +1,358 @@
+use super::{Edge, Node, NodeKind, Workflow};
+use crate::{Error, Result};
+use petgraph::algo::{astar, tarjan_scc, toposort};
+use petgraph::graph::{DiGraph, NodeIndex, UnGraph};
+use petgraph::visit::{Bfs, EdgeRef};
+use petgraph::Direction;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+impl Workflow {
+    /// The node with the given id.
+    pub fn find_node(&self, id: &str) -> Option<NodeIndex> {
+        self.graph
+            .node_indices()
+            .find(|&node_idx| self.graph[node_idx].id == id)
+    }
+
+    /// Id of the node at `node_idx`. Unlike the index, which removing another node can reassign, the id keeps
+    /// naming the same node across edits, so it is what views should key per-node state by.
+    pub fn stable_id(&self, node_idx: NodeIndex) -> Option<&str> {
+        self.graph
+            .node_weight(node_idx)
+            .map(|node| node.id.as_str())
+    }
+
+    /// Every node, ordered by id rather than by insertion so iteration is reproducible across loads.
+    pub fn nodes_sorted_by_id(&self) -> Vec<NodeIndex> {
+        let mut nodes: Vec<NodeIndex> = self.graph.node_indices().collect();
+        nodes.sort_by(|&a, &b| self.graph[a].id.cmp(&self.graph[b].id));
+        nodes
+    }
+
+    /// Nodes whose id or name contains `query`, ignoring case. An empty query matches nothing.
+    pub fn search(&self, query: &str) -> Vec<NodeIndex> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        self.graph
+            .node_indices()
+            .filter(|&node_idx| {
+                let node = &self.graph[node_idx];
+                node.id.to_lowercase().contains(&query) || node.name.to_lowercase().contains(&query)
+            })
+            .collect()
+    }
+
+    /// Action nodes running `action`, e.g. every `CsvReader`. Sub graph nodes never match.
+    pub fn find_nodes_by_action(&self, action: &str) -> Vec<NodeIndex> {
+        self.graph
+            .node_indices()
+            .filter(|&node_idx| match &self.graph[node_idx].kind {
+                NodeKind::Action(node_action) => node_action.to_string() == action,
+                NodeKind::SubGraph(_) => false,
+            })
+            .collect()
+    }
+
+    /// How many Action nodes run each action, keyed by action name. Sub graph nodes aren't counted.
+    pub fn actions_summary(&self) -> BTreeMap<String, usize> {
+        let mut summary = BTreeMap::new();
+        for node in self.graph.node_weights() {
+            if let NodeKind::Action(action) = &node.kind {
+                *summary.entry(action.to_string()).or_insert(0) += 1;
+            }
+        }
+        summary
+    }
+
+    /// Distinct ids of the graphs nodes belong to or expand into, in the order they first appear.
+    pub fn graph_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        for node in self.graph.node_weights() {
+            let referenced = match &node.kind {
+                NodeKind::SubGraph(sub_graph_id) => Some(sub_graph_id),
+                NodeKind::Action(_) => None,
+            };
+            for id in std::iter::once(&node.subgraph).chain(referenced) {
+                if !id.is_empty() && !ids.contains(id) {
+                    ids.push(id.clone());
+                }
+            }
+        }
+        ids
+    }
+
+    /// The name the workflow gives the graph `id`.
+    pub fn graph_name(&self, id: &str) -> Option<&str> {
+        self.graph_names.get(id).map(String::as_str)
+    }
+
+    /// Nodes with an edge into `node_idx`.
+    pub fn predecessors(&self, node_idx: NodeIndex) -> Vec<NodeIndex> {
+        self.graph
+            .neighbors_directed(node_idx, Direction::Incoming)
+            .collect()
+    }
+
+    /// Nodes `node_idx` has an edge to.
+    pub fn successors(&self, node_idx: NodeIndex) -> Vec<NodeIndex> {
+        self.graph
+            .neighbors_directed(node_idx, Direction::Outgoing)
+            .collect()
+    }
+
+    /// Number of edges into `node_idx`.
+    pub fn in_degree(&self, node_idx: NodeIndex) -> usize {
+        self.graph
+            .edges_directed(node_idx, Direction::Incoming)
+            .count()
+    }
+
+    /// Number of edges leaving `node_idx`.
+    pub fn out_degree(&self, node_idx: NodeIndex) -> usize {
+        self.graph
+            .edges_directed(node_idx, Direction::Outgoing)
+            .count()
+    }
+
+    /// Directed density: the share of the `n * (n - 1)` possible edges between distinct nodes that are present, so a
+    /// sparse chain scores near 0.0 and a graph with every edge in both directions 1.0. Graphs with fewer than two
+    /// nodes have a density of 0.0. Parallel edges and self loops count too, so the result can exceed 1.0.
+    pub fn density(&self) -> f64 {
+        let nodes = self.graph.node_count();
+        if nodes < 2 {
+            return 0.0;
+        }
+        self.graph.edge_count() as f64 / (nodes * (nodes - 1)) as f64
+    }
+
+    /// Every node reachable from `node_idx` by following edges forward. Excludes `node_idx` itself unless it lies
+    /// on a cycle.
+    pub fn descendants(&self, node_idx: NodeIndex) -> HashSet<NodeIndex> {
+        self.reachable(node_idx, Direction::Outgoing)
+    }
+
+    /// Every node that can reach `node_idx`. Excludes `node_idx` itself unless it lies on a cycle.
+    pub fn ancestors(&self, node_idx: NodeIndex) -> HashSet<NodeIndex> {
+        self.reachable(node_idx, Direction::Incoming)
+    }
+
+    /// Nodes the workflow can never get to, in index order. Running starts from the nodes of the entry graphs that
+    /// have no incoming edge and follows edges forward; reaching a sub graph node also starts the graph it expands
+    /// into. Without an entry graph there is nothing to start from, and no node is reported.
+    pub fn unreachable_nodes(&self) -> Vec<NodeIndex> {
+        if self.entry_graph_ids.is_empty() {
+            return Vec::new();
+        }
+        let graph_sources = |graph_id: &str| -> Vec<NodeIndex> {
+            self.graph
+                .node_indices()
+                .filter(|&node_idx| {
+                    self.graph[node_idx].subgraph == graph_id && self.in_degree(node_idx) == 0
+                })
+                .collect()
+        };
+
+        let mut reached = HashSet::new();
+        let mut stack: Vec<NodeIndex> = self
+            .entry_graph_ids
+            .iter()
+            .flat_map(|graph_id| graph_sources(graph_id))
+            .collect();
+        while let Some(node_idx) = stack.pop() {
+            if !reached.insert(node_idx) {
+                continue;
+            }
+            stack.extend(self.graph.neighbors_directed(node_idx, Direction::Outgoing));
+            if let NodeKind::SubGraph(graph_id) = &self.graph[node_idx].kind {
+                stack.extend(graph_sources(graph_id));
+            }
+        }
+        self.graph
+            .node_indices()
+            .filter(|node_idx| !reached.contains(node_idx))
+            .collect()
+    }
+
+    /// Every node connected to `start` through edges followed in either direction, `start` included.
+    pub fn connected_component(&self, start: NodeIndex) -> HashSet<NodeIndex> {
+        let mut component = HashSet::new();
+        if self.graph.node_weight(start).is_none() {
+            return component;
+        }
+        component.insert(start);
+        let mut stack = vec![start];
+        while let Some(current) = stack.pop() {
+            for next in self.graph.neighbors_undirected(current) {
+                if component.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        component
+    }
+
+    /// A copy of the graph with edge directions dropped, for undirected analyses. Nodes keep their indices. With
+    /// `merge_reciprocal`, of two edges running between the same nodes in opposite directions only the first is kept;
+    /// parallel edges in the same direction are always all kept.
+    pub fn undirected(&self, merge_reciprocal: bool) -> UnGraph<Node, Edge> {
+        let mut undirected =
+            UnGraph::with_capacity(self.graph.node_count(), self.graph.edge_count());
+        for node in self.graph.node_weights() {
+            undirected.add_node(node.clone());
+        }
+        let mut directions = HashSet::new();
+        for edge in self.graph.edge_references() {
+            let (source, target) = (edge.source(), edge.target());
+            if merge_reciprocal
+                && directions.contains(&(target, source))
+                && !directions.contains(&(source, target))
+            {
+                continue;
+            }
+            directions.insert((source, target));
+            undirected.add_edge(source, target, edge.weight().clone());
+        }
+        undirected
+    }
+
+    /// Nodes reachable from `start` by following edges forward, `start` first and then level by level, as a
+    /// breadth-first search visits them. Each node appears once.
+    pub fn bfs_order(&self, start: NodeIndex) -> Vec<NodeIndex> {
+        if self.graph.node_weight(start).is_none() {
+            return Vec::new();
+        }
+        let mut order = Vec::new();
+        let mut bfs = Bfs::new(&self.graph, start);
+        while let Some(node_idx) = bfs.next(&self.graph) {
+            order.push(node_idx);
+        }
+        order
+    }
+
+    /// Fewest-edges path from `from` to `to`, both included, or `None` when `to` can't be reached.
+    pub fn shortest_path(&self, from: NodeIndex, to: NodeIndex) -> Option<Vec<NodeIndex>> {
+        astar(&self.graph, from, |node_idx| node_idx == to, |_| 1, |_| 0).map(|(_, path)| path)
+    }
+
+    /// Every cycle in the graph, as the nodes of each strongly connected component with more than one node or with a
+    /// self-loop. Nodes within a cycle, and the cycles themselves, are ordered by index.
+    pub fn all_cycles(&self) -> Vec<Vec<NodeIndex>> {
+        let mut cycles: Vec<Vec<NodeIndex>> = tarjan_scc(&self.graph)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || self.graph.contains_edge(component[0], component[0])
+            })
+            .map(|mut component| {
+                component.sort();
+                component
+            })
+            .collect();
+        cycles.sort();
+        cycles
+    }
+
+    /// Each node's depth: the number of edges on the longest path reaching it from a node without incoming edges,
+    /// so source nodes are at depth 0 and a join sits below its longest branch. Fails if the graph has a cycle.
+    pub fn depths(&self) -> Result<HashMap<NodeIndex, usize>> {
+        let order = toposort(&self.graph, None)
+            .map_err(|_| Error::Input("workflow graph contains a cycle".to_string()))?;
+        let mut depths: HashMap<NodeIndex, usize> = HashMap::new();
+        for node_idx in order {
+            let depth = self
+                .graph
+                .neighbors_directed(node_idx, Direction::Incoming)
+                .map(|predecessor| depths[&predecessor] + 1)
+                .max()
+                .unwrap_or(0);
+            depths.insert(node_idx, depth);
+        }
+        Ok(depths)
+    }
+
+    /// How many distinct paths lead from `from` to `to`, counting each sequence of nodes once, as a measure of how
+    /// many routes data can take. A node has one path to itself. The count for each node is worked out once, from
+    /// the counts of its successors, and saturates at `u64::MAX` rather than overflowing. Fails if either node doesn't
+    /// exist or the graph has a cycle, around which paths could go on forever. The whole graph is sorted, so any cycle
+    /// fails the count, even one that no path from `from` to `to` passes through.
+    pub fn path_count(&self, from: NodeIndex, to: NodeIndex) -> Result<u64> {
+        for node_idx in [from, to] {
+            if self.graph.node_weight(node_idx).is_none() {
+                return Err(Error::Input(format!(
+                    "node {} does not exist",
+                    node_idx.index()
+                )));
+            }
+        }
+        let order = toposort(&self.graph, None)
+            .map_err(|_| Error::Input("workflow graph contains a cycle".to_string()))?;
+
+        // Successors come before their predecessors in reverse topological order, so their counts are known.
+        let mut counts: HashMap<NodeIndex, u64> = HashMap::new();
+        for node_idx in order.into_iter().rev() {
+            let count = if node_idx == to {
+                1
+            } else {
+                let successors: HashSet<NodeIndex> = self.graph.neighbors(node_idx).collect();
+                successors.iter().fold(0u64, |total, successor| {
+                    total.saturating_add(counts[successor])
+                })
+            };
+            counts.insert(node_idx, count);
+        }
+        Ok(counts[&from])
+    }
+
+    /// Wiring at the level of ports: a node for each `(node id, port)` pair that a router declares or an edge uses, and
+    /// an edge from the source port to the target port of every workflow edge. Edges joining the same two ports are
+    /// merged. Declared ports come first, in node order, followed by the ports met along the edges.
+    pub fn port_graph(&self) -> DiGraph<(String, String), ()> {
+        let mut ports = PortGraph::default();
+        for node in self.graph.node_weights() {
+            for port in node.routing_ports().unwrap_or_default() {
+                ports.port(&node.id, &port);
+            }
+        }
+        for edge in self.graph.edge_references() {
+            let from = ports.port(&self.graph[edge.source()].id, &edge.weight().from_port);
+            let to = ports.port(&self.graph[edge.target()].id, &edge.weight().to_port);
+            ports.graph.update_edge(from, to, ());
+        }
+        ports.graph
+    }
+
+    /// Depth-first walk from `start` in `direction`, not counting `start` as visited until an edge leads back to it.
+    fn reachable(&self, start: NodeIndex, direction: Direction) -> HashSet<NodeIndex> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![start];
+        while let Some(current) = stack.pop() {
+            for next in self.graph.neighbors_directed(current, direction) {
+                if visited.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        visited
+    }
+}
+
+/// A port graph being built by [`Workflow::port_graph`], with each port's index so every port is added once.
+#[derive(Default)]
+struct PortGraph {
+    graph: DiGraph<(String, String), ()>,
+    indices: HashMap<(String, String), NodeIndex>,
+}
+
+impl PortGraph {
+    /// The index of `port` on the node `node_id`, adding it on first use.
+    fn port(&mut self, node_id: &str, port: &str) -> NodeIndex {
+        let key = (node_id.to_string(), port.to_string());
+        if let Some(&port_idx) = self.indices.get(&key) {
+            return port_idx;
+        }
+        let port_idx = self.graph.add_node(key.clone());
+        self.indices.insert(key, port_idx);
+        port_idx
+    }
+}
@@ -1,5 +1,11 @@
-use super::{Edge, Node, Workflow};
+use super::{
+    node_to_yaml, register_node_type, validate_node_params, Edge, EdgeDefinition, EdgeKind,
+    ExecutionState, GraphDefinition, LoadOptions, Node, NodeAction, NodeDefinition, NodeKind,
+    Workflow, WorkflowDefinition,
+};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[cfg(test)]
 mod tests {
@@ -39,8 +45,8 @@ mod tests {
             assert_eq!(workflow.id, "2d4e1c6a-eb70-11f0-9b6c-7c70db10a7e3");
             assert_eq!(workflow.name, "CalculateCloudCorrectionFactor");
             assert_eq!(
-                workflow.entry_graph_id,
-                Some("a2df1dee-eba2-11f0-b126-7c70db10a7e3".to_string())
+                workflow.primary_entry(),
+                Some("a2df1dee-eba2-11f0-b126-7c70db10a7e3")
             );
 
             // Check that the workflow graph has nodes
@@ -79,8 +85,8 @@ mod tests {
             assert_eq!(workflow.id, "2d4e1c6a-eb70-11f0-9b6c-7c70db10a7e3");
             assert_eq!(workflow.name, "CalculateCloudCorrectionFactor");
             assert_eq!(
-                workflow.entry_graph_id,
-                Some("a2df1dee-eba2-11f0-b126-7c70db10a7e3".to_string())
+                workflow.primary_entry(),
+                Some("a2df1dee-eba2-11f0-b126-7c70db10a7e3")
             );
 
             // Check that the workflow graph has nodes
@@ -187,4 +193,2291 @@ mod tests {
 
         let workflow = result.unwrap();
     }
+
+    const INLINE_WORKFLOW: &str = r#"
+id: 5b0e7c2a-0000-4000-8000-000000000001
+name: InlineWorkflow
+entryGraphId: graph-main
+graphs:
+  - id: graph-main
+    name: MainGraph
+    nodes:
+      - id: node-reader
+        name: CsvReader
+        type: action
+        action: CsvReader
+        with:
+          format: csv
+      - id: node-sink
+        name: Sink
+        type: action
+        action: NoopSink
+    edges:
+      - id: edge-reader-sink
+        from: node-reader
+        to: node-sink
+        fromPort: default
+        toPort: default
+"#;
+
+    #[test]
+    fn test_load_from_str_inline_workflow() {
+        let workflow =
+            Workflow::load_from_str(INLINE_WORKFLOW).expect("inline workflow should parse");
+
+        assert_eq!(workflow.name, "InlineWorkflow");
+        assert_eq!(workflow.primary_entry(), Some("graph-main"));
+        assert_eq!(workflow.graph.node_count(), 2);
+        assert_eq!(workflow.graph.edge_count(), 1);
+
+        let edge_idx = workflow.graph.edge_indices().next().unwrap();
+        let (source, target) = workflow.graph.edge_endpoints(edge_idx).unwrap();
+        assert_eq!(workflow.graph[source].id, "node-reader");
+        assert_eq!(workflow.graph[target].id, "node-sink");
+        assert_eq!(workflow.graph[source].subgraph, "graph-main");
+    }
+
+    #[test]
+    fn test_load_from_str_rejects_include_without_base() {
+        let yaml = "id: a\nname: b\ngraphs:\n  - !include ../time-to-time-value/workflow.yml\n";
+        let result = Workflow::load_from_str(yaml);
+        assert!(result.is_err(), "include without a base dir should fail");
+    }
+
+    #[test]
+    fn test_load_from_str_with_base_resolves_includes() {
+        let base_dir = std::env::current_dir()
+            .expect("Failed to get current directory")
+            .join("documents")
+            .join("solar-radiation")
+            .join("solar-potential");
+        let yaml = "id: a\nname: b\ngraphs:\n  - !include ../time-to-time-value/workflow.yml\n";
+
+        let workflow = Workflow::load_from_str_with_base(yaml, &base_dir)
+            .expect("include should be resolved against the base dir");
+        assert_eq!(workflow.graph.node_count(), 7);
+        assert_eq!(workflow.graph.edge_count(), 6);
+    }
+
+    fn single_node_workflow(node_type: &str) -> String {
+        format!(
+            "id: wf\nname: SingleNode\nentryGraphId: g\ngraphs:\n  - id: g\n    name: G\n    nodes:\n      - id: node-1\n        name: Only\n        type: {}\n        action: NoopSink\n",
+            node_type
+        )
+    }
+
+    #[test]
+    fn test_registered_node_type_is_kept() {
+        register_node_type("customSink");
+        let workflow = Workflow::load_from_str(&single_node_workflow("customSink")).unwrap();
+        let node = workflow.graph.node_weights().next().unwrap();
+        assert_eq!(node.node_type.as_deref(), Some("customSink"));
+        assert!(workflow.nodes_to_csv().contains(",customSink,"));
+        assert!(node_to_yaml(node).unwrap().contains("type: customSink"));
+        let reloaded = Workflow::load_from_str(&workflow.to_yaml().unwrap()).unwrap();
+        let node = reloaded.graph.node_weights().next().unwrap();
+        assert_eq!(node.node_type.as_deref(), Some("customSink"));
+    }
+
+    #[test]
+    fn test_known_node_types_are_accepted() {
+        assert!(Workflow::load_from_str(&single_node_workflow("action")).is_ok());
+        assert!(Workflow::load_from_str(&single_node_workflow("subGraph")).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_node_type_is_rejected() {
+        let err = Workflow::load_from_str(&single_node_workflow("actoin"))
+            .expect_err("misspelled node type should fail");
+        let message = err.to_string();
+        assert!(
+            message.contains("actoin"),
+            "error should name the type: {}",
+            message
+        );
+        assert!(
+            message.contains("node-1"),
+            "error should name the node: {}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_registered_node_type_is_accepted() {
+        assert!(Workflow::load_from_str(&single_node_workflow("customRouter")).is_err());
+        register_node_type("customRouter");
+        assert!(Workflow::load_from_str(&single_node_workflow("customRouter")).is_ok());
+    }
+
+    #[test]
+    fn test_node_action_parses_known_actions() {
+        assert_eq!(
+            "CsvReader".parse::<NodeAction>().unwrap(),
+            NodeAction::CsvReader
+        );
+        assert_eq!(
+            "AttributeManager".parse::<NodeAction>().unwrap(),
+            NodeAction::AttributeManager
+        );
+        assert_eq!(NodeAction::FeatureFilter.to_string(), "FeatureFilter");
+
+        let workflow = Workflow::load_from_str(INLINE_WORKFLOW).unwrap();
+        let reader = workflow
+            .graph
+            .node_weights()
+            .find(|node| node.id == "node-reader")
+            .unwrap();
+        assert_eq!(reader.kind, NodeKind::Action(NodeAction::CsvReader));
+    }
+
+    #[test]
+    fn test_node_action_falls_back_to_other() {
+        let action = "CsvReadr".parse::<NodeAction>().unwrap();
+        assert_eq!(action, NodeAction::Other("CsvReadr".to_string()));
+        assert_eq!(action.to_string(), "CsvReadr");
+    }
+
+    #[test]
+    fn test_validate_node_params_complete_node() {
+        let workflow = Workflow::load_from_str(INLINE_WORKFLOW).unwrap();
+        let mut reader = workflow
+            .graph
+            .node_weights()
+            .find(|node| node.id == "node-reader")
+            .unwrap()
+            .clone();
+        reader
+            .with
+            .as_mut()
+            .unwrap()
+            .insert("inline".to_string(), "a,b\n1,2".into());
+
+        assert!(validate_node_params(&reader).is_ok());
+    }
+
+    #[test]
+    fn test_validate_node_params_missing_key() {
+        // The inline CsvReader has a format but no data source.
+        let workflow = Workflow::load_from_str(INLINE_WORKFLOW).unwrap();
+        let reader = workflow
+            .graph
+            .node_weights()
+            .find(|node| node.id == "node-reader")
+            .unwrap();
+
+        let message = validate_node_params(reader).unwrap_err().to_string();
+        assert!(message.contains("inline or dataset"), "{}", message);
+        assert!(workflow.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_fixture_node_params() {
+        let workflow_path = std::env::current_dir()
+            .expect("Failed to get current directory")
+            .join("documents")
+            .join("solar-radiation")
+            .join("solar-potential")
+            .join("workflow.yaml");
+        let workflow = Workflow::load_from_path(workflow_path).unwrap();
+
+        for node in workflow.graph.node_weights() {
+            assert!(
+                validate_node_params(node).is_ok(),
+                "{:?}",
+                validate_node_params(node)
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_reports_every_failure() {
+        // node-b -> node-a closes a cycle and the entry graph id points nowhere.
+        let yaml = r#"
+id: wf
+name: Broken
+entryGraphId: missing-graph
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - id: node-a
+        name: A
+        type: action
+        action: NoopSink
+      - id: node-b
+        name: B
+        type: action
+        action: NoopSink
+    edges:
+      - id: edge-ab
+        from: node-a
+        to: node-b
+        fromPort: default
+        toPort: default
+      - id: edge-ba
+        from: node-b
+        to: node-a
+        fromPort: default
+        toPort: default
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+
+        let message = workflow.validate().unwrap_err().to_string();
+        assert!(message.contains("cycle"), "{}", message);
+        assert!(message.contains("missing-graph"), "{}", message);
+    }
+
+    #[test]
+    fn test_check_orphans_skips_single_node_graphs() {
+        // Graph g holds node-a alone; graph h holds two unconnected nodes.
+        let yaml = r#"
+id: wf
+name: Orphans
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - id: node-a
+        name: A
+        type: action
+        action: NoopSink
+    edges: []
+  - id: h
+    name: H
+    nodes:
+      - id: node-b
+        name: B
+        type: action
+        action: NoopSink
+      - id: node-c
+        name: C
+        type: action
+        action: NoopSink
+    edges: []
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+
+        let message = workflow.check_orphans().unwrap_err().to_string();
+        assert!(message.contains("node-b, node-c"), "{}", message);
+        assert!(!message.contains("node-a"), "{}", message);
+    }
+
+    #[test]
+    fn test_validation_problems_lists_each_failed_check() {
+        // node-b -> node-a closes a cycle and the entry graph id points nowhere.
+        let yaml = r#"
+id: wf
+name: Broken
+entryGraphId: missing-graph
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - id: node-a
+        name: A
+        type: action
+        action: NoopSink
+      - id: node-b
+        name: B
+        type: action
+        action: NoopSink
+    edges:
+      - id: edge-ab
+        from: node-a
+        to: node-b
+        fromPort: default
+        toPort: default
+      - id: edge-ba
+        from: node-b
+        to: node-a
+        fromPort: default
+        toPort: default
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+
+        let problems = workflow.validation_problems();
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].contains("cycle"), "{:?}", problems);
+        assert!(problems[1].contains("missing-graph"), "{:?}", problems);
+    }
+
+    #[test]
+    fn test_validate_fixture_passes() {
+        let workflow_path = std::env::current_dir()
+            .expect("Failed to get current directory")
+            .join("documents")
+            .join("solar-radiation")
+            .join("calculate-cloud-correction")
+            .join("workflow.yaml");
+        let workflow = Workflow::load_from_path(workflow_path).unwrap();
+
+        assert!(workflow.validate().is_ok(), "{:?}", workflow.validate());
+    }
+
+    #[test]
+    fn test_routing_port_edges_are_control_edges() {
+        let workflow_path = std::env::current_dir()
+            .expect("Failed to get current directory")
+            .join("documents")
+            .join("solar-radiation")
+            .join("calculate-cloud-correction")
+            .join("workflow.yaml");
+        let workflow = Workflow::load_from_path(workflow_path).unwrap();
+
+        for edge in workflow.graph.edge_weights() {
+            let expected = if edge.from_port == "yearPort" || edge.from_port == "year9999Port" {
+                EdgeKind::Control
+            } else {
+                EdgeKind::Data
+            };
+            assert_eq!(edge.edge_kind, expected, "edge {}", edge.id);
+        }
+    }
+
+    #[test]
+    fn test_duplicate_node() {
+        let mut workflow = Workflow::load_from_str(INLINE_WORKFLOW).unwrap();
+        let reader_idx = workflow
+            .graph
+            .node_indices()
+            .find(|&idx| workflow.graph[idx].id == "node-reader")
+            .unwrap();
+
+        let copy_idx = workflow.duplicate_node(reader_idx);
+
+        assert_eq!(workflow.graph.node_count(), 3);
+        assert_eq!(workflow.graph.edge_count(), 1, "edges are not copied");
+        let original = &workflow.graph[reader_idx];
+        let copy = &workflow.graph[copy_idx];
+        assert_ne!(copy.id, original.id);
+        assert_eq!(copy.name, "CsvReader copy");
+        assert_eq!(copy.kind, original.kind);
+    }
+
+    #[test]
+    fn test_rename_node_and_edge() {
+        let mut workflow = Workflow::load_from_str(INLINE_WORKFLOW).unwrap();
+        let node_idx = workflow.graph.node_indices().next().unwrap();
+        let edge_idx = workflow.graph.edge_indices().next().unwrap();
+
+        workflow
+            .rename_node(node_idx, "SourceReader".to_string())
+            .unwrap();
+        workflow.rename_edge(edge_idx, "rows".to_string()).unwrap();
+
+        assert_eq!(workflow.graph[node_idx].name, "SourceReader");
+        assert_eq!(workflow.graph[edge_idx].name, "rows");
+    }
+
+    #[test]
+    fn test_rename_rejects_empty_names() {
+        let mut workflow = Workflow::load_from_str(INLINE_WORKFLOW).unwrap();
+        let node_idx = workflow.graph.node_indices().next().unwrap();
+        let edge_idx = workflow.graph.edge_indices().next().unwrap();
+
+        assert!(workflow.rename_node(node_idx, "".to_string()).is_err());
+        assert!(workflow.rename_edge(edge_idx, "  ".to_string()).is_err());
+        assert_eq!(workflow.graph[node_idx].name, "CsvReader");
+        assert_eq!(workflow.graph[edge_idx].name, "default");
+    }
+
+    /// A -> B, A -> C, B -> C, all in graph `g`.
+    const TRIANGLE_WORKFLOW: &str = r#"
+id: wf-triangle
+name: Triangle
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+      - { id: b, name: B, type: action, action: NoopSink }
+      - { id: c, name: C, type: action, action: NoopSink }
+    edges:
+      - { id: ab, from: a, to: b, fromPort: default, toPort: default }
+      - { id: ac, from: a, to: c, fromPort: default, toPort: default }
+      - { id: bc, from: b, to: c, fromPort: default, toPort: default }
+"#;
+
+    fn node_by_id(workflow: &Workflow, id: &str) -> petgraph::graph::NodeIndex {
+        workflow
+            .graph
+            .node_indices()
+            .find(|&idx| workflow.graph[idx].id == id)
+            .unwrap_or_else(|| panic!("node {} not found", id))
+    }
+
+    fn ids(
+        workflow: &Workflow,
+        nodes: impl IntoIterator<Item = petgraph::graph::NodeIndex>,
+    ) -> Vec<String> {
+        let mut ids: Vec<String> = nodes
+            .into_iter()
+            .map(|idx| workflow.graph[idx].id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_neighbor_queries_follow_edge_direction() {
+        let workflow = Workflow::load_from_str(TRIANGLE_WORKFLOW).unwrap();
+        let a = node_by_id(&workflow, "a");
+        let b = node_by_id(&workflow, "b");
+        let c = node_by_id(&workflow, "c");
+
+        assert_eq!(ids(&workflow, workflow.successors(a)), vec!["b", "c"]);
+        assert!(workflow.predecessors(a).is_empty());
+        assert_eq!(ids(&workflow, workflow.predecessors(c)), vec!["a", "b"]);
+        assert!(workflow.successors(c).is_empty());
+        assert_eq!(ids(&workflow, workflow.successors(b)), vec!["c"]);
+
+        assert_eq!((workflow.in_degree(a), workflow.out_degree(a)), (0, 2));
+        assert_eq!((workflow.in_degree(b), workflow.out_degree(b)), (1, 1));
+        assert_eq!((workflow.in_degree(c), workflow.out_degree(c)), (2, 0));
+    }
+
+    /// A -> B -> C -> D, plus X -> C.
+    const CHAIN_WORKFLOW: &str = r#"
+id: wf-chain
+name: Chain
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+      - { id: b, name: B, type: action, action: NoopSink }
+      - { id: c, name: C, type: action, action: NoopSink }
+      - { id: d, name: D, type: action, action: NoopSink }
+      - { id: x, name: X, type: action, action: NoopSink }
+    edges:
+      - { id: ab, from: a, to: b, fromPort: default, toPort: default }
+      - { id: bc, from: b, to: c, fromPort: default, toPort: default }
+      - { id: cd, from: c, to: d, fromPort: default, toPort: default }
+      - { id: xc, from: x, to: c, fromPort: default, toPort: default }
+"#;
+
+    #[test]
+    fn test_descendants_and_ancestors_on_chain() {
+        let workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        let b = node_by_id(&workflow, "b");
+        let c = node_by_id(&workflow, "c");
+
+        // Transitive successors only: neither the start node nor anything upstream of it
+        assert_eq!(ids(&workflow, workflow.descendants(b)), vec!["c", "d"]);
+        assert_eq!(ids(&workflow, workflow.ancestors(b)), vec!["a"]);
+
+        assert_eq!(ids(&workflow, workflow.descendants(c)), vec!["d"]);
+        assert_eq!(ids(&workflow, workflow.ancestors(c)), vec!["a", "b", "x"]);
+        assert!(workflow.descendants(node_by_id(&workflow, "d")).is_empty());
+    }
+
+    fn action_node_definition(id: &str, action: &str) -> NodeDefinition {
+        NodeDefinition {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            node_type: "action".to_string(),
+            action: Some(action.to_string()),
+            sub_graph_id: None,
+            with: None,
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_from_definition_builds_graph_without_yaml() {
+        let graph_def = GraphDefinition {
+            id: "g".to_string(),
+            name: "G".to_string(),
+            nodes: vec![
+                action_node_definition("reader", "CsvReader"),
+                action_node_definition("sink", "NoopSink"),
+            ],
+            edges: vec![EdgeDefinition {
+                id: "reader-sink".to_string(),
+                from: "reader".to_string(),
+                to: "sink".to_string(),
+                from_port: "default".to_string(),
+                to_port: "default".to_string(),
+                weight: None,
+                with: None,
+            }],
+        };
+        let definition = WorkflowDefinition {
+            id: "wf".to_string(),
+            name: "In code".to_string(),
+            entry_graph_ids: vec!["g".to_string()],
+            with: None,
+            graphs: vec![serde_yaml::to_value(graph_def).unwrap()],
+        };
+
+        let workflow = Workflow::from_definition(definition).unwrap();
+        assert_eq!(workflow.name, "In code");
+        assert_eq!(workflow.graph.node_count(), 2);
+        assert_eq!(workflow.graph.edge_count(), 1);
+
+        let reader = node_by_id(&workflow, "reader");
+        let sink = node_by_id(&workflow, "sink");
+        assert_eq!(workflow.successors(reader), vec![sink]);
+        assert_eq!(
+            workflow.graph[reader].kind,
+            NodeKind::Action(NodeAction::CsvReader)
+        );
+        assert_eq!(workflow.graph[sink].subgraph, "g");
+    }
+
+    /// Two independent graphs, `first` and `second`, each with a single node. `{}` is the `entryGraphId` value.
+    fn two_graph_workflow(entry: &str) -> String {
+        format!(
+            r#"
+id: wf-entries
+name: Entries
+entryGraphId: {}
+graphs:
+  - id: first
+    name: First
+    nodes:
+      - {{ id: a, name: A, type: action, action: NoopSink }}
+  - id: second
+    name: Second
+    nodes:
+      - {{ id: b, name: B, type: action, action: NoopSink }}
+"#,
+            entry
+        )
+    }
+
+    #[test]
+    fn test_entry_graph_scalar_form() {
+        let workflow = Workflow::load_from_str(&two_graph_workflow("second")).unwrap();
+        assert_eq!(workflow.entry_graph_ids, vec!["second"]);
+        assert_eq!(workflow.primary_entry(), Some("second"));
+        assert!(workflow.check_entry_graph().is_ok());
+    }
+
+    #[test]
+    fn test_entry_graph_list_form() {
+        let workflow = Workflow::load_from_str(&two_graph_workflow("[first, second]")).unwrap();
+        assert_eq!(workflow.entry_graph_ids, vec!["first", "second"]);
+        assert_eq!(workflow.primary_entry(), Some("first"));
+        assert!(workflow.check_entry_graph().is_ok());
+    }
+
+    #[test]
+    fn test_entry_graph_list_with_missing_id() {
+        let workflow = Workflow::load_from_str(&two_graph_workflow("[first, nowhere]")).unwrap();
+        let err = workflow.check_entry_graph().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("entry graph nowhere does not exist"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    /// Nodes `a`, `b`, `c` in graph `g`, wired by the given `from -> to` pairs.
+    fn wired_workflow(node_order: [&str; 3], edges: &[(&str, &str)]) -> Workflow {
+        let nodes: String = node_order
+            .iter()
+            .map(|id| {
+                format!("      - {{ id: {id}, name: {id}, type: action, action: NoopSink }}\n")
+            })
+            .collect();
+        let edges: String = edges
+            .iter()
+            .map(|(from, to)| {
+                format!(
+                    "      - {{ id: {from}{to}, from: {from}, to: {to}, fromPort: default, toPort: default }}\n"
+                )
+            })
+            .collect();
+        let yaml = format!(
+            "id: wf\nname: Wired\nentryGraphId: g\ngraphs:\n  - id: g\n    name: G\n    nodes:\n{}    edges:\n{}",
+            nodes, edges
+        );
+        Workflow::load_from_str(&yaml).unwrap()
+    }
+
+    #[test]
+    fn test_equality_ignores_insertion_order() {
+        let first = wired_workflow(["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        let second = wired_workflow(["c", "a", "b"], &[("b", "c"), ("a", "b")]);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_equality_detects_different_wiring_with_same_counts() {
+        let chain = wired_workflow(["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        let fork = wired_workflow(["a", "b", "c"], &[("a", "b"), ("a", "c")]);
+        let reversed = wired_workflow(["a", "b", "c"], &[("b", "a"), ("c", "b")]);
+
+        assert_eq!(chain.graph.edge_count(), fork.graph.edge_count());
+        assert_ne!(chain, fork);
+        assert_ne!(chain, reversed);
+    }
+
+    #[test]
+    fn test_equality_compares_node_ids_and_edge_names() {
+        let workflow = wired_workflow(["a", "b", "c"], &[("a", "b")]);
+
+        let mut renamed_node = workflow.clone();
+        let node_idx = renamed_node.graph.node_indices().next().unwrap();
+        renamed_node.graph[node_idx].id = "z".to_string();
+        assert_ne!(workflow, renamed_node);
+
+        let mut renamed_edge = workflow.clone();
+        let edge_idx = renamed_edge.graph.edge_indices().next().unwrap();
+        renamed_edge.graph[edge_idx].name = "other".to_string();
+        assert_ne!(workflow, renamed_edge);
+    }
+
+    #[test]
+    fn test_execution_state_transitions() {
+        let mut workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        let a = node_by_id(&workflow, "a");
+        let b = node_by_id(&workflow, "b");
+
+        assert_eq!(workflow.execution_state(a), ExecutionState::Pending);
+
+        workflow.mark_running(a).unwrap();
+        assert_eq!(workflow.execution_state(a), ExecutionState::Running);
+        workflow.mark_succeeded(a).unwrap();
+        assert_eq!(workflow.execution_state(a), ExecutionState::Succeeded);
+
+        workflow.mark_running(b).unwrap();
+        workflow.mark_failed(b).unwrap();
+        assert_eq!(workflow.execution_state(b), ExecutionState::Failed);
+
+        workflow.reset_states();
+        assert_eq!(workflow.execution_state(a), ExecutionState::Pending);
+        assert_eq!(workflow.execution_state(b), ExecutionState::Pending);
+    }
+
+    #[test]
+    fn test_execution_state_for_removed_node() {
+        let mut workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        let a = node_by_id(&workflow, "a");
+        let x = node_by_id(&workflow, "x");
+        workflow.mark_running(a).unwrap();
+        workflow.mark_failed(x).unwrap();
+
+        // `x` is the last node, so petgraph moves it into the freed index; its state moves along with it
+        workflow.remove_node(a).unwrap();
+        let x = node_by_id(&workflow, "x");
+        assert_eq!(workflow.execution_state(x), ExecutionState::Failed);
+        assert_eq!(workflow.node_states.len(), 1);
+
+        let stale = petgraph::graph::NodeIndex::new(workflow.graph.node_count());
+        assert!(workflow.mark_running(stale).is_err());
+        assert_eq!(workflow.execution_state(stale), ExecutionState::Pending);
+        assert!(workflow.remove_node(stale).is_none());
+    }
+
+    #[test]
+    fn test_execution_state_is_ignored_by_equality() {
+        let workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        let mut running = workflow.clone();
+        running.mark_running(node_by_id(&running, "a")).unwrap();
+        assert_eq!(workflow, running);
+    }
+
+    #[test]
+    fn test_find_and_search_nodes() {
+        let workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        assert_eq!(workflow.find_node("c"), Some(node_by_id(&workflow, "c")));
+        assert_eq!(workflow.find_node("missing"), None);
+
+        assert_eq!(ids(&workflow, workflow.search("x")), vec!["x"]);
+        assert_eq!(ids(&workflow, workflow.search("  B ")), vec!["b"]);
+        assert!(workflow.search("").is_empty());
+    }
+
+    /// Writes `files` (name, content) into a fresh temporary directory and returns it.
+    fn temp_workflow_dir(files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("workflow-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, content) in files {
+            std::fs::write(dir.join(name), content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_include_depth_limit() {
+        // workflow.yaml -> graph.yml -> node.yml is two includes deep
+        let dir = temp_workflow_dir(&[
+            (
+                "workflow.yaml",
+                "id: wf\nname: Nested\nentryGraphId: g\ngraphs:\n  - !include graph.yml\n",
+            ),
+            (
+                "graph.yml",
+                "id: g\nname: G\nnodes:\n  - !include node.yml\n",
+            ),
+            (
+                "node.yml",
+                "id: only\nname: Only\ntype: action\naction: NoopSink\n",
+            ),
+        ]);
+        let path = dir.join("workflow.yaml");
+
+        let shallow = LoadOptions {
+            max_include_depth: 1,
+            ..Default::default()
+        };
+        let too_deep = Workflow::load_from_path_with_options(path.clone(), &shallow);
+        let default_limit = Workflow::load_from_path(path);
+        std::fs::remove_dir_all(&dir).ok();
+
+        let err = too_deep.unwrap_err();
+        assert!(
+            err.to_string().contains("maximum include depth of 1"),
+            "unexpected error: {}",
+            err
+        );
+        assert_eq!(default_limit.unwrap().graph.node_count(), 1);
+    }
+
+    #[test]
+    fn test_include_keeps_four_space_indentation() {
+        let dir = temp_workflow_dir(&[
+            (
+                "workflow.yaml",
+                "id: wf\nname: Wide\nentryGraphId: g\ngraphs:\n    -   !include graph.yml\n",
+            ),
+            // Indented as a whole and nested with four spaces
+            (
+                "graph.yml",
+                "    id: g\n    name: G\n    nodes:\n        -   id: a\n            name: A\n            type: action\n            action: NoopSink\n        -   id: b\n            name: B\n            type: action\n            action: NoopSink\n    edges:\n        -   id: ab\n            from: a\n            to: b\n            fromPort: default\n            toPort: default\n",
+            ),
+        ]);
+        let loaded = Workflow::load_from_path(dir.join("workflow.yaml"));
+        std::fs::remove_dir_all(&dir).ok();
+
+        let workflow = loaded.expect("merged YAML should parse");
+        assert_eq!(workflow.graph.node_count(), 2);
+        assert_eq!(workflow.graph.edge_count(), 1);
+        assert_eq!(workflow.primary_entry(), Some("g"));
+    }
+
+    #[test]
+    fn test_graph_ids_match_solar_potential_graphs() {
+        let workflow_path = std::env::current_dir()
+            .expect("Failed to get current directory")
+            .join("documents")
+            .join("solar-radiation")
+            .join("solar-potential")
+            .join("workflow.yaml");
+        let workflow = Workflow::load_from_path(workflow_path).unwrap();
+
+        let mut graph_ids = workflow.graph_ids();
+        graph_ids.sort();
+        assert_eq!(
+            graph_ids,
+            vec![
+                "78ce67ea-d017-11f0-99c4-7c70db10a7e3",
+                "8a6c4f2e-9b1d-4e7a-b3c5-6f8e2a4b9d7c",
+                "ef57d360-df12-11f0-a558-7c70db10a7e3",
+            ]
+        );
+        assert_eq!(
+            workflow.graph_name("ef57d360-df12-11f0-a558-7c70db10a7e3"),
+            Some("SolarPotentialWorkflow")
+        );
+        assert_eq!(
+            workflow.graph_name("78ce67ea-d017-11f0-99c4-7c70db10a7e3"),
+            Some("TimeDataProcessor")
+        );
+    }
+
+    #[test]
+    fn test_nodes_to_csv() {
+        let mut workflow = Workflow::load_from_str(INLINE_WORKFLOW).unwrap();
+        let reader = node_by_id(&workflow, "node-reader");
+        workflow.graph[reader].name = "Reader, \"CSV\"".to_string();
+
+        let csv = workflow.nodes_to_csv();
+        let mut rows = csv.split("\r\n");
+        assert_eq!(rows.next(), Some("id,name,type,action,subgraph"));
+        assert!(
+            csv.contains("node-reader,\"Reader, \"\"CSV\"\"\",action,CsvReader,graph-main\r\n"),
+            "unexpected csv: {}",
+            csv
+        );
+        assert!(csv.contains("node-sink,"));
+        // Header, two nodes and the empty remainder after the final CRLF
+        assert_eq!(csv.split("\r\n").count(), 4);
+    }
+
+    /// A single-graph workflow with `count` nodes chained by `count - 1` edges.
+    fn generated_chain(count: usize) -> String {
+        let mut yaml =
+            String::from("id: wf\nname: Generated\nentryGraphId: g\ngraphs:\n  - id: g\n    name: G\n    nodes:\n");
+        for i in 0..count {
+            yaml.push_str(&format!(
+                "      - {{ id: n{i}, name: N{i}, type: action, action: NoopSink }}\n"
+            ));
+        }
+        yaml.push_str("    edges:\n");
+        for i in 1..count {
+            yaml.push_str(&format!(
+                "      - {{ id: e{i}, from: n{}, to: n{i}, fromPort: default, toPort: default }}\n",
+                i - 1
+            ));
+        }
+        yaml
+    }
+
+    #[test]
+    fn test_node_and_edge_count_limits() {
+        let dir = temp_workflow_dir(&[("workflow.yaml", &generated_chain(2_000))]);
+        let path = dir.join("workflow.yaml");
+
+        let node_capped = Workflow::load_from_path_with_options(
+            path.clone(),
+            &LoadOptions {
+                max_nodes: Some(1_000),
+                ..Default::default()
+            },
+        );
+        let edge_capped = Workflow::load_from_path_with_options(
+            path.clone(),
+            &LoadOptions {
+                max_edges: Some(1_000),
+                ..Default::default()
+            },
+        );
+        let within_limits = Workflow::load_from_path_with_options(
+            path,
+            &LoadOptions {
+                max_nodes: Some(2_000),
+                max_edges: Some(1_999),
+                ..Default::default()
+            },
+        );
+        std::fs::remove_dir_all(&dir).ok();
+
+        let err = node_capped.unwrap_err();
+        assert!(
+            err.to_string().contains("more than 1000 nodes"),
+            "unexpected error: {}",
+            err
+        );
+        let err = edge_capped.unwrap_err();
+        assert!(
+            err.to_string().contains("more than 1000 edges"),
+            "unexpected error: {}",
+            err
+        );
+        assert_eq!(within_limits.unwrap().graph.node_count(), 2_000);
+    }
+
+    #[test]
+    fn test_exports_are_deterministic_across_loads() {
+        let reordered = CHAIN_WORKFLOW.replace(
+            "      - { id: a, name: A, type: action, action: NoopSink }\n",
+            "",
+        );
+        let reordered = reordered.replace(
+            "      - { id: x, name: X, type: action, action: NoopSink }\n",
+            "      - { id: x, name: X, type: action, action: NoopSink }\n      - { id: a, name: A, type: action, action: NoopSink }\n",
+        );
+
+        let first = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        let second = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        let shuffled = Workflow::load_from_str(&reordered).unwrap();
+        assert_ne!(
+            shuffled.graph[shuffled.graph.node_indices().next().unwrap()].id,
+            "a"
+        );
+
+        let sorted: Vec<&str> = first
+            .nodes_sorted_by_id()
+            .into_iter()
+            .map(|idx| first.graph[idx].id.as_str())
+            .collect();
+        assert_eq!(sorted, vec!["a", "b", "c", "d", "x"]);
+        assert_eq!(first.nodes_to_csv(), second.nodes_to_csv());
+        assert_eq!(first.nodes_to_csv(), shuffled.nodes_to_csv());
+    }
+
+    #[test]
+    fn test_remove_node_bridging_reconnects_chain() {
+        let mut workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        let removed = workflow
+            .remove_node_bridging(node_by_id(&workflow, "b"))
+            .unwrap();
+        assert_eq!(removed.id, "b");
+
+        let a = node_by_id(&workflow, "a");
+        let c = node_by_id(&workflow, "c");
+        let bridge = workflow.graph.find_edge(a, c).expect("a -> c bridge");
+        assert_eq!(workflow.graph[bridge].name, "B");
+        assert_eq!(workflow.graph[bridge].from_port, "default");
+        assert_eq!(workflow.graph.edge_count(), 3);
+    }
+
+    #[test]
+    fn test_remove_node_bridging_connects_every_predecessor() {
+        let mut workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        workflow.remove_node_bridging(node_by_id(&workflow, "c"));
+
+        let d = node_by_id(&workflow, "d");
+        assert_eq!(ids(&workflow, workflow.predecessors(d)), vec!["b", "x"]);
+        // Plain removal leaves the neighbors disconnected
+        let mut workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        workflow.remove_node(node_by_id(&workflow, "c"));
+        assert!(workflow.predecessors(node_by_id(&workflow, "d")).is_empty());
+    }
+
+    #[test]
+    fn test_remove_node_bridging_skips_existing_edges() {
+        let mut workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        let (a, c) = (node_by_id(&workflow, "a"), node_by_id(&workflow, "c"));
+        workflow.graph.add_edge(a, c, Edge::default());
+        workflow.remove_node_bridging(node_by_id(&workflow, "b"));
+
+        let (a, c) = (node_by_id(&workflow, "a"), node_by_id(&workflow, "c"));
+        assert_eq!(workflow.graph.edges_connecting(a, c).count(), 1);
+        assert_eq!(workflow.graph.edge_count(), 3);
+    }
+
+    #[test]
+    fn test_remove_node_bridging_skips_self_loops() {
+        // c -> b -> c: removing b must not leave c looping onto itself
+        let mut workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        let (b, c) = (node_by_id(&workflow, "b"), node_by_id(&workflow, "c"));
+        workflow.graph.add_edge(c, b, Edge::default());
+        workflow.remove_node_bridging(b);
+
+        let (a, c) = (node_by_id(&workflow, "a"), node_by_id(&workflow, "c"));
+        assert!(workflow.graph.find_edge(c, c).is_none());
+        assert!(workflow.graph.find_edge(a, c).is_some());
+    }
+
+    #[test]
+    fn test_replace_node_action_keeps_id_name_and_with() {
+        let mut workflow = Workflow::load_from_str(INLINE_WORKFLOW).unwrap();
+        let reader = node_by_id(&workflow, "node-reader");
+        workflow
+            .replace_node_action(reader, NodeAction::FeatureFilter)
+            .unwrap();
+
+        let node = &workflow.graph[reader];
+        assert_eq!(node.kind, NodeKind::Action(NodeAction::FeatureFilter));
+        assert_eq!(node.id, "node-reader");
+        assert_eq!(node.name, "CsvReader");
+        assert_eq!(node.with.as_ref().unwrap()["format"], "csv");
+    }
+
+    #[test]
+    fn test_replace_node_action_rejects_sub_graph_nodes() {
+        let mut workflow = Workflow::load_from_str(INLINE_WORKFLOW).unwrap();
+        let sub_graph = workflow.graph.add_node(Node {
+            id: "node-nested".to_string(),
+            kind: NodeKind::SubGraph("graph-nested".to_string()),
+            ..Default::default()
+        });
+
+        let err = workflow
+            .replace_node_action(sub_graph, NodeAction::NoopSink)
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("node-nested"),
+            "unexpected error: {}",
+            err
+        );
+        assert_eq!(
+            workflow.graph[sub_graph].kind,
+            NodeKind::SubGraph("graph-nested".to_string())
+        );
+    }
+
+    /// Everything a `tracing` fmt subscriber writes, shared with the test that inspects it.
+    #[cfg(feature = "parse-tracing")]
+    #[derive(Clone, Default)]
+    struct CapturedOutput(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    #[cfg(feature = "parse-tracing")]
+    impl std::io::Write for CapturedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "parse-tracing")]
+    #[test]
+    fn test_include_resolution_is_traced() {
+        let dir = temp_workflow_dir(&[
+            (
+                "workflow.yaml",
+                "id: wf\nname: Traced\nentryGraphId: g\ngraphs:\n  - !include graph.yml\n",
+            ),
+            (
+                "graph.yml",
+                "id: g\nname: G\nnodes:\n  - id: only\n    name: Only\n    type: action\n    action: NoopSink\n",
+            ),
+        ]);
+        let captured = CapturedOutput::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        let result = tracing::subscriber::with_default(subscriber, || {
+            Workflow::load_from_path(dir.join("workflow.yaml"))
+        });
+        std::fs::remove_dir_all(&dir).ok();
+        result.unwrap();
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let resolved = output
+            .lines()
+            .find(|line| line.contains("resolved include"))
+            .unwrap_or_else(|| panic!("no include event in:\n{}", output));
+        assert!(resolved.contains("graph.yml"));
+        assert!(resolved.contains("depth=1"));
+        assert!(output.contains("nodes=1"));
+    }
+
+    #[test]
+    fn test_load_timed_reports_each_phase() {
+        let workflow_path = std::env::current_dir()
+            .expect("Failed to get current directory")
+            .join("documents")
+            .join("solar-radiation")
+            .join("solar-potential")
+            .join("workflow.yaml");
+
+        let started = std::time::Instant::now();
+        let (workflow, timings) = Workflow::load_timed(workflow_path.clone()).unwrap();
+        let elapsed = started.elapsed();
+
+        assert_eq!(workflow, Workflow::load_from_path(workflow_path).unwrap());
+        assert!(timings.include_resolution > Duration::ZERO);
+        assert!(timings.deserialization > Duration::ZERO);
+        assert!(timings.graph_construction > Duration::ZERO);
+        assert_eq!(
+            timings.total(),
+            timings.include_resolution + timings.deserialization + timings.graph_construction
+        );
+        // Only the bookkeeping between phases is left out of the total
+        assert!(timings.total() <= elapsed);
+    }
+
+    #[test]
+    fn test_node_description_and_note_are_retained() {
+        let yaml = INLINE_WORKFLOW
+            .replace(
+                "        action: CsvReader\n",
+                "        action: CsvReader\n        description: Reads the building list\n",
+            )
+            .replace(
+                "        action: NoopSink\n",
+                "        action: NoopSink\n        note: Discards everything\n",
+            );
+        let workflow = Workflow::load_from_str(&yaml).unwrap();
+
+        let reader = &workflow.graph[node_by_id(&workflow, "node-reader")];
+        assert_eq!(
+            reader.description.as_deref(),
+            Some("Reads the building list")
+        );
+        let sink = &workflow.graph[node_by_id(&workflow, "node-sink")];
+        assert_eq!(sink.description.as_deref(), Some("Discards everything"));
+
+        let plain = Workflow::load_from_str(INLINE_WORKFLOW).unwrap();
+        assert_eq!(
+            plain.graph[node_by_id(&plain, "node-reader")].description,
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_nodes_by_action() {
+        let mut workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        workflow
+            .replace_node_action(node_by_id(&workflow, "b"), NodeAction::CsvReader)
+            .unwrap();
+        workflow.graph.add_node(Node {
+            id: "nested".to_string(),
+            kind: NodeKind::SubGraph("NoopSink".to_string()),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            ids(&workflow, workflow.find_nodes_by_action("NoopSink")),
+            vec!["a", "c", "d", "x"]
+        );
+        assert_eq!(
+            ids(&workflow, workflow.find_nodes_by_action("CsvReader")),
+            vec!["b"]
+        );
+        assert!(workflow.find_nodes_by_action("FeatureFilter").is_empty());
+    }
+
+    #[test]
+    fn test_shortest_path_on_reachable_pair() {
+        // a -> c directly beats a -> b -> c
+        let workflow = Workflow::load_from_str(TRIANGLE_WORKFLOW).unwrap();
+        let a = node_by_id(&workflow, "a");
+        let c = node_by_id(&workflow, "c");
+        assert_eq!(workflow.shortest_path(a, c), Some(vec![a, c]));
+
+        let workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        let path = workflow
+            .shortest_path(node_by_id(&workflow, "a"), node_by_id(&workflow, "d"))
+            .unwrap();
+        let path: Vec<&str> = path
+            .iter()
+            .map(|&idx| workflow.graph[idx].id.as_str())
+            .collect();
+        assert_eq!(path, vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_shortest_path_on_unreachable_pair() {
+        let workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        // Edges only lead forward, and x is on a separate branch from a
+        assert_eq!(
+            workflow.shortest_path(node_by_id(&workflow, "d"), node_by_id(&workflow, "a")),
+            None
+        );
+        assert_eq!(
+            workflow.shortest_path(node_by_id(&workflow, "a"), node_by_id(&workflow, "x")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_all_cycles_reports_each_cycle() {
+        // a <-> b and c -> d -> e -> c, joined by b -> c, plus a self-loop on f
+        let yaml = r#"
+id: wf-cycles
+name: Cycles
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+      - { id: b, name: B, type: action, action: NoopSink }
+      - { id: c, name: C, type: action, action: NoopSink }
+      - { id: d, name: D, type: action, action: NoopSink }
+      - { id: e, name: E, type: action, action: NoopSink }
+      - { id: f, name: F, type: action, action: NoopSink }
+    edges:
+      - { id: ab, from: a, to: b, fromPort: default, toPort: default }
+      - { id: ba, from: b, to: a, fromPort: default, toPort: default }
+      - { id: bc, from: b, to: c, fromPort: default, toPort: default }
+      - { id: cd, from: c, to: d, fromPort: default, toPort: default }
+      - { id: de, from: d, to: e, fromPort: default, toPort: default }
+      - { id: ec, from: e, to: c, fromPort: default, toPort: default }
+      - { id: ff, from: f, to: f, fromPort: default, toPort: default }
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+        let cycles: Vec<Vec<String>> = workflow
+            .all_cycles()
+            .into_iter()
+            .map(|cycle| ids(&workflow, cycle))
+            .collect();
+        assert_eq!(cycles, vec![vec!["a", "b"], vec!["c", "d", "e"], vec!["f"]]);
+
+        let acyclic = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        assert!(acyclic.all_cycles().is_empty());
+    }
+
+    #[test]
+    fn test_rename_graph_id_updates_every_reference() {
+        let mut workflow = Workflow::load_from_str(&two_graph_workflow("[first, second]")).unwrap();
+        let expand = workflow.graph.add_node(Node {
+            id: "expand-second".to_string(),
+            subgraph: "first".to_string(),
+            kind: NodeKind::SubGraph("second".to_string()),
+            ..Default::default()
+        });
+
+        workflow.rename_graph_id("second", "renamed").unwrap();
+        assert_eq!(
+            workflow.graph[node_by_id(&workflow, "b")].subgraph,
+            "renamed"
+        );
+        assert_eq!(
+            workflow.graph[expand].kind,
+            NodeKind::SubGraph("renamed".to_string())
+        );
+        assert_eq!(workflow.graph_name("renamed"), Some("Second"));
+        assert_eq!(workflow.graph_name("second"), None);
+        assert_eq!(workflow.entry_graph_ids, vec!["first", "renamed"]);
+
+        workflow.rename_graph_id("first", "main").unwrap();
+        assert_eq!(workflow.primary_entry(), Some("main"));
+        assert_eq!(workflow.graph[node_by_id(&workflow, "a")].subgraph, "main");
+        assert_eq!(workflow.graph[expand].subgraph, "main");
+        assert!(workflow.check_entry_graph().is_ok());
+    }
+
+    #[test]
+    fn test_rename_graph_id_rejects_missing_and_taken_ids() {
+        let mut workflow = Workflow::load_from_str(&two_graph_workflow("first")).unwrap();
+        let err = workflow.rename_graph_id("missing", "other").unwrap_err();
+        assert!(
+            err.to_string().contains("missing"),
+            "unexpected error: {}",
+            err
+        );
+        assert!(workflow.rename_graph_id("first", "second").is_err());
+        assert_eq!(workflow.primary_entry(), Some("first"));
+    }
+
+    #[test]
+    fn test_node_to_yaml_round_trips() {
+        let mut workflow = Workflow::load_from_str(INLINE_WORKFLOW).unwrap();
+        let reader = node_by_id(&workflow, "node-reader");
+        workflow.graph[reader].description = Some("Reads the building list".to_string());
+
+        let yaml = node_to_yaml(&workflow.graph[reader]).unwrap();
+        let parsed: NodeDefinition = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(
+            parsed,
+            NodeDefinition {
+                id: "node-reader".to_string(),
+                name: "CsvReader".to_string(),
+                node_type: "action".to_string(),
+                action: Some("CsvReader".to_string()),
+                sub_graph_id: None,
+                with: workflow.graph[reader].with.clone(),
+                description: Some("Reads the building list".to_string()),
+            }
+        );
+        assert!(!yaml.contains("subGraphId"), "unexpected yaml: {}", yaml);
+
+        let nested = Node {
+            id: "node-nested".to_string(),
+            name: "Nested".to_string(),
+            kind: NodeKind::SubGraph("graph-nested".to_string()),
+            ..Default::default()
+        };
+        let parsed: NodeDefinition = serde_yaml::from_str(&node_to_yaml(&nested).unwrap()).unwrap();
+        assert_eq!(parsed.node_type, "subGraph");
+        assert_eq!(parsed.sub_graph_id.as_deref(), Some("graph-nested"));
+        assert_eq!(parsed.action, None);
+    }
+
+    #[test]
+    fn test_lint_reports_orphan_and_unreachable_nodes() {
+        // a -> b is fine, o has no edges, the c <-> d cycle has no way in, and nothing expands into h, whose e -> f
+        // starts without incoming edges
+        let yaml = r#"
+id: wf-lint
+name: Lint
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+      - { id: b, name: B, type: action, action: NoopSink }
+      - { id: o, name: O, type: action, action: NoopSink }
+      - { id: c, name: C, type: action, action: NoopSink }
+      - { id: d, name: D, type: action, action: NoopSink }
+    edges:
+      - { id: ab, from: a, to: b, fromPort: default, toPort: default }
+      - { id: cd, from: c, to: d, fromPort: default, toPort: default }
+      - { id: dc, from: d, to: c, fromPort: default, toPort: default }
+  - id: h
+    name: H
+    nodes:
+      - { id: e, name: E, type: action, action: NoopSink }
+      - { id: f, name: F, type: action, action: NoopSink }
+    edges:
+      - { id: ef, from: e, to: f, fromPort: default, toPort: default }
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+        let warnings = workflow.lint();
+        assert_eq!(warnings.len(), 5, "{:?}", warnings);
+
+        let about = |id: &str| {
+            warnings
+                .iter()
+                .find(|warning| warning.node == Some(node_by_id(&workflow, id)))
+                .unwrap_or_else(|| panic!("no warning for {}", id))
+        };
+        assert!(about("o").message.contains("not connected"));
+        assert!(about("c").message.contains("cannot be reached"));
+        assert!(about("d").message.contains("cannot be reached"));
+        assert!(about("e").message.contains("cannot be reached"));
+        assert!(about("f").message.contains("cannot be reached"));
+        assert!(warnings.iter().all(|warning| warning.edge.is_none()));
+
+        assert!(Workflow::load_from_str(CHAIN_WORKFLOW)
+            .unwrap()
+            .lint()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_to_definition_round_trips() {
+        let workflow_path = std::env::current_dir()
+            .expect("Failed to get current directory")
+            .join("documents")
+            .join("solar-radiation")
+            .join("solar-potential")
+            .join("workflow.yaml");
+        for workflow in [
+            Workflow::load_from_path(workflow_path).unwrap(),
+            Workflow::load_from_str(INLINE_WORKFLOW).unwrap(),
+            Workflow::load_from_str(CHAIN_WORKFLOW).unwrap(),
+        ] {
+            let rebuilt = Workflow::from_definition(workflow.to_definition()).unwrap();
+            assert_eq!(rebuilt, workflow);
+            assert_eq!(rebuilt.graph.node_count(), workflow.graph.node_count());
+            assert_eq!(rebuilt.graph.edge_count(), workflow.graph.edge_count());
+
+            let reparsed = Workflow::load_from_str(&workflow.to_yaml().unwrap()).unwrap();
+            assert_eq!(reparsed, workflow);
+        }
+    }
+
+    #[test]
+    fn test_relabel_renames_mapped_nodes_only() {
+        let mut workflow = Workflow::load_from_str(TRIANGLE_WORKFLOW).unwrap();
+        let mapping = HashMap::from([
+            ("a".to_string(), "Start".to_string()),
+            ("c".to_string(), "End".to_string()),
+        ]);
+
+        workflow.relabel(&mapping);
+
+        let mut names: Vec<(String, String)> = workflow
+            .graph
+            .node_weights()
+            .map(|node| (node.id.clone(), node.name.clone()))
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                ("a".to_string(), "Start".to_string()),
+                ("b".to_string(), "B".to_string()),
+                ("c".to_string(), "End".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_depths_follow_longest_branch() {
+        // Diamond a -> b -> c -> d and a -> d; d joins a long and a short branch
+        let yaml = r#"
+id: wf-diamond
+name: Diamond
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+      - { id: b, name: B, type: action, action: NoopSink }
+      - { id: c, name: C, type: action, action: NoopSink }
+      - { id: d, name: D, type: action, action: NoopSink }
+    edges:
+      - { id: ab, from: a, to: b, fromPort: default, toPort: default }
+      - { id: bc, from: b, to: c, fromPort: default, toPort: default }
+      - { id: cd, from: c, to: d, fromPort: default, toPort: default }
+      - { id: ad, from: a, to: d, fromPort: default, toPort: default }
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+        let depths = workflow.depths().unwrap();
+        assert_eq!(depths[&node_by_id(&workflow, "a")], 0);
+        assert_eq!(depths[&node_by_id(&workflow, "b")], 1);
+        assert_eq!(depths[&node_by_id(&workflow, "c")], 2);
+        assert_eq!(depths[&node_by_id(&workflow, "d")], 3);
+    }
+
+    #[test]
+    fn test_depths_reject_cycles() {
+        let mut workflow = Workflow::load_from_str(TRIANGLE_WORKFLOW).unwrap();
+        let (a, c) = (node_by_id(&workflow, "a"), node_by_id(&workflow, "c"));
+        workflow.graph.add_edge(c, a, Edge::default());
+        assert!(workflow.depths().is_err());
+    }
+
+    #[test]
+    fn test_insert_node_on_edge_splits_it() {
+        let mut workflow = Workflow::load_from_str(TRIANGLE_WORKFLOW).unwrap();
+        let (a, b) = (node_by_id(&workflow, "a"), node_by_id(&workflow, "b"));
+        let edge_idx = workflow.graph.find_edge(a, b).unwrap();
+        let node = Node {
+            id: "m".to_string(),
+            name: "M".to_string(),
+            ..Default::default()
+        };
+
+        let (m, incoming, outgoing) = workflow.insert_node_on_edge(edge_idx, node).unwrap();
+
+        assert!(workflow.graph.find_edge(a, b).is_none());
+        assert_eq!(workflow.graph.edge_count(), 4);
+        assert_eq!(workflow.graph.edge_endpoints(incoming), Some((a, m)));
+        assert_eq!(workflow.graph.edge_endpoints(outgoing), Some((m, b)));
+        assert_eq!(workflow.graph[incoming].name, "default to M");
+        assert_eq!(workflow.graph[outgoing].name, "default from M");
+        assert_eq!(workflow.successors(m), vec![b]);
+    }
+
+    #[test]
+    fn test_insert_node_on_missing_edge_fails() {
+        let mut workflow = Workflow::load_from_str(TRIANGLE_WORKFLOW).unwrap();
+        let missing = petgraph::graph::EdgeIndex::new(10);
+        assert!(workflow
+            .insert_node_on_edge(missing, Node::default())
+            .is_err());
+        assert_eq!(workflow.graph.node_count(), 3);
+    }
+
+    #[test]
+    fn test_port_graph_connects_ports() {
+        // A filter declaring `kept` and `dropped`, fed by a sink on its default port, with only `kept` wired onwards
+        let yaml = r#"
+id: wf-ports
+name: Ports
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+      - id: r
+        name: R
+        type: action
+        action: FeatureFilter
+        with:
+          conditions:
+            - { expr: "true", outputPort: kept }
+            - { expr: "false", outputPort: dropped }
+      - { id: b, name: B, type: action, action: NoopSink }
+    edges:
+      - { id: ar, from: a, to: r, fromPort: default, toPort: default }
+      - { id: rb, from: r, to: b, fromPort: kept, toPort: input }
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+        let ports = workflow.port_graph();
+        let port = |node_id: &str, port: &str| (node_id.to_string(), port.to_string());
+
+        let nodes: Vec<(String, String)> = ports.node_weights().cloned().collect();
+        assert_eq!(
+            nodes,
+            vec![
+                port("r", "kept"),
+                port("r", "dropped"),
+                port("a", "default"),
+                port("r", "default"),
+                port("b", "input"),
+            ]
+        );
+
+        let mut edges: Vec<((String, String), (String, String))> = ports
+            .edge_indices()
+            .map(|edge_idx| {
+                let (from, to) = ports.edge_endpoints(edge_idx).unwrap();
+                (ports[from].clone(), ports[to].clone())
+            })
+            .collect();
+        edges.sort();
+        assert_eq!(
+            edges,
+            vec![
+                (port("a", "default"), port("r", "default")),
+                (port("r", "kept"), port("b", "input")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_graph_is_reported() {
+        // The second graph's nodes are a string rather than a list
+        let yaml = r#"
+id: wf-malformed
+name: Malformed
+entryGraphId: good
+graphs:
+  - id: good
+    name: Good
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+  - id: bad
+    name: Bad
+    nodes: not-a-list
+"#;
+        let message = Workflow::load_from_str(yaml).unwrap_err().to_string();
+        assert!(message.starts_with("Serde"), "{}", message);
+        assert!(message.contains("graph `bad`"), "{}", message);
+    }
+
+    #[test]
+    fn test_malformed_graph_without_id_is_named_by_position() {
+        let yaml = r#"
+id: wf-malformed
+name: Malformed
+entryGraphId: good
+graphs:
+  - id: good
+    name: Good
+  - name: Nameless
+"#;
+        let message = Workflow::load_from_str(yaml).unwrap_err().to_string();
+        assert!(message.contains("graph #1"), "{}", message);
+    }
+
+    #[test]
+    fn test_bfs_order_goes_level_by_level() {
+        // r -> a, r -> b, a -> c, a -> d, b -> e
+        let yaml = r#"
+id: wf-tree
+name: Tree
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: r, name: R, type: action, action: NoopSink }
+      - { id: a, name: A, type: action, action: NoopSink }
+      - { id: b, name: B, type: action, action: NoopSink }
+      - { id: c, name: C, type: action, action: NoopSink }
+      - { id: d, name: D, type: action, action: NoopSink }
+      - { id: e, name: E, type: action, action: NoopSink }
+    edges:
+      - { id: ra, from: r, to: a, fromPort: default, toPort: default }
+      - { id: rb, from: r, to: b, fromPort: default, toPort: default }
+      - { id: ac, from: a, to: c, fromPort: default, toPort: default }
+      - { id: ad, from: a, to: d, fromPort: default, toPort: default }
+      - { id: be, from: b, to: e, fromPort: default, toPort: default }
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+        let bfs_ids = |start: &str| -> Vec<String> {
+            workflow
+                .bfs_order(node_by_id(&workflow, start))
+                .into_iter()
+                .map(|node_idx| workflow.graph[node_idx].id.clone())
+                .collect()
+        };
+        let order = bfs_ids("r");
+        let level = |id: &str| match id {
+            "r" => 0,
+            "a" | "b" => 1,
+            _ => 2,
+        };
+
+        assert_eq!(order.len(), 6);
+        assert_eq!(order[0], "r");
+        assert!(order
+            .windows(2)
+            .all(|pair| level(&pair[0]) <= level(&pair[1])));
+
+        // Starting further down only reaches that subtree
+        let subtree = bfs_ids("a");
+        assert_eq!(subtree[0], "a");
+        assert_eq!(subtree.len(), 3);
+    }
+
+    #[test]
+    fn test_edge_weight_is_parsed_when_present() {
+        let yaml = r#"
+id: wf-weights
+name: Weights
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+      - { id: b, name: B, type: action, action: NoopSink }
+      - { id: c, name: C, type: action, action: NoopSink }
+    edges:
+      - { id: ab, from: a, to: b, fromPort: default, toPort: default, weight: 2.5 }
+      - { id: bc, from: b, to: c, fromPort: default, toPort: default }
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+        let edge = |from: &str, to: &str| {
+            let edge_idx = workflow
+                .graph
+                .find_edge(node_by_id(&workflow, from), node_by_id(&workflow, to))
+                .unwrap();
+            workflow.graph[edge_idx].clone()
+        };
+
+        assert_eq!(edge("a", "b").weight, Some(2.5));
+        assert_eq!(edge("a", "b").to_string(), "default (2.5)");
+        assert_eq!(edge("b", "c").weight, None);
+        assert_eq!(edge("b", "c").to_string(), "default");
+    }
+
+    #[test]
+    fn test_edge_weight_survives_export() {
+        let mut workflow = Workflow::load_from_str(TRIANGLE_WORKFLOW).unwrap();
+        let edge_idx = workflow.graph.edge_indices().next().unwrap();
+        workflow.graph[edge_idx].weight = Some(4.0);
+
+        let yaml = workflow.to_yaml().unwrap();
+        assert_eq!(yaml.matches("weight:").count(), 1);
+        let reloaded = Workflow::load_from_str(&yaml).unwrap();
+        let weights: Vec<Option<f64>> = reloaded
+            .graph
+            .edge_weights()
+            .map(|edge| edge.weight)
+            .filter(Option::is_some)
+            .collect();
+        assert_eq!(weights, vec![Some(4.0)]);
+    }
+
+    #[test]
+    fn test_remove_edge_between_hit_and_miss() {
+        let mut workflow = Workflow::load_from_str(TRIANGLE_WORKFLOW).unwrap();
+        let (a, b, c) = (
+            node_by_id(&workflow, "a"),
+            node_by_id(&workflow, "b"),
+            node_by_id(&workflow, "c"),
+        );
+
+        assert!(workflow.remove_edge_between(a, b));
+        assert!(workflow.graph.find_edge(a, b).is_none());
+        assert_eq!(workflow.graph.edge_count(), 2);
+
+        // Already gone, and edges only count in their own direction
+        assert!(!workflow.remove_edge_between(a, b));
+        assert!(!workflow.remove_edge_between(c, b));
+        assert_eq!(workflow.graph.edge_count(), 2);
+    }
+
+    #[test]
+    fn test_remove_edge_between_parallel_edges_removes_one() {
+        let mut workflow = Workflow::load_from_str(TRIANGLE_WORKFLOW).unwrap();
+        let (a, b) = (node_by_id(&workflow, "a"), node_by_id(&workflow, "b"));
+        workflow.graph.add_edge(a, b, Edge::default());
+
+        assert!(workflow.remove_edge_between(a, b));
+        assert_eq!(workflow.graph.edges_connecting(a, b).count(), 1);
+        assert_eq!(workflow.graph.edge_count(), 3);
+    }
+
+    #[test]
+    fn test_connected_component_ignores_edge_direction() {
+        // a -> b <- c forms one component, d -> e another
+        let yaml = r#"
+id: wf-components
+name: Components
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+      - { id: b, name: B, type: action, action: NoopSink }
+      - { id: c, name: C, type: action, action: NoopSink }
+      - { id: d, name: D, type: action, action: NoopSink }
+      - { id: e, name: E, type: action, action: NoopSink }
+    edges:
+      - { id: ab, from: a, to: b, fromPort: default, toPort: default }
+      - { id: cb, from: c, to: b, fromPort: default, toPort: default }
+      - { id: de, from: d, to: e, fromPort: default, toPort: default }
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+        let component = |id: &str| {
+            ids(
+                &workflow,
+                workflow.connected_component(node_by_id(&workflow, id)),
+            )
+        };
+
+        assert_eq!(component("a"), vec!["a", "b", "c"]);
+        assert_eq!(component("b"), vec!["a", "b", "c"]);
+        assert_eq!(component("e"), vec!["d", "e"]);
+    }
+
+    #[test]
+    fn test_actions_summary_counts_solar_action_nodes() {
+        let solar = std::env::current_dir()
+            .expect("Failed to get current directory")
+            .join("documents")
+            .join("solar-radiation");
+        // The other fixtures are graphs the solar potential workflow includes
+        for name in ["calculate-cloud-correction", "solar-potential"] {
+            let workflow =
+                Workflow::load_from_path(solar.join(name).join("workflow.yaml")).unwrap();
+            let action_nodes = workflow
+                .graph
+                .node_weights()
+                .filter(|node| matches!(node.kind, NodeKind::Action(_)))
+                .count();
+
+            let summary = workflow.actions_summary();
+            assert_eq!(summary.values().sum::<usize>(), action_nodes, "{name}");
+            assert!(summary.values().all(|&count| count > 0), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_undirected_merges_reciprocal_edges() {
+        // a <-> b counts once undirected, b -> c and the parallel a -> b stay
+        let yaml = r#"
+id: wf-undirected
+name: Undirected
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+      - { id: b, name: B, type: action, action: NoopSink }
+      - { id: c, name: C, type: action, action: NoopSink }
+    edges:
+      - { id: ab, from: a, to: b, fromPort: default, toPort: default }
+      - { id: ba, from: b, to: a, fromPort: default, toPort: default }
+      - { id: ab2, from: a, to: b, fromPort: other, toPort: default }
+      - { id: bc, from: b, to: c, fromPort: default, toPort: default }
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+        let undirected = workflow.undirected(true);
+
+        assert_eq!(undirected.node_count(), workflow.graph.node_count());
+        assert_eq!(workflow.graph.edge_count(), 4);
+        assert_eq!(undirected.edge_count(), 3);
+        assert_eq!(workflow.undirected(false).edge_count(), 4);
+        for node_idx in workflow.graph.node_indices() {
+            assert_eq!(undirected[node_idx], workflow.graph[node_idx]);
+        }
+        let (a, c) = (node_by_id(&workflow, "a"), node_by_id(&workflow, "c"));
+        assert!(undirected
+            .find_edge(c, node_by_id(&workflow, "b"))
+            .is_some());
+        assert!(undirected.find_edge(a, c).is_none());
+    }
+
+    #[test]
+    fn test_validate_unique_names_groups_shared_names() {
+        let yaml = r#"
+id: wf-names
+name: Names
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: r1, name: Reader, type: action, action: CsvReader }
+      - { id: s, name: Sink, type: action, action: NoopSink }
+      - { id: r2, name: Reader, type: action, action: CsvReader }
+    edges:
+      - { id: r1s, from: r1, to: s, fromPort: default, toPort: default }
+      - { id: r2s, from: r2, to: s, fromPort: default, toPort: default }
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+        let warnings = workflow.validate_unique_names();
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert_eq!(warnings[0].message, "nodes r1, r2 share the name Reader");
+        assert_eq!(warnings[0].node, Some(node_by_id(&workflow, "r1")));
+
+        // Reported by the lint as well, but only as a warning: the workflow loads
+        assert_eq!(workflow.lint(), warnings);
+    }
+
+    #[test]
+    fn test_removing_last_entry_node_repoints_entry() {
+        // The entry graph g holds only a; h still has nodes
+        let yaml = r#"
+id: wf-entry
+name: Entry
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+  - id: h
+    name: H
+    nodes:
+      - { id: b, name: B, type: action, action: NoopSink }
+      - { id: c, name: C, type: action, action: NoopSink }
+"#;
+        let mut workflow = Workflow::load_from_str(yaml).unwrap();
+        workflow.remove_node(node_by_id(&workflow, "b"));
+        assert_eq!(workflow.entry_graph_ids, vec!["g"]);
+        assert!(workflow.edit_warnings.is_empty());
+
+        workflow.remove_node(node_by_id(&workflow, "a"));
+        assert_eq!(workflow.entry_graph_ids, vec!["h"]);
+        assert_eq!(workflow.primary_entry(), Some("h"));
+        assert_eq!(workflow.edit_warnings.len(), 1);
+        assert!(workflow.edit_warnings[0]
+            .message
+            .contains("now starts from graph h"));
+
+        // Nothing left to start from
+        workflow.remove_node(node_by_id(&workflow, "c"));
+        assert!(workflow.entry_graph_ids.is_empty());
+        assert_eq!(workflow.edit_warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_removing_last_entry_node_drops_entry_among_several() {
+        let mut workflow = Workflow {
+            entry_graph_ids: vec!["g".to_string(), "h".to_string()],
+            ..Default::default()
+        };
+        let a = workflow.graph.add_node(Node {
+            subgraph: "g".to_string(),
+            ..Default::default()
+        });
+        workflow.graph.add_node(Node {
+            subgraph: "h".to_string(),
+            ..Default::default()
+        });
+
+        workflow.remove_node(a);
+        assert_eq!(workflow.entry_graph_ids, vec!["h"]);
+        assert_eq!(workflow.edit_warnings.len(), 1);
+    }
+
+    fn split_workflow_dir() -> PathBuf {
+        std::env::current_dir()
+            .expect("Failed to get current directory")
+            .join("documents")
+            .join("split-workflow")
+    }
+
+    #[test]
+    fn test_load_from_dir_combines_graph_files() {
+        let workflow = Workflow::load_from_dir(split_workflow_dir()).unwrap();
+
+        assert_eq!(workflow.name, "SplitWorkflow");
+        assert_eq!(workflow.primary_entry(), Some("main"));
+        assert_eq!(workflow.graph_name("reader"), Some("Reader"));
+        assert_eq!(workflow.graph.node_count(), 3);
+        assert_eq!(workflow.graph.edge_count(), 1);
+        assert_eq!(
+            workflow.graph[node_by_id(&workflow, "csv")].subgraph,
+            "reader"
+        );
+        assert_eq!(
+            ids(
+                &workflow,
+                workflow.successors(node_by_id(&workflow, "read"))
+            ),
+            vec!["sink"]
+        );
+    }
+
+    #[test]
+    fn test_load_from_dir_requires_manifest() {
+        let err = Workflow::load_from_dir(split_workflow_dir().join("missing")).unwrap_err();
+        assert!(
+            err.to_string().starts_with("Input(") && err.to_string().contains("manifest"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_load_from_dir_include_depth_limit() {
+        // workflow.yaml -> graph.yml -> node.yml is two includes deep, the graph file counting as the first
+        let dir = temp_workflow_dir(&[
+            (
+                "workflow.yaml",
+                "id: wf\nname: Split\nentryGraphId: g\ngraphs:\n  - graph.yml\n",
+            ),
+            (
+                "graph.yml",
+                "id: g\nname: G\nnodes:\n  - !include node.yml\n",
+            ),
+            (
+                "node.yml",
+                "id: only\nname: Only\ntype: action\naction: NoopSink\n",
+            ),
+        ]);
+
+        let shallow = LoadOptions {
+            max_include_depth: 1,
+            ..Default::default()
+        };
+        let too_deep = Workflow::load_from_dir_with_options(dir.clone(), &shallow);
+        let default_limit = Workflow::load_from_dir(dir.clone());
+        std::fs::remove_dir_all(&dir).ok();
+
+        let err = too_deep.unwrap_err();
+        assert!(
+            err.to_string().contains("maximum include depth of 1"),
+            "unexpected error: {}",
+            err
+        );
+        assert_eq!(default_limit.unwrap().graph.node_count(), 1);
+    }
+
+    #[test]
+    fn test_load_from_dir_rejects_paths_outside_dir() {
+        let dir = std::env::temp_dir().join(format!("split-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("workflow.yaml"),
+            "id: wf\nname: Wf\nentryGraphId: g\ngraphs:\n  - ../g.yml\n",
+        )
+        .unwrap();
+        let result = Workflow::load_from_dir(dir.clone());
+        std::fs::remove_dir_all(&dir).ok();
+
+        let err = result.unwrap_err();
+        assert!(
+            err.to_string().contains("is not a file name"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_prune_unreachable_removes_dead_branches() {
+        // a -> b -> sub runs from the entry and sub starts h; nothing leads into the c <-> d cycle
+        let yaml = r#"
+id: wf-prune
+name: Prune
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+      - { id: b, name: B, type: action, action: NoopSink }
+      - { id: c, name: C, type: action, action: NoopSink }
+      - { id: d, name: D, type: action, action: NoopSink }
+      - { id: sub, name: Sub, type: subGraph, subGraphId: h }
+    edges:
+      - { id: ab, from: a, to: b, fromPort: default, toPort: default }
+      - { id: bsub, from: b, to: sub, fromPort: default, toPort: default }
+      - { id: cd, from: c, to: d, fromPort: default, toPort: default }
+      - { id: dc, from: d, to: c, fromPort: default, toPort: default }
+  - id: h
+    name: H
+    nodes:
+      - { id: x, name: X, type: action, action: NoopSink }
+      - { id: y, name: Y, type: action, action: NoopSink }
+    edges:
+      - { id: xy, from: x, to: y, fromPort: default, toPort: default }
+"#;
+        let mut workflow = Workflow::load_from_str(yaml).unwrap();
+        assert_eq!(ids(&workflow, workflow.unreachable_nodes()), vec!["c", "d"]);
+
+        let removed = workflow.prune_unreachable();
+        assert_eq!(removed, vec!["c", "d"]);
+        assert_eq!(workflow.graph.node_count(), 5);
+        for id in ["a", "b", "sub", "x", "y"] {
+            node_by_id(&workflow, id);
+        }
+        // Edges follow their nodes to the indices they moved to
+        assert_eq!(
+            ids(&workflow, workflow.successors(node_by_id(&workflow, "x"))),
+            vec!["y"]
+        );
+        assert_eq!(
+            ids(&workflow, workflow.successors(node_by_id(&workflow, "b"))),
+            vec!["sub"]
+        );
+        assert!(workflow.prune_unreachable().is_empty());
+    }
+
+    #[test]
+    fn test_describe_summarizes_fixture() {
+        let workflow_path = std::env::current_dir()
+            .expect("Failed to get current directory")
+            .join("documents")
+            .join("solar-radiation")
+            .join("calculate-cloud-correction")
+            .join("workflow.yaml");
+        let workflow = Workflow::load_from_path(workflow_path).unwrap();
+        let description = workflow.describe();
+
+        assert!(description.starts_with("Workflow: CalculateCloudCorrectionFactor\n"));
+        assert!(description.contains(&format!("\nNodes: {}\n", workflow.graph.node_count())));
+        assert!(description.contains(&format!("\nEdges: {}\n", workflow.graph.edge_count())));
+        assert!(description.contains("\nAcyclic: yes\n"));
+        for (action, count) in workflow.actions_summary() {
+            assert!(description.contains(&format!("\n  {}: {}\n", action, count)));
+        }
+    }
+
+    #[test]
+    fn test_to_adjacency_list_matches_edges() {
+        let yaml = r#"
+id: wf-adjacency
+name: Adjacency
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+      - { id: c, name: C, type: action, action: NoopSink }
+      - { id: b, name: B, type: action, action: NoopSink }
+      - { id: lone, name: Lone, type: action, action: NoopSink }
+    edges:
+      - { id: ac, from: a, to: c, fromPort: default, toPort: default }
+      - { id: ab, from: a, to: b, fromPort: default, toPort: default }
+      - { id: bc, from: b, to: c, fromPort: default, toPort: default }
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+        let adjacency = workflow.to_adjacency_list();
+
+        let expected: Vec<(&str, Vec<&str>)> = vec![
+            ("a", vec!["b", "c"]),
+            ("b", vec!["c"]),
+            ("c", vec![]),
+            ("lone", vec![]),
+        ];
+        let actual: Vec<(&str, Vec<&str>)> = adjacency
+            .iter()
+            .map(|(id, successors)| (id.as_str(), successors.iter().map(String::as_str).collect()))
+            .collect();
+        assert_eq!(actual, expected);
+
+        let listed: usize = adjacency.values().map(Vec::len).sum();
+        assert_eq!(listed, workflow.graph.edge_count());
+    }
+
+    #[test]
+    fn test_edge_with_block_is_retained() {
+        let yaml = r#"
+id: wf-edge-with
+name: Edge With
+entryGraphId: g
+graphs:
+  - id: g
+    name: G
+    nodes:
+      - { id: a, name: A, type: action, action: NoopSink }
+      - { id: b, name: B, type: action, action: NoopSink }
+    edges:
+      - id: ab
+        from: a
+        to: b
+        fromPort: default
+        toPort: default
+        with:
+          mapping: geometry
+          batchSize: 50
+      - { id: ba, from: b, to: a, fromPort: default, toPort: default }
+"#;
+        let workflow = Workflow::load_from_str(yaml).unwrap();
+        let edge_by_id = |id: &str| {
+            workflow
+                .graph
+                .edge_weights()
+                .find(|edge| edge.id == id)
+                .unwrap()
+                .clone()
+        };
+
+        let with = edge_by_id("ab").with.expect("with block parsed");
+        assert_eq!(with["mapping"].as_str(), Some("geometry"));
+        assert_eq!(with["batchSize"].as_u64(), Some(50));
+        assert!(edge_by_id("ba").with.is_none());
+        assert!(Edge::default().with.is_none());
+
+        // Written back out unchanged
+        let reloaded = Workflow::load_from_str(&workflow.to_yaml().unwrap()).unwrap();
+        let edge = reloaded
+            .graph
+            .edge_weights()
+            .find(|edge| edge.id == "ab")
+            .unwrap();
+        assert_eq!(edge.with.as_ref(), Some(&with));
+    }
+
+    #[test]
+    fn test_density() {
+        let mut workflow = Workflow::default();
+        assert_eq!(workflow.density(), 0.0);
+
+        let a = workflow.graph.add_node(Node {
+            id: "a".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(workflow.density(), 0.0);
+
+        // Every ordered pair of a triad connected
+        let b = workflow.graph.add_node(Node {
+            id: "b".to_string(),
+            ..Default::default()
+        });
+        let c = workflow.graph.add_node(Node {
+            id: "c".to_string(),
+            ..Default::default()
+        });
+        for (source, target) in [(a, b), (a, c), (b, a), (b, c), (c, a), (c, b)] {
+            workflow.graph.add_edge(source, target, Edge::default());
+        }
+        assert_eq!(workflow.density(), 1.0);
+
+        workflow
+            .graph
+            .remove_edge(workflow.graph.find_edge(c, b).unwrap());
+        workflow
+            .graph
+            .remove_edge(workflow.graph.find_edge(c, a).unwrap());
+        workflow
+            .graph
+            .remove_edge(workflow.graph.find_edge(b, a).unwrap());
+        assert_eq!(workflow.density(), 0.5);
+    }
+
+    #[test]
+    fn test_is_executable_passes_on_chain() {
+        let workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        assert!(workflow.is_executable().is_ok());
+    }
+
+    #[test]
+    fn test_is_executable_rejects_cycle() {
+        let mut workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        let (d, b) = (node_by_id(&workflow, "d"), node_by_id(&workflow, "b"));
+        workflow.graph.add_edge(d, b, Edge::default());
+        assert_eq!(
+            workflow.is_executable().unwrap_err().to_string(),
+            "Input(\"workflow graph contains a cycle\")"
+        );
+    }
+
+    #[test]
+    fn test_is_executable_rejects_missing_entry() {
+        let mut workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        workflow.entry_graph_ids = vec!["missing".to_string()];
+        assert_eq!(
+            workflow.is_executable().unwrap_err().to_string(),
+            "Input(\"entry graph missing does not exist\")"
+        );
+
+        workflow.entry_graph_ids.clear();
+        assert_eq!(
+            workflow.is_executable().unwrap_err().to_string(),
+            "Input(\"workflow has no entryGraphId\")"
+        );
+    }
+
+    #[test]
+    fn test_is_executable_rejects_unreachable_nodes() {
+        let mut workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        for id in ["stray", "lost"] {
+            workflow.graph.add_node(Node {
+                id: id.to_string(),
+                subgraph: "unused".to_string(),
+                ..Default::default()
+            });
+        }
+        assert_eq!(
+            workflow.is_executable().unwrap_err().to_string(),
+            "Input(\"nodes stray, lost cannot be reached from the entry graph\")"
+        );
+    }
+
+    #[test]
+    fn test_load_with_progress_returns_resolved_yaml() {
+        let workflow_path =
+            PathBuf::from("documents/solar-radiation/solar-potential/workflow.yaml");
+        assert!(std::fs::read_to_string(&workflow_path)
+            .unwrap()
+            .contains("!include"));
+
+        let (loaded, resolved) = Workflow::load_with_progress(workflow_path, |_| {}).unwrap();
+        assert!(!resolved.contains("!include"), "{}", resolved);
+
+        // The resolved text is the workflow that loading the file gives
+        let from_text = Workflow::load_from_str(&resolved).unwrap();
+        assert_eq!(from_text.graph.node_count(), loaded.graph.node_count());
+        assert_eq!(from_text.graph.edge_count(), loaded.graph.edge_count());
+    }
+
+    #[test]
+    fn test_path_count_on_chain() {
+        let workflow = Workflow::load_from_str(CHAIN_WORKFLOW).unwrap();
+        let (a, c, d) = (
+            node_by_id(&workflow, "a"),
+            node_by_id(&workflow, "c"),
+            node_by_id(&workflow, "d"),
+        );
+        assert_eq!(workflow.path_count(a, d).unwrap(), 1);
+        assert_eq!(workflow.path_count(c, c).unwrap(), 1);
+        // Against the direction of the edges there is no path
+        assert_eq!(workflow.path_count(d, a).unwrap(), 0);
+    }
+
+    /// `diamonds` diamonds in a row, each splitting into two branches that join again. Returns the first and the last
+    /// node.
+    fn diamond_chain(
+        diamonds: usize,
+    ) -> (
+        Workflow,
+        petgraph::graph::NodeIndex,
+        petgraph::graph::NodeIndex,
+    ) {
+        let mut workflow = Workflow::default();
+        let add = |workflow: &mut Workflow, id: String| {
+            workflow.graph.add_node(Node {
+                id,
+                ..Default::default()
+            })
+        };
+        let start = add(&mut workflow, "join-0".to_string());
+        let mut join = start;
+        for i in 1..=diamonds {
+            let left = add(&mut workflow, format!("left-{}", i));
+            let right = add(&mut workflow, format!("right-{}", i));
+            let next = add(&mut workflow, format!("join-{}", i));
+            for (source, target) in [(join, left), (join, right), (left, next), (right, next)] {
+                workflow.graph.add_edge(source, target, Edge::default());
+            }
+            join = next;
+        }
+        (workflow, start, join)
+    }
+
+    #[test]
+    fn test_path_count_on_diamond() {
+        let (mut workflow, start, end) = diamond_chain(1);
+        assert_eq!(workflow.path_count(start, end).unwrap(), 2);
+
+        // A parallel edge doesn't make another path
+        let left = node_by_id(&workflow, "left-1");
+        workflow.graph.add_edge(start, left, Edge::default());
+        assert_eq!(workflow.path_count(start, end).unwrap(), 2);
+
+        let (workflow, start, end) = diamond_chain(3);
+        assert_eq!(workflow.path_count(start, end).unwrap(), 8);
+    }
+
+    #[test]
+    fn test_path_count_saturates() {
+        let (workflow, start, end) = diamond_chain(70);
+        assert_eq!(workflow.path_count(start, end).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn test_path_count_rejects_cycles_and_missing_nodes() {
+        let (mut workflow, start, end) = diamond_chain(1);
+        assert_eq!(
+            workflow
+                .path_count(start, petgraph::graph::NodeIndex::new(99))
+                .unwrap_err()
+                .to_string(),
+            "Input(\"node 99 does not exist\")"
+        );
+
+        workflow.graph.add_edge(end, start, Edge::default());
+        assert_eq!(
+            workflow.path_count(start, end).unwrap_err().to_string(),
+            "Input(\"workflow graph contains a cycle\")"
+        );
+    }
 }
@@ -0,0 +1,239 @@
+use super::{Node, NodeAction, NodeKind, Workflow};
+use crate::{Error, Result};
+use petgraph::graph::NodeIndex;
+use std::collections::{BTreeSet, HashSet};
+
+/// Keys each action needs in its `with` block. A group lists alternatives and is satisfied by any one of its keys.
+fn required_params(action: &NodeAction) -> &'static [&'static [&'static str]] {
+    match action {
+        NodeAction::AreaCalculator => &[&["areaType"]],
+        NodeAction::AttributeAggregator => &[&["aggregateAttributes"], &["calculations"]],
+        NodeAction::AttributeManager => &[&["operations"]],
+        NodeAction::AttributeMapper => &[&["mappers"]],
+        NodeAction::CsvReader => &[&["format"], &["inline", "dataset"]],
+        NodeAction::FeatureCityGmlReader => &[&["format"], &["dataset"]],
+        NodeAction::FeatureCreator => &[&["creator"]],
+        NodeAction::FeatureFilter => &[&["conditions"]],
+        NodeAction::FeatureMerger => &[&["requestorAttribute"], &["supplierAttribute"]],
+        NodeAction::GeometryCoercer => &[&["targetType"]],
+        NodeAction::GeometryExtractor => &[&["outputAttribute"]],
+        NodeAction::HorizontalReprojector => &[&["targetEpsgCode"]],
+        NodeAction::OutputRouter => &[&["routingPort"]],
+        NodeAction::StatisticsCalculator => &[&["calculations"]],
+        NodeAction::GeometryPartExtractor
+        | NodeAction::NoopSink
+        | NodeAction::PolygonNormalExtractor
+        | NodeAction::Other(_) => &[],
+    }
+}
+
+/// Checks that an action node carries every `with` key its action requires.
+pub fn validate_node_params(node: &Node) -> Result<()> {
+    let NodeKind::Action(action) = &node.kind else {
+        return Ok(());
+    };
+
+    let missing: Vec<String> = required_params(action)
+        .iter()
+        .filter(|group| {
+            !group.iter().any(|key| {
+                node.with
+                    .as_ref()
+                    .is_some_and(|with| with.contains_key(*key))
+            })
+        })
+        .map(|group| group.join(" or "))
+        .collect();
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Input(format!(
+            "node {} ({}) is missing required `with` keys: {}",
+            node.id,
+            action,
+            missing.join(", ")
+        )))
+    }
+}
+
+impl Workflow {
+    /// Checks that the graph has no directed cycle.
+    pub fn check_acyclic(&self) -> Result<()> {
+        if petgraph::algo::is_cyclic_directed(&self.graph) {
+            Err(Error::Input("workflow graph contains a cycle".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks that at least one entry graph is listed and that every listed id names a graph that has nodes.
+    pub fn check_entry_graph(&self) -> Result<()> {
+        if self.entry_graph_ids.is_empty() {
+            return Err(Error::Input("workflow has no entryGraphId".to_string()));
+        }
+        let missing: Vec<&str> = self
+            .entry_graph_ids
+            .iter()
+            .filter(|entry| {
+                !self
+                    .graph
+                    .node_weights()
+                    .any(|node| &node.subgraph == *entry)
+            })
+            .map(String::as_str)
+            .collect();
+        match missing.as_slice() {
+            [] => Ok(()),
+            [entry] => Err(Error::Input(format!(
+                "entry graph {} does not exist",
+                entry
+            ))),
+            entries => Err(Error::Input(format!(
+                "entry graphs {} do not exist",
+                entries.join(", ")
+            ))),
+        }
+    }
+
+    /// Checks that node ids and edge ids are unique.
+    pub fn check_duplicate_ids(&self) -> Result<()> {
+        let mut seen = HashSet::new();
+        let mut duplicates = BTreeSet::new();
+        let ids = self
+            .graph
+            .node_weights()
+            .map(|node| &node.id)
+            .chain(self.graph.edge_weights().map(|edge| &edge.id));
+        for id in ids {
+            if !seen.insert(id) {
+                duplicates.insert(id.as_str());
+            }
+        }
+
+        if duplicates.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Input(format!(
+                "duplicate ids: {}",
+                duplicates.into_iter().collect::<Vec<_>>().join(", ")
+            )))
+        }
+    }
+
+    /// Whether the node has no edge although its graph has other nodes. A graph holding a single node can't connect
+    /// it to anything, so that node isn't an orphan.
+    pub fn is_orphan(&self, node_idx: NodeIndex) -> bool {
+        self.graph.neighbors_undirected(node_idx).next().is_none()
+            && self
+                .graph
+                .node_weights()
+                .filter(|node| node.subgraph == self.graph[node_idx].subgraph)
+                .count()
+                > 1
+    }
+
+    /// Checks that no node is an orphan, see [`Workflow::is_orphan`].
+    pub fn check_orphans(&self) -> Result<()> {
+        let orphans: Vec<&str> = self
+            .graph
+            .node_indices()
+            .filter(|&node_idx| self.is_orphan(node_idx))
+            .map(|node_idx| self.graph[node_idx].id.as_str())
+            .collect();
+
+        if orphans.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Input(format!(
+                "orphan nodes: {}",
+                orphans.join(", ")
+            )))
+        }
+    }
+
+    /// Checks that every edge names both ports and leaves from a port its source node declares.
+    pub fn check_ports(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
+        for edge_idx in self.graph.edge_indices() {
+            let edge = &self.graph[edge_idx];
+            if edge.from_port.is_empty() || edge.to_port.is_empty() {
+                problems.push(format!("edge {} has an empty port", edge.id));
+                continue;
+            }
+
+            let (source, _) = self.graph.edge_endpoints(edge_idx).unwrap();
+            if let Some(ports) = self.graph[source].routing_ports() {
+                if !ports.contains(&edge.from_port) {
+                    problems.push(format!(
+                        "edge {} leaves from undeclared port `{}` of node {}",
+                        edge.id, edge.from_port, self.graph[source].id
+                    ));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Input(problems.join("\n")))
+        }
+    }
+
+    /// Runs every workflow check and returns one message per problem found, in check order. Empty when the workflow
+    /// passes them all.
+    pub fn validation_problems(&self) -> Vec<String> {
+        let mut results = vec![
+            self.check_acyclic(),
+            self.check_entry_graph(),
+            self.check_duplicate_ids(),
+            self.check_orphans(),
+            self.check_ports(),
+        ];
+        results.extend(self.graph.node_weights().map(validate_node_params));
+
+        results
+            .into_iter()
+            .filter_map(|result| result.err())
+            .flat_map(|err| match err {
+                Error::Input(msg) => msg.lines().map(str::to_string).collect(),
+                other => vec![other.to_string()],
+            })
+            .collect()
+    }
+
+    /// Runs every workflow check and reports all failures together, one per line, see
+    /// [`Workflow::validation_problems`].
+    pub fn validate(&self) -> Result<()> {
+        let problems = self.validation_problems();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Input(problems.join("\n")))
+        }
+    }
+
+    /// Checks that the workflow can run as it stands: it has no cycle, its entry graphs exist, and every node can be
+    /// reached from them, see [`Workflow::unreachable_nodes`]. Stops at the first check that fails.
+    pub fn is_executable(&self) -> Result<()> {
+        self.check_acyclic()?;
+        self.check_entry_graph()?;
+        let unreachable: Vec<&str> = self
+            .unreachable_nodes()
+            .into_iter()
+            .map(|node_idx| self.graph[node_idx].id.as_str())
+            .collect();
+        match unreachable.as_slice() {
+            [] => Ok(()),
+            [node] => Err(Error::Input(format!(
+                "node {} cannot be reached from the entry graph",
+                node
+            ))),
+            nodes => Err(Error::Input(format!(
+                "nodes {} cannot be reached from the entry graph",
+                nodes.join(", ")
+            ))),
+        }
+    }
+}
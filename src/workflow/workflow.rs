@@ -1,22 +1,525 @@
-use super::{Edge, Node};
+use super::definition::{ACTION_NODE_TYPE, SUB_GRAPH_NODE_TYPE};
+use super::{
+    Edge, EdgeKind, ExecutionState, GraphDefinition, Node, NodeKind, Warning, WorkflowDefinition,
+};
 use crate::{Error, Result};
-use petgraph::graph::DiGraph;
+use once_cell::sync::Lazy;
+use petgraph::graph::{DiGraph, NodeIndex};
 use regex::Regex;
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+/// Matches a graph entry such as `  - !include ../other/workflow.yml`, capturing the indentation, the spacing after
+/// the dash and the path.
+static INCLUDE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\s*)-(\s+)!include\s+(\S.*?)\s*$").expect("valid include regex"));
+
+/// Emits a debug event with the `workflow::parse` target when the `parse-tracing` feature is on. Without the feature
+/// the call and its arguments are compiled out.
+macro_rules! parse_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "parse-tracing")]
+        tracing::debug!(target: "workflow::parse", $($arg)*);
+    };
+}
+
+/// Name of the manifest in a directory loaded by [`Workflow::load_from_dir`].
+const MANIFEST_FILE_NAME: &str = "workflow.yaml";
+
+/// How deep `!include` entries may nest unless [`LoadOptions`] says otherwise.
+pub const DEFAULT_MAX_INCLUDE_DEPTH: usize = 32;
+
+/// Settings for [`Workflow::load_from_path_with_options`].
 #[derive(Debug, Clone)]
+pub struct LoadOptions {
+    /// How many `!include` levels may nest below the loaded file before loading fails.
+    pub max_include_depth: usize,
+    /// Loading fails as soon as the workflow has more nodes than this. `None` means no limit.
+    pub max_nodes: Option<usize>,
+    /// Loading fails as soon as the workflow has more edges than this. `None` means no limit.
+    pub max_edges: Option<usize>,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            max_include_depth: DEFAULT_MAX_INCLUDE_DEPTH,
+            max_nodes: None,
+            max_edges: None,
+        }
+    }
+}
+
+/// Where [`Workflow::load_timed`] spent its time.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoadTimings {
+    /// Reading the workflow file and splicing in its `!include` entries.
+    pub include_resolution: Duration,
+    /// Deserializing the resolved YAML into a [`WorkflowDefinition`].
+    pub deserialization: Duration,
+    /// Building the graph from the definition.
+    pub graph_construction: Duration,
+}
+
+impl LoadTimings {
+    /// The three phases together.
+    pub fn total(&self) -> Duration {
+        self.include_resolution + self.deserialization + self.graph_construction
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Workflow {
     pub id: String,
     pub name: String,
-    pub entry_graph_id: Option<String>,
+    /// Graphs the workflow starts from. Most workflows have exactly one.
+    pub entry_graph_ids: Vec<String>,
     pub graph: DiGraph<Node, Edge>,
+    /// Names of the graphs defined in the workflow, keyed by graph id.
+    pub graph_names: HashMap<String, String>,
+    /// Run state of the nodes, see [`ExecutionState`]. Not part of the workflow's definition, so it is ignored when
+    /// comparing workflows.
+    pub node_states: HashMap<NodeIndex, ExecutionState>,
+    /// Issues raised by edits, such as an entry graph being replaced once its last node is removed. Not part of the
+    /// workflow's definition either.
+    pub edit_warnings: Vec<Warning>,
 }
 
 impl Workflow {
+    /// The first entry graph, for code that only deals with a single entry point.
+    pub fn primary_entry(&self) -> Option<&str> {
+        self.entry_graph_ids.first().map(String::as_str)
+    }
+
+    /// Loads a workflow from a YAML file. `!include` entries are resolved relative to the file's directory.
     pub fn load_from_path(path: PathBuf) -> Result<Self> {
-        todo!()
+        Self::load_from_path_with_options(path, &LoadOptions::default())
+    }
+
+    /// Like [`Workflow::load_from_path`], with the limits in `options` applied.
+    pub fn load_from_path_with_options(path: PathBuf, options: &LoadOptions) -> Result<Self> {
+        Self::load_with_timings(path, options, &mut |_| {}).map(|(workflow, _, _)| workflow)
+    }
+
+    /// Like [`Workflow::load_from_path`], also reporting how long each loading phase took.
+    pub fn load_timed(path: PathBuf) -> Result<(Self, LoadTimings)> {
+        Self::load_with_timings(path, &LoadOptions::default(), &mut |_| {})
+            .map(|(workflow, timings, _)| (workflow, timings))
+    }
+
+    /// Like [`Workflow::load_from_path`], calling `on_file` with each file as it is read: the workflow file first,
+    /// then its `!include` entries in the order they are resolved. Also returns the text that was parsed: the workflow
+    /// file with its `!include` entries spliced in.
+    pub fn load_with_progress(
+        path: PathBuf,
+        mut on_file: impl FnMut(&Path),
+    ) -> Result<(Self, String)> {
+        Self::load_with_timings(path, &LoadOptions::default(), &mut on_file)
+            .map(|(workflow, _, resolved)| (workflow, resolved))
+    }
+
+    /// Loads a workflow split over the directory `dir`: a `workflow.yaml` manifest whose `graphs` entries may name
+    /// sibling files holding one graph each, e.g. `- main.yml`. Graphs written out in the manifest and `!include`
+    /// entries, in the manifest or the graph files, work as in [`Workflow::load_from_path`].
+    pub fn load_from_dir(dir: PathBuf) -> Result<Self> {
+        Self::load_from_dir_with_options(dir, &LoadOptions::default())
+    }
+
+    /// Like [`Workflow::load_from_dir`], with the limits in `options` applied. A graph file counts as one include
+    /// below the manifest, so its own `!include` entries are one level deeper.
+    pub fn load_from_dir_with_options(dir: PathBuf, options: &LoadOptions) -> Result<Self> {
+        let manifest_path = dir.join(MANIFEST_FILE_NAME);
+        if !manifest_path.is_file() {
+            return Err(Error::Input(format!(
+                "{} has no {} manifest",
+                dir.display(),
+                MANIFEST_FILE_NAME
+            )));
+        }
+        let content = fs::read_to_string(&manifest_path)?;
+        let mut stack = vec![fs::canonicalize(&manifest_path)?];
+        let resolved = resolve_includes(&content, &dir, &mut stack, 0, options, &mut |_| {})?;
+        let mut definition: WorkflowDefinition = serde_yaml::from_str(&resolved)?;
+        for graph in &mut definition.graphs {
+            if let serde_yaml::Value::String(file_name) = graph {
+                *graph = read_graph_file(&dir, file_name, &mut stack, 0, options)?;
+            }
+        }
+        Self::from_definition_with_options(definition, options)
+    }
+
+    fn load_with_timings(
+        path: PathBuf,
+        options: &LoadOptions,
+        on_file: &mut dyn FnMut(&Path),
+    ) -> Result<(Self, LoadTimings, String)> {
+        let started = Instant::now();
+        on_file(&path);
+        let content = fs::read_to_string(&path)?;
+        parse_event!(path = %path.display(), bytes = content.len(), "read workflow file");
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let mut stack = vec![fs::canonicalize(&path)?];
+        let resolved = resolve_includes(&content, base_dir, &mut stack, 0, options, on_file)?;
+        let include_resolution = started.elapsed();
+
+        let started = Instant::now();
+        let definition: WorkflowDefinition = serde_yaml::from_str(&resolved)?;
+        let deserialization = started.elapsed();
+
+        let started = Instant::now();
+        let workflow = Self::from_definition_with_options(definition, options)?;
+        let timings = LoadTimings {
+            include_resolution,
+            deserialization,
+            graph_construction: started.elapsed(),
+        };
+        parse_event!(
+            path = %path.display(),
+            nodes = workflow.graph.node_count(),
+            edges = workflow.graph.edge_count(),
+            elapsed_us = timings.total().as_micros() as u64,
+            "loaded workflow"
+        );
+        Ok((workflow, timings, resolved))
+    }
+
+    /// Loads a workflow from YAML text, resolving `!include` entries relative to `base_dir`.
+    pub fn load_from_str_with_base(yaml: &str, base_dir: &Path) -> Result<Self> {
+        let resolved = resolve_includes(
+            yaml,
+            base_dir,
+            &mut Vec::new(),
+            0,
+            &LoadOptions::default(),
+            &mut |_| {},
+        )?;
+        Self::load_from_str(&resolved)
+    }
+
+    /// Loads a workflow from YAML text. There is no directory to resolve `!include` entries against, so they are
+    /// rejected; use [`Workflow::load_from_str_with_base`] for those.
+    pub fn load_from_str(yaml: &str) -> Result<Self> {
+        if let Some(line) = yaml.lines().find(|line| INCLUDE_RE.is_match(line)) {
+            return Err(Error::Input(format!(
+                "cannot resolve `{}` without a base directory",
+                line.trim()
+            )));
+        }
+
+        let definition: WorkflowDefinition = serde_yaml::from_str(yaml)?;
+        Self::from_definition(definition)
+    }
+
+    /// Builds the workflow graph from an already-parsed definition. Nodes of every graph go into one `DiGraph`,
+    /// tagged with the id of the graph they came from. Fails if any graph doesn't match [`GraphDefinition`].
+    pub fn from_definition(definition: WorkflowDefinition) -> Result<Self> {
+        Self::from_definition_with_options(definition, &LoadOptions::default())
+    }
+
+    /// Like [`Workflow::from_definition`], failing as soon as the node or edge count passes the limits in `options`.
+    pub fn from_definition_with_options(
+        definition: WorkflowDefinition,
+        options: &LoadOptions,
+    ) -> Result<Self> {
+        let mut graph = DiGraph::<Node, Edge>::new();
+        let mut node_indices = HashMap::new();
+        let mut edge_definitions = Vec::new();
+        let mut graph_names = HashMap::new();
+
+        for (position, graph_value) in definition.graphs.into_iter().enumerate() {
+            let graph_def = parse_graph(graph_value, position)?;
+            graph_names.insert(graph_def.id.clone(), graph_def.name.clone());
+            for node_def in graph_def.nodes {
+                node_def.validate_type()?;
+                let custom_type = match node_def.node_type.as_str() {
+                    ACTION_NODE_TYPE | SUB_GRAPH_NODE_TYPE => None,
+                    other => Some(other.to_string()),
+                };
+                let kind = match node_def.node_type.as_str() {
+                    SUB_GRAPH_NODE_TYPE => {
+                        NodeKind::SubGraph(node_def.sub_graph_id.unwrap_or_default())
+                    }
+                    _ => NodeKind::Action(
+                        node_def
+                            .action
+                            .as_deref()
+                            .unwrap_or_default()
+                            .parse()
+                            .unwrap_or_default(),
+                    ),
+                };
+                let node_idx = graph.add_node(Node {
+                    id: node_def.id.clone(),
+                    name: node_def.name,
+                    subgraph: graph_def.id.clone(),
+                    kind,
+                    with: node_def.with,
+                    description: node_def.description,
+                    node_type: custom_type,
+                });
+                node_indices.insert(node_def.id, node_idx);
+                check_limit("node", graph.node_count(), options.max_nodes)?;
+            }
+            edge_definitions.extend(graph_def.edges);
+            check_limit("edge", edge_definitions.len(), options.max_edges)?;
+        }
+
+        // Edges are added once every graph is loaded, so an edge may point at a node declared later in the file.
+        for edge_def in edge_definitions {
+            let source = *node_indices.get(&edge_def.from).ok_or_else(|| {
+                Error::Input(format!(
+                    "edge {} references unknown node {}",
+                    edge_def.id, edge_def.from
+                ))
+            })?;
+            let target = *node_indices.get(&edge_def.to).ok_or_else(|| {
+                Error::Input(format!(
+                    "edge {} references unknown node {}",
+                    edge_def.id, edge_def.to
+                ))
+            })?;
+            // Leaving through one of the source's routing ports makes this a control edge.
+            let edge_kind = match graph[source].routing_ports() {
+                Some(ports) if ports.contains(&edge_def.from_port) => EdgeKind::Control,
+                _ => EdgeKind::Data,
+            };
+            graph.add_edge(
+                source,
+                target,
+                Edge {
+                    id: edge_def.id,
+                    name: edge_def.from_port.clone(),
+                    from_port: edge_def.from_port,
+                    to_port: edge_def.to_port,
+                    edge_kind,
+                    weight: edge_def.weight,
+                    with: edge_def.with,
+                },
+            );
+        }
+
+        Ok(Workflow {
+            id: definition.id,
+            name: definition.name,
+            entry_graph_ids: definition.entry_graph_ids,
+            graph,
+            graph_names,
+            node_states: HashMap::new(),
+            edit_warnings: Vec::new(),
+        })
+    }
+}
+
+/// Workflows are equal when they hold the same nodes (matched by id) wired by the same edges (matched by endpoint ids
+/// and name), regardless of the order they were inserted in.
+impl PartialEq for Workflow {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.name == other.name
+            && self.entry_graph_ids == other.entry_graph_ids
+            && self.graph_names == other.graph_names
+            && self.node_signature() == other.node_signature()
+            && self.edge_signature() == other.edge_signature()
     }
 }
+
+impl Workflow {
+    /// Nodes sorted by id, with the fields that define them.
+    fn node_signature(&self) -> Vec<NodeSignature<'_>> {
+        let mut nodes: Vec<NodeSignature<'_>> = self
+            .graph
+            .node_weights()
+            .map(|node| {
+                (
+                    node.id.as_str(),
+                    node.name.as_str(),
+                    node.subgraph.as_str(),
+                    &node.kind,
+                    &node.with,
+                )
+            })
+            .collect();
+        nodes.sort_by(|a, b| (a.0, a.1, a.2).cmp(&(b.0, b.1, b.2)));
+        nodes
+    }
+
+    /// Sorted `(from id, to id, edge name)` triples, one per edge.
+    fn edge_signature(&self) -> Vec<(&str, &str, &str)> {
+        let mut edges: Vec<(&str, &str, &str)> = self
+            .graph
+            .edge_indices()
+            .filter_map(|edge_idx| {
+                let (source, target) = self.graph.edge_endpoints(edge_idx)?;
+                Some((
+                    self.graph[source].id.as_str(),
+                    self.graph[target].id.as_str(),
+                    self.graph[edge_idx].name.as_str(),
+                ))
+            })
+            .collect();
+        edges.sort_unstable();
+        edges
+    }
+}
+
+/// `(id, name, subgraph, kind, with)` of a node.
+type NodeSignature<'a> = (
+    &'a str,
+    &'a str,
+    &'a str,
+    &'a NodeKind,
+    &'a Option<HashMap<String, serde_yaml::Value>>,
+);
+
+/// Deserializes the graph at `position` in the workflow's `graphs` list. The error names the graph by its id when it
+/// has one and by its position otherwise, so a malformed graph is reported rather than left out of the workflow.
+fn parse_graph(graph_value: serde_yaml::Value, position: usize) -> Result<GraphDefinition> {
+    let label = match graph_value.get("id").and_then(|id| id.as_str()) {
+        Some(id) => format!("graph `{}`", id),
+        None => format!("graph #{}", position),
+    };
+    serde_yaml::from_value(graph_value)
+        .map_err(|e| Error::Serde(format!("{} is malformed: {}", label, e)))
+}
+
+/// Fails once `count` items of `kind` pass `max`, if there is one.
+fn check_limit(kind: &str, count: usize, max: Option<usize>) -> Result<()> {
+    match max {
+        Some(max) if count > max => Err(Error::Input(format!(
+            "workflow has more than {} {}s, the configured maximum",
+            max, kind
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Reads the graph file `file_name` named by the manifest of a workflow directory, `depth` includes deep.
+/// Only plain file names are accepted, so graph files can't come from outside the directory.
+fn read_graph_file(
+    dir: &Path,
+    file_name: &str,
+    stack: &mut Vec<PathBuf>,
+    depth: usize,
+    options: &LoadOptions,
+) -> Result<serde_yaml::Value> {
+    if Path::new(file_name).file_name() != Some(OsStr::new(file_name)) {
+        return Err(Error::Input(format!(
+            "graph file `{}` in {} is not a file name",
+            file_name,
+            dir.display()
+        )));
+    }
+    let path = dir.join(file_name);
+    if depth >= options.max_include_depth {
+        return Err(Error::Input(format!(
+            "graph file {} exceeds the maximum include depth of {}",
+            path.display(),
+            options.max_include_depth
+        )));
+    }
+    let content = fs::read_to_string(&path).map_err(|e| {
+        Error::Input(format!(
+            "failed to read graph file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let resolved = resolve_includes(&content, dir, stack, depth + 1, options, &mut |_| {})?;
+    Ok(serde_yaml::from_str(&resolved)?)
+}
+
+/// Splices the content of every `- !include <path>` entry into `content`. Included files are resolved recursively,
+/// relative to their own directory. `stack` holds the files currently being included and is used to detect cycles;
+/// `depth` is how many includes deep `content` is, checked against `options.max_include_depth`.
+fn resolve_includes(
+    content: &str,
+    base_dir: &Path,
+    stack: &mut Vec<PathBuf>,
+    depth: usize,
+    options: &LoadOptions,
+    on_file: &mut dyn FnMut(&Path),
+) -> Result<String> {
+    let mut resolved = String::new();
+
+    for line in content.lines() {
+        let Some(captures) = INCLUDE_RE.captures(line) else {
+            resolved.push_str(line);
+            resolved.push('\n');
+            continue;
+        };
+
+        let indent = &captures[1];
+        let dash_spacing = &captures[2];
+        let include_path = base_dir.join(&captures[3]);
+        if depth >= options.max_include_depth {
+            return Err(Error::Input(format!(
+                "include {} exceeds the maximum include depth of {}",
+                include_path.display(),
+                options.max_include_depth
+            )));
+        }
+        let canonical = fs::canonicalize(&include_path).map_err(|e| {
+            Error::Input(format!(
+                "failed to read include {}: {}",
+                include_path.display(),
+                e
+            ))
+        })?;
+        if stack.contains(&canonical) {
+            return Err(Error::Input(format!(
+                "include cycle detected at {}",
+                include_path.display()
+            )));
+        }
+
+        on_file(&canonical);
+        let included = fs::read_to_string(&canonical)?;
+        parse_event!(
+            path = %canonical.display(),
+            bytes = included.len(),
+            depth = depth + 1,
+            "read include file"
+        );
+        let include_dir = canonical.parent().unwrap_or_else(|| Path::new("."));
+        stack.push(canonical.clone());
+        let included =
+            resolve_includes(&included, include_dir, stack, depth + 1, options, on_file)?;
+        stack.pop();
+        parse_event!(
+            path = %canonical.display(),
+            depth = depth + 1,
+            lines = included.lines().count(),
+            "resolved include"
+        );
+
+        // The first line takes the place of the `!include` directive, the rest line up under it. The included file's
+        // own first-line indentation is dropped so its relative indentation, whatever its width, is kept.
+        let item_indent = format!("{} {}", indent, " ".repeat(dash_spacing.chars().count()));
+        let mut lines = included.lines().skip_while(|line| line.trim().is_empty());
+        let Some(first_line) = lines.next() else {
+            continue;
+        };
+        let first_indent = &first_line[..first_line.len() - first_line.trim_start().len()];
+        resolved.push_str(&format!(
+            "{}-{}{}",
+            indent,
+            dash_spacing,
+            first_line.trim_start()
+        ));
+        resolved.push('\n');
+        for included_line in lines {
+            if !included_line.trim().is_empty() {
+                let relative = included_line
+                    .strip_prefix(first_indent)
+                    .unwrap_or_else(|| included_line.trim_start());
+                resolved.push_str(&format!("{}{}", item_indent, relative));
+            }
+            resolved.push('\n');
+        }
+    }
+
+    Ok(resolved)
+}